@@ -0,0 +1,24 @@
+//! Async functions tested on the crate's tokio runtime.
+
+use std::time::Duration;
+
+pub async fn delayed_double(x: u32, delay_ms: u64) -> u32 {
+    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+    x * 2
+}
+
+pub async fn fetch_name(id: u64) -> Result<String, String> {
+    if id == 0 {
+        Err("no user 0".to_string())
+    } else {
+        Ok(format!("user-{}", id))
+    }
+}
+
+pub async fn total(values: Vec<i64>) -> i64 {
+    values.into_iter().sum()
+}
+
+pub fn blocking_len(s: &str) -> usize {
+    s.len()
+}
@@ -0,0 +1,47 @@
+//! Generic functions and a generic container.
+
+use std::fmt::Display;
+
+pub fn largest<T: PartialOrd + Copy>(items: &[T]) -> Option<T> {
+    let mut iter = items.iter().copied();
+    let first = iter.next()?;
+    Some(iter.fold(first, |max, x| if x > max { x } else { max }))
+}
+
+pub fn describe<T>(value: T) -> String
+where
+    T: Display,
+{
+    format!("<{}>", value)
+}
+
+pub fn pair_up<A: Clone, B: Clone>(a: &A, b: &B) -> (A, B) {
+    (a.clone(), b.clone())
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Stack<T> {
+    items: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
@@ -0,0 +1,13 @@
+//! A trait with implementations, methods and trait-object parameters.
+
+pub mod shapes;
+
+pub use shapes::{Circle, Rectangle, Shape};
+
+pub fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+pub fn describe(shape: &dyn Shape) -> String {
+    format!("{} with area {:.2}", shape.name(), shape.area())
+}
@@ -0,0 +1,11 @@
+pub fn celsius_to_fahrenheit(c: f64) -> f64 {
+    c * 9.0 / 5.0 + 32.0
+}
+
+pub fn fahrenheit_to_celsius(f: f64) -> f64 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+pub fn clamp_percent(value: i32) -> i32 {
+    value.clamp(0, 100)
+}
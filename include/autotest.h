@@ -0,0 +1,31 @@
+/*
+ * C API of auto_test, built with the `ffi` feature:
+ *
+ *   cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * Strings are NUL-terminated UTF-8. Strings returned by the library are owned
+ * by the caller and must be released with autotest_string_free().
+ */
+#ifndef AUTOTEST_H
+#define AUTOTEST_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Generate tests for the project at `project_path` and return the generation
+ * report as JSON. `config_json` is a JSON object with the layout of
+ * auto_test.toml, or NULL to use the project's config file. On failure the
+ * result is {"error": {"code": "AT0201", "message": "..."}}.
+ */
+char *autotest_generate(const char *project_path, const char *config_json);
+
+/* Release a string returned by autotest_generate(). NULL is ignored. */
+void autotest_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AUTOTEST_H */
@@ -0,0 +1,432 @@
+//! # Builder API
+//!
+//! [`AutoTest`] is the library entry point: configure a run step by step and
+//! execute it with [`AutoTestBuilder::run`]. The free functions in the crate
+//! root are thin wrappers around it.
+//!
+//! ```no_run
+//! use auto_test::{AutoTest, Strategy};
+//!
+//! let run = AutoTest::builder()
+//!     .project("./my_project")
+//!     .strategy(Strategy::Property)
+//!     .dry_run(true)
+//!     .run()?;
+//! for file in &run.files {
+//!     println!("{}", file.path);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::config::Config;
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::core::generator::v_gen::VParser;
+use crate::core::models::TestFile;
+use crate::core::report::{
+    millis, FileAction, FunctionReport, GenerationReport, ManifestEntry, RunManifest,
+};
+use crate::error::AutoTestError;
+use crate::utils::fs::FsUtils;
+use crate::utils::log::status;
+use crate::utils::parallel;
+use crate::utils::progress::progress_bar;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Test generation strategy, see `generation.strategy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Integration tests in `tests/` calling the public API.
+    Integration,
+    /// Unit tests.
+    Unit,
+    /// Proptest properties for pure functions, example-based tests for the rest.
+    Property,
+    /// One test per function looping over a table of cases.
+    Table,
+}
+
+impl Strategy {
+    /// The value of `generation.strategy` for this strategy.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Integration => "integration",
+            Self::Unit => "unit",
+            Self::Property => "property",
+            Self::Table => "table",
+        }
+    }
+}
+
+/// Receives progress of a run, e.g. to show it in an IDE or collect metrics.
+///
+/// Every method has an empty default, so implementors only override what they need.
+pub trait Observer {
+    /// A function was analyzed, with its outcome.
+    fn on_function(&mut self, _function: &FunctionReport) {}
+
+    /// A test file was generated; called before it is written, or instead of
+    /// writing it in a dry run.
+    fn on_test_file(&mut self, _file: &TestFile) {}
+
+    /// The run finished successfully.
+    fn on_finish(&mut self, _report: &GenerationReport) {}
+}
+
+/// Outcome of [`AutoTestBuilder::run`].
+#[derive(Debug, Clone)]
+pub struct GenerationRun {
+    /// Per-function outcome, as written to the JSON report.
+    pub report: GenerationReport,
+    /// The generated test files (not written in a dry run).
+    pub files: Vec<TestFile>,
+}
+
+/// Entry point of the builder API, see [`AutoTest::builder`].
+pub struct AutoTest;
+
+impl AutoTest {
+    /// Start configuring a run. Defaults: the current directory, its
+    /// `auto_test.toml` (or built-in defaults), writing files.
+    pub fn builder() -> AutoTestBuilder {
+        AutoTestBuilder::default()
+    }
+}
+
+/// Configures and runs test generation.
+#[derive(Default)]
+pub struct AutoTestBuilder {
+    project: Option<PathBuf>,
+    config: Option<Config>,
+    strategy: Option<Strategy>,
+    observers: Vec<Box<dyn Observer>>,
+    dry_run: bool,
+}
+
+impl AutoTestBuilder {
+    /// Project root to generate tests for (default: the current directory).
+    pub fn project(mut self, path: impl AsRef<Path>) -> Self {
+        self.project = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Configuration to use instead of loading the project's config file.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Override `generation.strategy`.
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Add an observer notified as the run progresses.
+    pub fn observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Generate without writing anything: no test files, reports, nextest
+    /// profile or hooks.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Run generation.
+    ///
+    /// # Returns
+    ///
+    /// The report and test files of the run, or an error if generation, writing
+    /// or a post-generation hook fails
+    pub fn run(mut self) -> Result<GenerationRun, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let project_path = self.project.take().unwrap_or_else(|| PathBuf::from("."));
+        let mut config = match self.config.take() {
+            Some(config) => config,
+            None => Config::load(&project_path)?,
+        };
+        if let Some(strategy) = self.strategy {
+            config.generation.strategy = strategy.as_str().to_string();
+        }
+
+        let mut report = GenerationReport::new(&project_path);
+        let result = RustGenerator::generate_with_report(&project_path, &config, &mut report);
+        for function in &report.functions {
+            self.notify(|observer| observer.on_function(function));
+        }
+
+        if self.dry_run {
+            let files = result?;
+            for file in &files {
+                self.notify(|observer| observer.on_test_file(file));
+            }
+            report.metrics.total_ms = millis(started.elapsed());
+            report.summarize();
+            self.notify(|observer| observer.on_finish(&report));
+            return Ok(GenerationRun { report, files });
+        }
+
+        let mut manifest = RunManifest {
+            generated_at: report.generated_at.clone(),
+            project: report.project.clone(),
+            files: Vec::new(),
+        };
+        if let Ok(test_files) = &result {
+            for test_file in test_files {
+                self.notify(|observer| observer.on_test_file(test_file));
+            }
+
+            // Each file is written atomically, in parallel unless disabled; every
+            // file is attempted and the failures are reported together
+            let writing = Instant::now();
+            let progress = progress_bar(test_files.len() as u64);
+            progress.set_message(format!("Writing {} file(s)", test_files.len()));
+            let write = |test_file: &TestFile| {
+                let existed = Path::new(&test_file.path).exists();
+                let written = FsUtils::write_test_file_if_changed(test_file);
+                progress.inc(1);
+                written.map(|written| match (written, existed) {
+                    (false, _) => FileAction::Skipped,
+                    (true, true) => FileAction::Updated,
+                    (true, false) => FileAction::Written,
+                })
+            };
+            let results = if config.parallel {
+                parallel::install(config.performance.threads, || test_files.par_iter().map(write).collect())?
+            } else {
+                test_files.iter().map(write).collect()
+            };
+            let actions = FsUtils::all_written(test_files, results)?;
+            progress.finish_with_message("Writing complete");
+            report.metrics.write_ms = millis(writing.elapsed());
+
+            for (test_file, action) in test_files.iter().zip(actions) {
+                let relative = Path::new(&test_file.path)
+                    .strip_prefix(&project_path)
+                    .map_or_else(
+                        |_| test_file.path.clone(),
+                        |p| p.to_string_lossy().to_string(),
+                    );
+                if action == FileAction::Skipped {
+                    report.unchanged_files.push(relative.clone());
+                } else if progress.is_hidden() {
+                    // Without a terminal the bar isn't drawn; keep a line per file for CI logs
+                    status!("Wrote test file: {}", test_file.path);
+                }
+                let path = relative.replace('\\', "/");
+                manifest.files.push(ManifestEntry::new(path, action, test_file.content.as_bytes()));
+                report.files.push(relative);
+            }
+
+            if let Some(profile) = &config.generation.nextest_profile {
+                if let Some(filter) = crate::core::generator::nextest::write_profile(
+                    &project_path,
+                    profile,
+                    &report.files,
+                )? {
+                    status!(
+                        "Nextest profile '{}' selects the generated tests: {}",
+                        profile, filter
+                    );
+                }
+            }
+
+            report.hooks = crate::core::hooks::run_hooks(&project_path, &report.files, &config);
+        }
+
+        // Written even when generation fails, so CI can see which functions broke
+        report.metrics.total_ms = millis(started.elapsed());
+        if let Some(metrics_path) = &config.generation.metrics_path {
+            report.write_prometheus(&project_path.join(metrics_path))?;
+        }
+        if let Some(junit_path) = &config.generation.junit_report {
+            report.write_junit(&project_path.join(junit_path))?;
+        }
+        if let Some(manifest_path) = &config.generation.manifest_path {
+            manifest.write(&project_path.join(manifest_path))?;
+        }
+        if !config.generation.report_path.is_empty() {
+            let report_path = project_path.join(&config.generation.report_path);
+            report.write(&report_path)?;
+            let summary = &report.summary;
+            if summary.failed + summary.unsupported > 0 {
+                status!(
+                    "{} function(s) failed, {} unsupported; see {}",
+                    summary.failed,
+                    summary.unsupported,
+                    report_path.display()
+                );
+            }
+        }
+        // Mappings for the types blocking the most functions pay off first
+        report.summarize();
+        if !report.blocking_types.is_empty() {
+            status!("Unsupported types by number of functions blocked:");
+            for blocking in report.blocking_types.iter().take(5) {
+                status!(
+                    "  {} function(s) blocked on type `{}`",
+                    blocking.functions.len(),
+                    blocking.type_name
+                );
+            }
+        }
+        let files = result?;
+
+        let failed_hooks: Vec<&str> = report
+            .hooks
+            .iter()
+            .filter(|hook| !hook.success)
+            .map(|hook| hook.command.as_str())
+            .collect();
+        if !failed_hooks.is_empty() {
+            return Err(AutoTestError::HookFailed {
+                count: failed_hooks.len(),
+                commands: failed_hooks.join(", "),
+            }
+            .into());
+        }
+
+        Self::generate_v_tests(&project_path, &config)?;
+
+        report.summarize();
+        self.notify(|observer| observer.on_finish(&report));
+        Ok(GenerationRun { report, files })
+    }
+
+    fn notify(&mut self, mut event: impl FnMut(&mut dyn Observer)) {
+        for observer in &mut self.observers {
+            event(observer.as_mut());
+        }
+    }
+
+    /// V Language Support: write a `<name>_test.v` next to every V source file.
+    fn generate_v_tests(project_path: &Path, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        use std::fs;
+
+        for path in crate::core::analyzer::discover_files(project_path, config, "v") {
+            // Skip test files
+            if path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .map(|s| s.ends_with("_test.v"))
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)?;
+            let functions = VParser::parse_function_signatures(&content);
+
+            if !functions.is_empty() {
+                let mut test_content = String::from("module main\n\n");
+                for func in functions {
+                    test_content.push_str(&VParser::generate_test(&func));
+                    test_content.push('\n');
+                }
+
+                let file_stem = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown");
+                let test_file_name = format!("{}_test.v", file_stem);
+                let test_file_path = path.parent().unwrap().join(&test_file_name);
+
+                status!("Writing V test file: {:?}", test_file_path);
+                fs::write(test_file_path, test_content)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::create_test_project;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Observer for Recorder {
+        fn on_function(&mut self, function: &FunctionReport) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("function {}", function.name));
+        }
+
+        fn on_test_file(&mut self, file: &TestFile) {
+            let name = Path::new(&file.path)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            self.0.lock().unwrap().push(format!("file {}", name));
+        }
+
+        fn on_finish(&mut self, report: &GenerationReport) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("finish {}", report.summary.generated));
+        }
+    }
+
+    #[test]
+    fn test_dry_run_notifies_observers_and_writes_nothing() {
+        let dir = create_test_project();
+
+        let recorder = Recorder::default();
+        let run = AutoTest::builder()
+            .project(dir.path())
+            .config(Config::default())
+            .strategy(Strategy::Property)
+            .observer(recorder.clone())
+            .dry_run(true)
+            .run()
+            .unwrap();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["function add", "file integration_tests.rs", "finish 1"]
+        );
+        assert!(run.files[0].content.contains("proptest!"));
+        assert!(!dir.path().join("tests").exists());
+        assert!(!dir.path().join("target").exists());
+    }
+
+    #[test]
+    fn test_manifest_lists_written_updated_and_skipped_files() {
+        let dir = create_test_project();
+        let lib = dir.path().join("src/lib.rs");
+        let mut config = Config::default();
+        config.generation.manifest_path = Some("target/manifest.json".to_string());
+        let run = |config: &Config| {
+            AutoTest::builder()
+                .project(dir.path())
+                .config(config.clone())
+                .run()
+                .unwrap();
+            let json = std::fs::read_to_string(dir.path().join("target/manifest.json")).unwrap();
+            serde_json::from_str::<RunManifest>(&json).unwrap().files
+        };
+
+        let files = run(&config);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "tests/integration_tests.rs");
+        assert_eq!(files[0].action, FileAction::Written);
+        let written = std::fs::read(dir.path().join(&files[0].path)).unwrap();
+        assert_eq!(files[0], ManifestEntry::new(&files[0].path, FileAction::Written, &written));
+
+        assert_eq!(run(&config)[0].action, FileAction::Skipped);
+        std::fs::write(&lib, "pub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n").unwrap();
+        let files = run(&config);
+        assert_eq!(files[0].action, FileAction::Updated);
+        assert_ne!(files[0].sha256, ManifestEntry::new("", FileAction::Written, &written).sha256);
+    }
+}
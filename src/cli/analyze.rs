@@ -0,0 +1,62 @@
+use clap::Parser;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use super::generate::ConfigSource;
+use crate::core::analyzer::{add_expanded_functions, analyze_rust_project_filtered};
+
+#[derive(Parser)]
+pub struct AnalyzeArgs {
+    /// Path to the project root
+    #[arg(default_value = ".")]
+    pub path: String,
+
+    /// Write the analysis to this file (e.g. .auto_test/project.json) instead of stdout,
+    /// for `autotest generate --from-analysis`
+    #[arg(long, value_name = "PATH")]
+    pub save: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub config_source: ConfigSource,
+
+    /// Include private functions with #[cfg(test)] access
+    #[arg(long)]
+    pub include_private: bool,
+
+    /// Do not respect .gitignore patterns
+    #[arg(long)]
+    pub no_gitignore: bool,
+
+    /// Also analyze the macro-expanded crate to find functions generated by macros
+    #[arg(long)]
+    pub expand: bool,
+}
+
+pub fn handle(args: AnalyzeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let project_path = Path::new(&args.path);
+    let mut config =
+        args.config_source.load(project_path)?;
+    if args.include_private {
+        config.include_private = true;
+    }
+    if args.no_gitignore {
+        config.respect_gitignore = false;
+    }
+
+    let mut project = analyze_rust_project_filtered(project_path, &config)?;
+    if args.expand || config.generation.expand {
+        let added = add_expanded_functions(&mut project, project_path, &config)?;
+        eprintln!("Found {} macro-generated function(s) in the expansion", added);
+    }
+    match &args.save {
+        Some(path) => {
+            project.save(path)?;
+            eprintln!("Saved analysis of {} functions to {}", project.functions.len(), path.display());
+        }
+        None => {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(project.to_json()?.as_bytes())?;
+            writeln!(stdout)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use std::io::Write;
+
+#[derive(Parser)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+pub fn handle(args: CompletionsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = super::Cli::command();
+    // Complete the name the binary was installed under (`auto_test` from cargo install)
+    let name = super::program_name();
+    let mut script = Vec::new();
+    clap_complete::generate(args.shell, &mut command, name, &mut script);
+    std::io::stdout().write_all(&script)?;
+    Ok(())
+}
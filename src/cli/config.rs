@@ -0,0 +1,180 @@
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use crate::config::{Config, IssueSeverity, find_project_root};
+use crate::error::AutoTestError;
+use super::generate::ConfigSource;
+
+#[derive(Parser)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the JSON Schema for the hierarchical configuration format
+    Schema,
+
+    /// Check a config file for unknown keys, type mismatches and deprecated legacy keys
+    Validate {
+        /// Config file to check (defaults to auto_test.toml or auto_test.yaml in the project root)
+        path: Option<PathBuf>,
+    },
+
+    /// Rewrite a legacy flat auto_test.toml in the hierarchical layout, printing a diff
+    Migrate {
+        /// Config file to migrate (defaults to auto_test.toml in the project root)
+        path: Option<PathBuf>,
+
+        /// Print the diff without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Print a setting by its dotted path, e.g. `generation.output_dir`
+    Get {
+        /// Dotted path of the setting
+        key: String,
+
+        #[command(flatten)]
+        config_source: ConfigSource,
+    },
+
+    /// Change a setting in the config file, keeping its comments and layout
+    Set {
+        /// Dotted path of the setting, e.g. `generation.output_dir`
+        key: String,
+
+        /// New value, as TOML (`false`, `8`, `["a", "b"]`) or a plain string
+        value: String,
+
+        /// Config file to edit (defaults to auto_test.toml in the project root, created if missing)
+        #[arg(long)]
+        config_path: Option<PathBuf>,
+    },
+}
+
+pub fn handle(args: ConfigArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match args.command {
+        ConfigCommand::Schema => {
+            println!("{}", serde_json::to_string_pretty(&Config::json_schema())?);
+            Ok(())
+        }
+        ConfigCommand::Validate { path } => validate(path),
+        ConfigCommand::Migrate { path, dry_run } => migrate(path, dry_run),
+        ConfigCommand::Get { key, config_source } => get(&key, &config_source),
+        ConfigCommand::Set { key, value, config_path } => set(&key, &value, config_path),
+    }
+}
+
+/// The given config path, or auto_test.toml / auto_test.yaml in the project root.
+fn locate_config(path: Option<PathBuf>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if let Some(path) = path {
+        return Ok(path);
+    }
+
+    let root = find_project_root(&std::env::current_dir()?)?;
+    let path = ["auto_test.toml", "auto_test.yaml"]
+        .iter()
+        .map(|name| root.join(name))
+        .find(|candidate| candidate.exists())
+        .ok_or_else(|| AutoTestError::InvalidConfig {
+            message: format!("No auto_test.toml or auto_test.yaml found in {}", root.display()),
+        })?;
+    Ok(path)
+}
+
+fn validate(path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = locate_config(path)?;
+
+    let issues = Config::validate_file(&path)?;
+    for issue in &issues {
+        println!("{}", issue);
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == IssueSeverity::Error).count();
+    let warnings = issues.len() - errors;
+    if errors > 0 {
+        return Err(AutoTestError::InvalidConfig {
+            message: format!("{}: {} error(s), {} warning(s)", path.display(), errors, warnings),
+        }
+        .into());
+    }
+
+    println!("✅ {} is valid ({} warning(s))", path.display(), warnings);
+    Ok(())
+}
+
+fn migrate(path: Option<PathBuf>, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let path = locate_config(path)?;
+    if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+        return Err(AutoTestError::InvalidConfig {
+            message: format!("{}: only TOML configs can be migrated", path.display()),
+        }
+        .into());
+    }
+
+    let original = std::fs::read_to_string(&path)?;
+    let Some(migrated) = Config::migrate_legacy_toml(&original)? else {
+        println!("✅ {} has no legacy keys", path.display());
+        return Ok(());
+    };
+
+    let display = path.display().to_string();
+    let diff = similar::TextDiff::from_lines(&original, &migrated);
+    print!("{}", diff.unified_diff().header(&display, &display));
+
+    if !dry_run {
+        std::fs::write(&path, migrated)?;
+        println!("✅ Migrated {}", display);
+    }
+    Ok(())
+}
+
+fn get(key: &str, config_source: &ConfigSource) -> Result<(), Box<dyn std::error::Error>> {
+    let config = config_source.load(&std::env::current_dir()?)?;
+    let value = config.get_key(key).ok_or_else(|| AutoTestError::InvalidConfig {
+        message: format!("Unknown config key `{}`", key),
+    })?;
+
+    // Strings print bare so scripts can use the output directly; unset options print nothing
+    match value {
+        serde_json::Value::Null => {}
+        serde_json::Value::String(value) => println!("{}", value),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            println!("{}", serde_json::to_string_pretty(&value)?)
+        }
+        value => println!("{}", value),
+    }
+    Ok(())
+}
+
+fn set(key: &str, value: &str, config_path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = match config_path {
+        Some(path) => path,
+        None => locate_config(None).or_else(|_| -> Result<PathBuf, Box<dyn std::error::Error>> {
+            Ok(find_project_root(&std::env::current_dir()?)?.join("auto_test.toml"))
+        })?,
+    };
+    if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+        return Err(AutoTestError::InvalidConfig {
+            message: format!("{}: only TOML configs can be edited", path.display()),
+        }
+        .into());
+    }
+
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    // Only the file's own settings, so values from extends or the environment aren't written into it
+    let mut config: Config = toml::from_str(&contents).map_err(|e| AutoTestError::InvalidConfig {
+        message: format!("{}: {}", path.display(), e.message()),
+    })?;
+    config.set_key(key, value)?;
+    config.save_to_file(&path)?;
+
+    println!("✅ Set {} in {}", key, path.display());
+    Ok(())
+}
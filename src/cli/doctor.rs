@@ -0,0 +1,311 @@
+use clap::Parser;
+use std::path::Path;
+use super::generate::ConfigSource;
+use std::process::Command;
+use crate::config::{Config, IssueSeverity, find_project_root};
+use crate::core::analyzer::{analyze_rust_source, project_rust_files, CrateManifest};
+
+#[derive(Parser)]
+pub struct DoctorArgs {
+    /// Path to the project root
+    #[arg(default_value = ".")]
+    pub path: String,
+
+    #[command(flatten)]
+    pub config_source: ConfigSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one check, with the fix to print when it didn't pass.
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn print(&self) {
+        let symbol = match self.status {
+            Status::Ok => "✅",
+            Status::Warn => "⚠️ ",
+            Status::Fail => "❌",
+        };
+        println!("{} {}: {}", symbol, self.name, self.detail);
+        if let Some(fix) = &self.fix {
+            println!("   fix: {}", fix);
+        }
+    }
+}
+
+pub fn handle(args: DoctorArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = vec![
+        tool("cargo", "cargo", &["--version"], Status::Fail, "install Rust from https://rustup.rs"),
+        tool("rustfmt", "rustfmt", &["--version"], Status::Warn, "rustup component add rustfmt"),
+        tool("git", "git", &["--version"], Status::Warn,
+            "install git; `--staged`, `hook install` and `--emit-patch` need it"),
+    ];
+
+    let root = match find_project_root(Path::new(&args.path)) {
+        Ok(root) => root,
+        Err(e) => {
+            checks.push(Check::fail("project", e.to_string(), "run doctor inside a crate, or pass its path"));
+            return finish(&checks);
+        }
+    };
+
+    let config = check_config(&root, &args, &mut checks);
+    let nextest_status = if config.generation.nextest_profile.is_some() { Status::Fail } else { Status::Warn };
+    checks.push(tool("nextest", "cargo", &["nextest", "--version"], nextest_status,
+        "cargo install cargo-nextest (needed for `--nextest-profile`)"));
+
+    let manifest = match CrateManifest::load(&root) {
+        Ok(manifest) => manifest.unwrap_or_default(),
+        Err(e) => {
+            checks.push(Check::fail("manifest", e.to_string(), "fix the syntax error in Cargo.toml"));
+            CrateManifest::default()
+        }
+    };
+    let has_async = check_sources(&root, &config, &mut checks);
+
+    for (name, dir) in [
+        ("output dir", Some(config.output_dir.as_str())),
+        ("report dir", Path::new(&config.generation.report_path).parent().and_then(Path::to_str)),
+    ] {
+        let Some(dir) = dir.filter(|dir| !dir.is_empty()) else {
+            continue;
+        };
+        let path = root.join(dir);
+        checks.push(if writable(&path) {
+            Check::ok(name, format!("{} is writable", path.display()))
+        } else {
+            Check::fail(name, format!("{} is not writable", path.display()),
+                "fix the directory's permissions or point the config elsewhere")
+        });
+    }
+
+    let required = required_dev_dependencies(&config, &manifest, has_async);
+    let missing: Vec<&str> = required
+        .iter()
+        .filter(|(name, _)| !manifest.depends_on(name))
+        .map(|(name, _)| *name)
+        .collect();
+    checks.push(if missing.is_empty() {
+        let names: Vec<&str> = required.iter().map(|(name, _)| *name).collect();
+        Check::ok("dev-dependencies", if names.is_empty() {
+            "none needed".to_string()
+        } else {
+            format!("found {}", names.join(", "))
+        })
+    } else {
+        let reasons: Vec<String> = required
+            .iter()
+            .filter(|(name, _)| missing.contains(name))
+            .map(|(name, reason)| format!("{} ({})", name, reason))
+            .collect();
+        Check::fail("dev-dependencies", format!("generated tests need {}", reasons.join(", ")),
+            format!("cargo add --dev {}", missing.join(" ")))
+    });
+
+    finish(&checks)
+}
+
+/// Print the checks and fail if any of them failed.
+fn finish(checks: &[Check]) -> Result<(), Box<dyn std::error::Error>> {
+    for check in checks {
+        check.print();
+    }
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    let warnings = checks.iter().filter(|c| c.status == Status::Warn).count();
+    if failed > 0 {
+        return Err(format!("{} check(s) failed, {} warning(s)", failed, warnings).into());
+    }
+    println!("\nNo problems found ({} warning(s))", warnings);
+    Ok(())
+}
+
+/// Check that `program args` runs, reporting the first line it prints.
+fn tool(name: &'static str, program: &str, args: &[&str], missing: Status, fix: &str) -> Check {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            Check::ok(name, version.lines().next().unwrap_or_default().trim())
+        }
+        _ if missing == Status::Fail => Check::fail(name, "not found", fix),
+        _ => Check::warn(name, "not found", fix),
+    }
+}
+
+/// Validate and load the configuration, falling back to the defaults when it's broken.
+fn check_config(root: &Path, args: &DoctorArgs, checks: &mut Vec<Check>) -> Config {
+    let path = args.config_source.config_path.clone().or_else(|| {
+        ["auto_test.toml", "auto_test.yaml"]
+            .iter()
+            .map(|name| root.join(name))
+            .find(|candidate| candidate.exists())
+    });
+    let Some(path) = path else {
+        checks.push(Check::ok("config", "no auto_test.toml, using the defaults"));
+        return Config::default();
+    };
+
+    let validation = match Config::validate_file(&path) {
+        Ok(issues) if issues.iter().any(|i| i.severity == IssueSeverity::Error) => {
+            let errors: Vec<String> = issues
+                .iter()
+                .filter(|i| i.severity == IssueSeverity::Error)
+                .map(|i| i.to_string())
+                .collect();
+            Some(Check::fail("config", errors.join("; "),
+                format!("run `autotest config validate {}` and fix the errors", path.display())))
+        }
+        Ok(issues) if !issues.is_empty() => Some(Check::warn("config",
+            format!("{} warning(s) in {}", issues.len(), path.display()),
+            format!("run `autotest config validate {}`", path.display()))),
+        Ok(_) => None,
+        Err(e) => Some(Check::fail("config", e.to_string(), "fix the file's syntax")),
+    };
+
+    let loaded = args.config_source.load(root);
+    checks.push(match (validation, &loaded) {
+        (Some(check), _) if check.status == Status::Fail => check,
+        (_, Err(e)) => Check::fail("config", e.to_string(), "fix the file, or check the --profile name"),
+        (Some(check), Ok(_)) => check,
+        (None, Ok(_)) => Check::ok("config", format!("{} is valid", path.display())),
+    });
+    loaded.unwrap_or_default()
+}
+
+/// Parse the project's Rust files like `generate` does, reporting files that
+/// don't parse and whether any function is async.
+fn check_sources(root: &Path, config: &Config, checks: &mut Vec<Check>) -> bool {
+    let mut functions = Vec::new();
+    let mut broken = Vec::new();
+    let files = project_rust_files(root, config);
+    for file in &files {
+        let path = file.to_string_lossy();
+        match std::fs::read_to_string(file) {
+            Ok(source) => match analyze_rust_source(&source, &path, config) {
+                Ok(found) => functions.extend(found),
+                Err(e) => broken.push(format!("{}: {}", path, e)),
+            },
+            Err(e) => broken.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    checks.push(if !broken.is_empty() {
+        Check::fail("sources", format!("{} of {} file(s) can't be analyzed: {}",
+            broken.len(), files.len(), broken.join("; ")),
+            "fix the syntax errors, or exclude the files with `skip_patterns`")
+    } else if functions.is_empty() {
+        Check::warn("sources", format!("no functions to test in {} file(s)", files.len()),
+            "check `skip_patterns` and `skip_functions`, or set `include_private = true`")
+    } else {
+        Check::ok("sources", format!("{} function(s) in {} file(s)", functions.len(), files.len()))
+    });
+    functions.iter().any(|f| f.is_async)
+}
+
+/// Whether files can be created in `dir`, or in its closest existing ancestor
+/// when it doesn't exist yet (generation creates it).
+fn writable(dir: &Path) -> bool {
+    dir.ancestors()
+        .find(|d| d.exists())
+        .is_some_and(|d| tempfile::tempfile_in(d).is_ok())
+}
+
+/// Crates the generated tests use with this configuration, with the reason.
+fn required_dev_dependencies(
+    config: &Config,
+    manifest: &CrateManifest,
+    has_async: bool,
+) -> Vec<(&'static str, &'static str)> {
+    let generation = &config.generation;
+    let mut required = Vec::new();
+    if manifest.is_proc_macro {
+        required.push(("trybuild", "proc-macro crates are tested through trybuild"));
+    }
+    if generation.strategy == "property" {
+        required.push(("proptest", "strategy = \"property\""));
+    }
+    if generation.strategy == "table" && generation.case_style == "test-case" {
+        required.push(("test-case", "case_style = \"test-case\""));
+    }
+    let wasm = generation
+        .wasm_bindgen_test
+        .unwrap_or_else(|| manifest.is_wasm_bindgen_crate());
+    if wasm {
+        required.push(("wasm-bindgen-test", "wasm-bindgen crate"));
+    } else if generation.test_timeout_secs.is_some() {
+        required.push(("ntest", "test_timeout_secs"));
+    }
+    if has_async {
+        let runtime = generation
+            .async_runtime
+            .as_deref()
+            .unwrap_or_else(|| manifest.async_runtime());
+        let runtime = match runtime {
+            "async-std" => "async-std",
+            "futures" => "futures",
+            "pollster" => "pollster",
+            _ => "tokio",
+        };
+        required.push((runtime, "runs the async tests"));
+    }
+    required
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_dev_dependencies_follow_the_config() {
+        let mut config = Config::default();
+        let mut manifest = CrateManifest::default();
+        assert!(required_dev_dependencies(&config, &manifest, false).is_empty());
+
+        config.generation.strategy = "property".to_string();
+        config.generation.test_timeout_secs = Some(5);
+        let names = |config: &Config, manifest: &CrateManifest| -> Vec<&str> {
+            required_dev_dependencies(config, manifest, true)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        // Without a runtime dependency async tests use futures' block_on
+        assert_eq!(names(&config, &manifest), ["proptest", "ntest", "futures"]);
+
+        manifest.dependencies.insert("tokio".to_string());
+        assert_eq!(names(&config, &manifest), ["proptest", "ntest", "tokio"]);
+
+        config.generation.wasm_bindgen_test = Some(true);
+        config.generation.async_runtime = Some("pollster".to_string());
+        assert_eq!(names(&config, &manifest), ["proptest", "wasm-bindgen-test", "pollster"]);
+    }
+
+    #[test]
+    fn test_missing_output_dir_is_writable_through_its_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(writable(dir.path()));
+        assert!(writable(&dir.path().join("tests/generated")));
+    }
+}
@@ -0,0 +1,22 @@
+use clap::Parser;
+use crate::error::{explain, ERROR_CODES};
+
+#[derive(Parser)]
+pub struct ExplainArgs {
+    /// Diagnostic code, e.g. AT0102 (lists all codes when omitted)
+    pub code: Option<String>,
+}
+
+pub fn handle(args: ExplainArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(code) = args.code else {
+        for (code, title, _) in ERROR_CODES {
+            println!("{}  {}", code, title);
+        }
+        return Ok(());
+    };
+
+    let (title, text) = explain(&code)
+        .ok_or_else(|| format!("Unknown error code '{}'; run `autotest explain` to list all codes", code))?;
+    println!("{}: {}\n\n{}", code.to_uppercase(), title, text);
+    Ok(())
+}
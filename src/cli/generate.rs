@@ -1,16 +1,56 @@
-use clap::Parser;
-use std::path::PathBuf;
-use crate::config::{Config, find_project_root};
+use clap::{ArgGroup, Args, Parser, ValueEnum};
+use std::path::{Path, PathBuf};
+use crate::config::{Config, find_project_root, PROFILE_ENV};
+use crate::core::analyzer::{analyze_rust_source, invoked_functions, CrateManifest, ModuleTree};
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::core::models::TestFile;
+use crate::core::report::GenerationReport;
+use crate::utils::fs::{FsUtils, RunLock};
+use crate::utils::git;
+use crate::utils::patch;
+use std::io::{Read, Write};
+use crate::error::AutoTestError;
 
 
 #[derive(Parser)]
+#[command(group(ArgGroup::new("input").args(["stdin", "file"])))]
 pub struct GenerateArgs {
     /// Path to the project root
+    #[arg(default_value = ".")]
     pub path: String,
 
-    /// Path to custom configuration file (auto_test.toml or auto_test.yaml)
-    #[arg(long)]
-    pub config_path: Option<PathBuf>,
+    /// Read a single Rust source file from stdin instead of analyzing the project
+    #[arg(long, requires = "stdout")]
+    pub stdin: bool,
+
+    /// Generate a test for the function enclosing --line in this file only
+    #[arg(long, requires = "line", value_name = "PATH")]
+    pub file: Option<PathBuf>,
+
+    /// Line (1-based) inside the function to test, used with --file
+    #[arg(long, requires = "file")]
+    pub line: Option<usize>,
+
+    /// Only look at Rust files staged in git; write tests for public functions no test
+    /// calls yet and fail if there were any (used by `hook install`)
+    #[arg(long, conflicts_with = "input")]
+    pub staged: bool,
+
+    /// With --staged, warn about functions without tests instead of failing
+    #[arg(long, requires = "staged")]
+    pub warn_only: bool,
+
+    /// Print the generated test module to stdout instead of writing files (with --stdin or --file)
+    #[arg(long, requires = "input")]
+    pub stdout: bool,
+
+    #[command(flatten)]
+    pub config_source: ConfigSource,
+
+    /// Override any setting by its dotted path, e.g. `--set generation.strategy=property`;
+    /// repeatable, applied after the config file and environment
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
 
     /// Output directory for tests (overrides config file)
     #[arg(long)]
@@ -28,25 +68,360 @@ pub struct GenerateArgs {
     #[arg(long)]
     pub no_parallel: bool,
 
+    /// Worker threads for generation and writing (overrides `performance.threads`)
+    #[arg(long, short, value_name = "N", conflicts_with = "no_parallel")]
+    pub jobs: Option<usize>,
+
     /// Do not respect .gitignore patterns
     #[arg(long)]
     pub no_gitignore: bool,
+
+    /// Coverage report (lcov or llvm-cov JSON); only uncovered functions get tests
+    #[arg(long)]
+    pub coverage_report: Option<PathBuf>,
+
+    /// cargo-mutants outcomes.json or missed.txt; strengthens tests of functions with surviving mutants
+    #[arg(long)]
+    pub mutants_report: Option<PathBuf>,
+
+    /// Generate from an analysis saved by `autotest analyze --save` instead of
+    /// analyzing the sources again
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["input", "staged"])]
+    pub from_analysis: Option<PathBuf>,
+
+    /// Also analyze the macro-expanded crate (`cargo expand`, or `cargo rustc
+    /// -Zunpretty=expanded` on nightly) to find functions generated by macros
+    #[arg(long, conflicts_with_all = ["input", "staged", "from_analysis"])]
+    pub expand: bool,
+
+    /// Write case tables as a `cases` loop or as `#[test_case]` attributes
+    /// (implies the table strategy)
+    #[arg(long, value_name = "STYLE", value_parser = ["loop", "test-case"])]
+    pub case_style: Option<String>,
+
+    /// Only generate tests for the N most complex functions
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Also generate tests for functions already called by existing tests
+    #[arg(long)]
+    pub include_covered: bool,
+
+    /// Write a JUnit XML summary with one testcase per analyzed function
+    #[arg(long, value_name = "PATH")]
+    pub junit: Option<PathBuf>,
+
+    /// Write a JSON manifest of the test files written, updated or skipped (already
+    /// up to date), with SHA-256 checksums, for build systems declaring outputs
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["input", "staged", "emit_patch"])]
+    pub emit_manifest: Option<PathBuf>,
+
+    /// Write a `[profile.NAME]` to .config/nextest.toml that runs only the generated tests
+    #[arg(long, value_name = "NAME")]
+    pub nextest_profile: Option<String>,
+
+    /// Print a unified diff of the test files instead of writing them, for
+    /// `git apply` or a bot to attach to a pull request
+    #[arg(long, conflicts_with_all = ["input", "staged"])]
+    pub emit_patch: bool,
+
+    /// Pick modules, functions and the strategy from a list before generating
+    #[arg(long)]
+    pub interactive: bool,
+
+    /// Which problems make the command exit with status 1
+    #[arg(long, value_enum, default_value_t = FailOn::Errors)]
+    pub fail_on: FailOn,
+
+    /// Print nothing to stderr but errors: no progress, notes, warnings or summary
+    #[arg(long, short)]
+    pub quiet: bool,
+
+    /// Don't take the project's `.auto_test/lock`, e.g. on filesystems without
+    /// locking; concurrent runs may then interleave their writes
+    #[arg(long)]
+    pub no_lock: bool,
+
+    /// How to print the outcome of the run: a summary table on stderr, or the
+    /// generation report as one JSON object on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, conflicts_with_all = ["input", "staged", "emit_patch"])]
+    pub format: OutputFormat,
 }
 
+/// Output of `autotest generate` once the tests are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Summary table on stderr
+    Text,
+    /// The generation report as JSON on stdout
+    Json,
+}
 
-pub fn handle(args: GenerateArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let project_path = PathBuf::from(&args.path);
+/// Problems that fail `autotest generate` once the tests are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    /// Functions that failed to generate
+    Errors,
+    /// Errors, unsupported types and any other warning
+    Warnings,
+    /// Errors and functions using types filled with placeholder values
+    UnsupportedTypes,
+    /// Only hard errors (bad config, unreadable sources, strict mode)
+    None,
+}
 
-    // Load configuration
-    let mut config = if let Some(config_path) = &args.config_path {
-        // Load from specified config file
-        Config::load_from_file(config_path)?
-    } else {
-        // Load from project root (auto-detection)
-        let project_root = find_project_root(&project_path)
-            .map_err(|e| format!("Could not find project root: {}", e))?;
-        Config::load(&project_root)?
+impl FailOn {
+    /// Check a finished run's report against the policy.
+    fn check(self, report: &GenerationReport) -> Result<(), AutoTestError> {
+        let policy = self
+            .to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default();
+        let summary = &report.summary;
+        let unsupported = report
+            .functions
+            .iter()
+            .filter(|f| f.uses_unsupported_types())
+            .count();
+
+        let (count, problem) = match self {
+            FailOn::None => (0, ""),
+            _ if summary.failed > 0 => (summary.failed, "failed to generate"),
+            FailOn::UnsupportedTypes if unsupported > 0 => (unsupported, "use unsupported types"),
+            FailOn::Warnings if summary.warnings + summary.unsupported > 0 => {
+                (summary.warnings + summary.unsupported, "generated with warnings")
+            }
+            _ => (0, ""),
+        };
+
+        if count == 0 {
+            return Ok(());
+        }
+        Err(AutoTestError::FailOn {
+            count,
+            problem: problem.to_string(),
+            policy,
+        })
+    }
+}
+
+
+/// `--config-path` and `--profile`, for the subcommands that load the configuration.
+#[derive(Args, Debug, Clone, Default)]
+pub struct ConfigSource {
+    /// Path to custom configuration file (auto_test.toml or auto_test.yaml)
+    #[arg(long)]
+    pub config_path: Option<PathBuf>,
+
+    /// Config profile to apply, e.g. `ci` for `[profile.ci]` (defaults to $AUTO_TEST_PROFILE)
+    #[arg(long)]
+    pub profile: Option<String>,
+}
+
+impl ConfigSource {
+    /// Load the configuration from `--config-path`, or auto-detect it from the project root.
+    pub(super) fn load(&self, project_path: &Path) -> Result<Config, AutoTestError> {
+        let profile = selected_profile(self.profile.as_deref());
+        if let Some(config_path) = &self.config_path {
+            // Load from specified config file
+            Config::load_from_file_with_profile(config_path, profile.as_deref())
+        } else {
+            // Load from project root (auto-detection)
+            let project_root = find_project_root(project_path)?;
+            Config::load_with_profile(&project_root, profile.as_deref())
+        }
+    }
+}
+
+/// The config profile to apply: `--profile`, else a non-empty `AUTO_TEST_PROFILE`.
+fn selected_profile(profile: Option<&str>) -> Option<String> {
+    profile
+        .map(str::to_string)
+        .or_else(|| std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty()))
+}
+
+/// Apply `--set KEY=VALUE` overrides, in order, on top of the loaded configuration.
+pub(super) fn apply_overrides(config: &mut Config, overrides: &[String]) -> Result<(), AutoTestError> {
+    for assignment in overrides {
+        let (key, value) = assignment.split_once('=').ok_or_else(|| AutoTestError::InvalidConfig {
+            message: format!("--set {}: expected KEY=VALUE, e.g. generation.output_dir=tests", assignment),
+        })?;
+        config.set_key(key.trim(), value.trim()).map_err(|e| match e {
+            AutoTestError::InvalidConfig { message } => AutoTestError::InvalidConfig {
+                message: format!("--set {}: {}", assignment, message),
+            },
+            e => e,
+        })?;
+    }
+    Ok(())
+}
+
+/// `--stdin --stdout`: generate tests for one source file without touching the disk.
+/// The project's config and crate name are used when run inside a project.
+fn generate_stdin(project_path: &Path, args: &GenerateArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let project_root = find_project_root(project_path).ok();
+    let profile = selected_profile(args.config_source.profile.as_deref());
+    let mut config = match (&args.config_source.config_path, &project_root) {
+        (Some(config_path), _) => Config::load_from_file_with_profile(config_path, profile.as_deref())?,
+        (None, Some(root)) => Config::load_with_profile(root, profile.as_deref())?,
+        (None, None) => Config::default(),
     };
+    apply_overrides(&mut config, &args.overrides)?;
+    if config.generation.crate_name.is_none() {
+        config.generation.crate_name = project_root
+            .and_then(|root| CrateManifest::load(&root).ok().flatten())
+            .and_then(|manifest| manifest.name);
+    }
+    if args.include_private {
+        config.include_private = true;
+    }
+    config.skip_functions.extend(args.skip_prefixes.iter().cloned());
+
+    let mut source = String::new();
+    std::io::stdin().read_to_string(&mut source)?;
+    let tests = RustGenerator::generate_for_source(&source, &config)?;
+    std::io::stdout().write_all(tests.as_bytes())?;
+    Ok(())
+}
+
+/// `--file --line`: print the test for the function at the cursor, or add it to
+/// the module's test file.
+fn generate_at_line(
+    project_path: &Path,
+    file: &Path,
+    line: usize,
+    stdout: bool,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cursor = RustGenerator::generate_at_line(project_path, file, line, config)?;
+    if stdout {
+        std::io::stdout().write_all(cursor.file.content.as_bytes())?;
+        return Ok(());
+    }
+
+    let path = PathBuf::from(&cursor.file.path);
+    let existing = path.exists().then(|| std::fs::read_to_string(&path)).transpose()?;
+    match cursor.merge_into(existing.as_deref()) {
+        Some(content) => {
+            FsUtils::write_test_file_atomic(&TestFile {
+                path: cursor.file.path.clone(),
+                content,
+            })?;
+            eprintln!("Wrote {} to {}", cursor.test_name, path.display());
+        }
+        None => eprintln!("{} already has {}", path.display(), cursor.test_name),
+    }
+    Ok(())
+}
+
+/// `--emit-patch`: print the generated test files as a patch against the working
+/// tree. Paths are relative to the git repository root (or the current directory
+/// outside a repository), so `git apply` works from the root.
+fn emit_patch(project_path: &Path, config: &Config) -> Result<GenerationReport, Box<dyn std::error::Error>> {
+    let mut report = GenerationReport::new(project_path);
+    let files = RustGenerator::generate_with_report(project_path, config, &mut report)?;
+    report.summarize();
+
+    // Compare canonical paths, as git reports the repository root with symlinks resolved
+    let project_root = project_path.canonicalize()?;
+    let files: Vec<TestFile> = files
+        .into_iter()
+        .map(|file| {
+            let path = Path::new(&file.path);
+            let path = path
+                .strip_prefix(project_path)
+                .map_or_else(|_| path.to_path_buf(), |relative| project_root.join(relative));
+            TestFile {
+                path: path.to_string_lossy().to_string(),
+                content: file.content,
+            }
+        })
+        .collect();
+    let base = match git::repo_root(&project_root) {
+        Ok(root) => root,
+        Err(_) => std::env::current_dir()?.canonicalize()?,
+    };
+
+    let patch = patch::build_patch(&files, &base)?;
+    if patch.is_empty() {
+        eprintln!("Generated tests are up to date");
+    }
+    std::io::stdout().write_all(patch.as_bytes())?;
+    Ok(report)
+}
+
+/// `--staged`: add tests for public functions in staged files that no test calls
+/// yet, failing unless `warn_only` so a pre-commit hook stops the commit.
+fn generate_staged(
+    project_path: &Path,
+    config: &Config,
+    warn_only: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = project_path.canonicalize()?;
+    let src = root.join("src");
+    let staged: Vec<PathBuf> = git::staged_files(&root)?
+        .into_iter()
+        .filter(|f| f.starts_with(&src) && f.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    if staged.is_empty() {
+        return Ok(());
+    }
+
+    // Any test counts here, generated ones included
+    let invoked = invoked_functions(&root, &std::collections::HashSet::new());
+    let mut count = 0;
+    let mut written = std::collections::BTreeSet::new();
+    let modules = ModuleTree::build(&root);
+    for file in &staged {
+        let source = git::staged_contents(file)?;
+        let file_str = file.to_string_lossy();
+        let module_path = modules
+            .module_path(file)
+            .map(String::from)
+            .unwrap_or_else(|| RustGenerator::module_path_in_project(&file_str, &root));
+        let functions = analyze_rust_source(&source, &file_str, config)?;
+
+        for func in functions.iter().filter(|f| {
+            !invoked.calls(f, &module_path) && !config.should_skip_function_in_module(&f.name, &module_path)
+        }) {
+            let cursor = RustGenerator::generate_at_line_in_source(&root, file, &source, func.line, config)?;
+            let path = PathBuf::from(&cursor.file.path);
+            let existing = path.exists().then(|| std::fs::read_to_string(&path)).transpose()?;
+            let Some(content) = cursor.merge_into(existing.as_deref()) else {
+                continue;
+            };
+            FsUtils::write_test_file_atomic(&TestFile {
+                path: cursor.file.path.clone(),
+                content,
+            })?;
+            eprintln!("{}: `{}` has no test, added {}", file_str, func.name, cursor.test_name);
+            count += 1;
+            written.insert(path.strip_prefix(&root).unwrap_or(&path).to_string_lossy().to_string());
+        }
+    }
+
+    if count == 0 {
+        return Ok(());
+    }
+    let error = AutoTestError::MissingTests {
+        count,
+        files: written.into_iter().collect::<Vec<_>>().join(", "),
+    };
+    if warn_only {
+        eprintln!("Warning: {}", error);
+        return Ok(());
+    }
+    Err(error.into())
+}
+
+pub fn handle(args: GenerateArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let project_path = PathBuf::from(&args.path);
+    crate::utils::log::set_quiet(args.quiet);
+    if args.stdin {
+        return generate_stdin(&project_path, &args);
+    }
+    let mut config = args.config_source.load(&project_path)?;
+    apply_overrides(&mut config, &args.overrides)?;
 
     // Override config with CLI arguments
     if let Some(output_dir) = args.output_dir {
@@ -65,10 +440,173 @@ pub fn handle(args: GenerateArgs) -> Result<(), Box<dyn std::error::Error>> {
         config.parallel = false;
     }
 
+    if let Some(jobs) = args.jobs {
+        config.performance.threads = Some(jobs);
+    }
+
     if args.no_gitignore {
         config.respect_gitignore = false;
     }
 
+    if let Some(report) = args.coverage_report {
+        config.generation.coverage_report = Some(report.to_string_lossy().to_string());
+    }
+
+    if let Some(report) = args.mutants_report {
+        config.generation.mutants_report = Some(report.to_string_lossy().to_string());
+    }
+
+    if let Some(analysis) = args.from_analysis {
+        config.generation.from_analysis = Some(analysis.to_string_lossy().to_string());
+    }
+
+    if args.expand {
+        config.generation.expand = true;
+    }
+
+    if let Some(case_style) = args.case_style {
+        config.generation.strategy = "table".to_string();
+        config.generation.case_style = case_style;
+    }
+
+    if let Some(top) = args.top {
+        config.generation.max_tests = Some(top);
+    }
+
+    if args.include_covered {
+        config.generation.include_covered = true;
+    }
+
+    if let Some(junit) = args.junit {
+        config.generation.junit_report = Some(junit.to_string_lossy().to_string());
+    }
+
+    if let Some(manifest) = args.emit_manifest {
+        config.generation.manifest_path = Some(manifest.to_string_lossy().to_string());
+    }
+
+    if let Some(profile) = args.nextest_profile {
+        config.generation.nextest_profile = Some(profile);
+    }
+
+    // Held until the run returns; runs that only print don't write to the project
+    let writes = !args.stdout && !args.emit_patch;
+    let _lock = if writes && !args.no_lock {
+        Some(RunLock::acquire(&find_project_root(&project_path)?)?)
+    } else {
+        None
+    };
+
+    if args.staged {
+        let warn_only = args.warn_only || config.generation.staged_warn_only;
+        return generate_staged(&project_path, &config, warn_only);
+    }
+
+    if let (Some(file), Some(line)) = (&args.file, args.line) {
+        return generate_at_line(&project_path, file, line, args.stdout, &config);
+    }
+
+    if args.interactive {
+        super::interactive::select(&project_path, &mut config)?;
+    }
+
+    if args.emit_patch {
+        let report = emit_patch(&project_path, &config)?;
+        args.fail_on.check(&report)?;
+        return Ok(());
+    }
+
     // Generate tests with configuration
-    crate::generate_tests_for_project_with_config(&project_path, &config)
+    let report = crate::generate_tests_for_project_with_report(&project_path, &config)?;
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Text if !args.quiet => eprint!("{}", report.summary_table()),
+        OutputFormat::Text => {}
+    }
+    args.fail_on.check(&report)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::FunctionInfo;
+    use crate::core::report::{FunctionStatus, PLACEHOLDER_WARNING};
+    use crate::utils::test_support::create_test_project;
+
+    #[test]
+    fn test_fail_on_policies() {
+        let mut report = GenerationReport::new(Path::new("."));
+        let func = FunctionInfo::default();
+        report.record(&func, "", FunctionStatus::Generated, None).warnings =
+            vec![format!("{} Socket", PLACEHOLDER_WARNING)];
+        report.summarize();
+
+        assert!(FailOn::Errors.check(&report).is_ok());
+        assert!(FailOn::UnsupportedTypes.check(&report).is_err());
+        assert!(FailOn::Warnings.check(&report).is_err());
+
+        report.record(&func, "", FunctionStatus::Failed, Some("boom".into()));
+        report.summarize();
+        let error = FailOn::Errors.check(&report).unwrap_err();
+        assert_eq!(error.to_string(), "1 function(s) failed to generate (--fail-on errors)");
+        assert!(FailOn::None.check(&report).is_ok());
+    }
+
+    #[test]
+    fn test_json_format_is_for_whole_project_runs() {
+        let args = GenerateArgs::try_parse_from(["generate", ".", "-q", "--format", "json"]).unwrap();
+        assert!(args.quiet);
+        assert_eq!(args.format, OutputFormat::Json);
+
+        // The patch or the generated test module already owns stdout
+        assert!(GenerateArgs::try_parse_from(["generate", ".", "--format", "json", "--emit-patch"]).is_err());
+        assert!(GenerateArgs::try_parse_from(["generate", ".", "--format", "json", "--stdin", "--stdout"]).is_err());
+    }
+
+    #[test]
+    fn test_set_overrides_any_setting_in_order() {
+        let args = GenerateArgs::try_parse_from([
+            "generate",
+            ".",
+            "--set",
+            "generation.strategy=property",
+            "--set",
+            "performance.parallel=false",
+            "--set",
+            "generation.output_dir = tests2",
+            "--set",
+            "generation.output_dir=tests3",
+        ])
+        .unwrap();
+        let mut config = Config::default();
+        apply_overrides(&mut config, &args.overrides).unwrap();
+        assert_eq!(config.generation.strategy, "property");
+        assert!(!config.parallel);
+        assert_eq!(config.output_dir, "tests3");
+
+        let error = apply_overrides(&mut config, &["performance.parallel".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("--set performance.parallel: expected KEY=VALUE"), "{}", error);
+        let error = apply_overrides(&mut config, &["generation.stratgy=table".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("did you mean `strategy`?"), "{}", error);
+    }
+
+    #[test]
+    fn test_concurrent_runs_are_refused_unless_unlocked() {
+        let dir = create_test_project();
+        let project = dir.path().to_string_lossy().to_string();
+        let args = |extra: &[&str]| {
+            GenerateArgs::try_parse_from(["generate", project.as_str(), "-q"].iter().chain(extra)).unwrap()
+        };
+
+        let held = RunLock::acquire(dir.path()).unwrap();
+        let error = handle(args(&[])).unwrap_err();
+        assert!(matches!(error.downcast_ref(), Some(AutoTestError::AlreadyRunning { .. })), "{}", error);
+        assert!(!dir.path().join("tests").exists());
+
+        handle(args(&["--no-lock"])).unwrap();
+        drop(held);
+        handle(args(&[])).unwrap();
+        assert!(RunLock::acquire(dir.path()).is_ok());
+    }
 }
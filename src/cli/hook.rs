@@ -0,0 +1,112 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use crate::config::find_project_root;
+use crate::error::AutoTestError;
+use crate::utils::git;
+
+/// First line after the shebang, identifying hooks this command may overwrite.
+const HOOK_MARKER: &str = "# Installed by autotest hook install";
+
+#[derive(Parser)]
+pub struct HookArgs {
+    #[command(subcommand)]
+    pub command: HookCommand,
+}
+
+#[derive(Subcommand)]
+pub enum HookCommand {
+    /// Write a git hook running `generate --staged` for this project
+    Install {
+        /// Project root (defaults to the current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Git hook to install
+        #[arg(long, value_enum, default_value_t = HookKind::PreCommit)]
+        hook: HookKind,
+
+        /// Only warn about staged functions without tests instead of failing
+        #[arg(long)]
+        warn_only: bool,
+
+        /// Replace an existing hook that wasn't installed by autotest
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    PreCommit,
+    PrePush,
+}
+
+impl HookKind {
+    fn file_name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+}
+
+pub fn handle(args: HookArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match args.command {
+        HookCommand::Install {
+            path,
+            hook,
+            warn_only,
+            force,
+        } => install(path, hook, warn_only, force),
+    }
+}
+
+fn install(
+    path: PathBuf,
+    hook: HookKind,
+    warn_only: bool,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let project_root = find_project_root(&path)?.canonicalize()?;
+    let repo_root = git::repo_root(&project_root)?.canonicalize()?;
+    let hook_path = git::hooks_dir(&project_root)?.join(hook.file_name());
+
+    if let Ok(existing) = std::fs::read_to_string(&hook_path) {
+        if !existing.contains(HOOK_MARKER) && !force {
+            return Err(AutoTestError::InvalidConfig {
+                message: format!(
+                    "{} already exists; pass --force to replace it",
+                    hook_path.display()
+                ),
+            }
+            .into());
+        }
+    }
+
+    // Hooks run from the repository root
+    let project = project_root
+        .strip_prefix(&repo_root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| project_root.to_string_lossy().to_string());
+    let project = if project.is_empty() { ".".to_string() } else { project };
+    let script = format!(
+        "#!/bin/sh\n{}; delete this file to disable.\nexec {} generate --staged{} '{}'\n",
+        HOOK_MARKER,
+        super::program_name(),
+        if warn_only { " --warn-only" } else { "" },
+        project.replace('\'', "'\\''")
+    );
+
+    if let Some(parent) = hook_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&hook_path, script)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    println!("✅ Installed {}", hook_path.display());
+    Ok(())
+}
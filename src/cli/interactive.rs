@@ -0,0 +1,113 @@
+use crate::config::Config;
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::core::models::FunctionInfo;
+use crate::error::AutoTestError;
+use dialoguer::console::{user_attended_stderr, Term};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{MultiSelect, Select};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const STRATEGIES: &[&str] = &["integration", "unit", "property", "table"];
+
+/// Let the user pick modules, functions and a strategy, and restrict `config`
+/// to that selection.
+///
+/// # Arguments
+///
+/// * `project_path` - Path to the project root
+/// * `config` - Configuration to update with the selection
+pub fn select(project_path: &Path, config: &mut Config) -> Result<(), Box<dyn std::error::Error>> {
+    if !user_attended_stderr() {
+        return Err(AutoTestError::InvalidConfig {
+            message: "--interactive needs a terminal".to_string(),
+        }
+        .into());
+    }
+
+    let project = crate::core::analyzer::analyze_rust_project_filtered(project_path, config)?;
+    let mut by_module: BTreeMap<String, Vec<&FunctionInfo>> = BTreeMap::new();
+    for func in &project.functions {
+        let module_path = RustGenerator::module_path_of(func, project_path);
+        if !config.should_skip_function_in_module(&func.name, &module_path) {
+            by_module.entry(module_path).or_default().push(func);
+        }
+    }
+    if by_module.is_empty() {
+        return Err("No functions found to select from".into());
+    }
+
+    let theme = ColorfulTheme::default();
+    let term = Term::stderr();
+    let cancelled = || -> Box<dyn std::error::Error> { "Selection cancelled".into() };
+
+    // With several modules, narrow down by module first
+    let modules: Vec<&String> = by_module.keys().collect();
+    let chosen_modules: Vec<&String> = if modules.len() > 1 {
+        let labels: Vec<String> = modules
+            .iter()
+            .map(|m| format!("{} ({} functions)", module_label(m), by_module[*m].len()))
+            .collect();
+        MultiSelect::with_theme(&theme)
+            .with_prompt("Modules (space toggles, enter confirms)")
+            .items(&labels)
+            .defaults(&vec![true; labels.len()])
+            .interact_on_opt(&term)?
+            .ok_or_else(cancelled)?
+            .into_iter()
+            .map(|i| modules[i])
+            .collect()
+    } else {
+        modules
+    };
+
+    let functions: Vec<(&String, &FunctionInfo)> = chosen_modules
+        .into_iter()
+        .flat_map(|m| by_module[m].iter().map(move |f| (m, *f)))
+        .collect();
+    let labels: Vec<String> = functions
+        .iter()
+        .map(|(m, f)| format!("{}: {} (line {})", module_label(m), f.name, f.line))
+        .collect();
+    let chosen = MultiSelect::with_theme(&theme)
+        .with_prompt("Functions to generate tests for")
+        .items(&labels)
+        .defaults(&vec![true; labels.len()])
+        .max_length(20)
+        .interact_on_opt(&term)?
+        .ok_or_else(cancelled)?;
+
+    let strategy = Select::with_theme(&theme)
+        .with_prompt("Strategy")
+        .items(STRATEGIES)
+        .default(
+            STRATEGIES
+                .iter()
+                .position(|s| *s == config.generation.strategy)
+                .unwrap_or(0),
+        )
+        .interact_on_opt(&term)?
+        .ok_or_else(cancelled)?;
+
+    config.generation.strategy = STRATEGIES[strategy].to_string();
+    config.selected_functions = Some(
+        chosen
+            .into_iter()
+            .map(|i| {
+                let func = functions[i].1;
+                let file = Path::new(&func.file);
+                let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+                (file, func.name.clone())
+            })
+            .collect::<BTreeSet<(PathBuf, String)>>(),
+    );
+    Ok(())
+}
+
+fn module_label(module_path: &str) -> &str {
+    if module_path.is_empty() {
+        "crate"
+    } else {
+        module_path
+    }
+}
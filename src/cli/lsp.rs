@@ -0,0 +1,207 @@
+use clap::Parser;
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::sync::Mutex;
+use tower_lsp::jsonrpc::Result as RpcResult;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{Client, LanguageServer, LspService, Server};
+use crate::config::{find_project_root, Config};
+use crate::core::generator::rust_gen::RustGenerator;
+
+#[derive(Parser)]
+pub struct LspArgs {}
+
+pub fn handle(_args: LspArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let (service, socket) = LspService::new(|client| Backend {
+            client,
+            documents: Mutex::new(HashMap::new()),
+        });
+        Server::new(tokio::io::stdin(), tokio::io::stdout(), socket)
+            .serve(service)
+            .await;
+    });
+    Ok(())
+}
+
+/// Language server offering a "Generate test" code action on functions.
+struct Backend {
+    client: Client,
+    /// Contents of open documents, so unsaved edits are analyzed
+    documents: Mutex<HashMap<Url, String>>,
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> RpcResult<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                ..ServerCapabilities::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "autotest".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client
+            .log_message(MessageType::INFO, "autotest language server ready")
+            .await;
+    }
+
+    async fn shutdown(&self) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.documents
+            .lock()
+            .await
+            .insert(params.text_document.uri, params.text_document.text);
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // Full sync: the last change holds the whole document
+        if let Some(change) = params.content_changes.into_iter().last() {
+            self.documents
+                .lock()
+                .await
+                .insert(params.text_document.uri, change.text);
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.documents.lock().await.remove(&params.text_document.uri);
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> RpcResult<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let Some(source) = self.documents.lock().await.get(&uri).cloned() else {
+            return Ok(None);
+        };
+        let Ok(path) = uri.to_file_path() else {
+            return Ok(None);
+        };
+        let line = params.range.start.line as usize + 1;
+
+        let action = generate_test_action(&path, &source, line).map_err(|e| e.to_string());
+        match action {
+            Ok(action) => Ok(action.map(|a| vec![CodeActionOrCommand::CodeAction(a)])),
+            Err(message) => {
+                self.client
+                    .log_message(MessageType::WARNING, format!("autotest: {}", message))
+                    .await;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// "Generate test" action for the function enclosing `line` (1-based) of a
+/// buffer, inserting the test into the module's test file.
+///
+/// # Returns
+///
+/// `None` when no function encloses the line or the test file already has the test
+fn generate_test_action(
+    path: &Path,
+    source: &str,
+    line: usize,
+) -> Result<Option<CodeAction>, Box<dyn std::error::Error>> {
+    let root = find_project_root(path.parent().unwrap_or(path))?;
+    let config = Config::load(&root)?;
+    let Ok(cursor) = RustGenerator::generate_at_line_in_source(&root, path, source, line, &config)
+    else {
+        return Ok(None);
+    };
+
+    let test_path = Path::new(&cursor.file.path);
+    let test_path = if test_path.is_absolute() {
+        test_path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(test_path)
+    };
+    let test_uri = Url::from_file_path(&test_path).map_err(|_| "invalid test file path")?;
+    let existing = test_path
+        .exists()
+        .then(|| std::fs::read_to_string(&test_path))
+        .transpose()?;
+    let Some(content) = cursor.merge_into(existing.as_deref()) else {
+        return Ok(None);
+    };
+
+    let edit = TextDocumentEdit {
+        text_document: OptionalVersionedTextDocumentIdentifier {
+            uri: test_uri.clone(),
+            version: None,
+        },
+        edits: vec![OneOf::Left(TextEdit {
+            range: Range::new(
+                Position::new(0, 0),
+                end_position(existing.as_deref().unwrap_or_default()),
+            ),
+            new_text: content,
+        })],
+    };
+    let mut operations = Vec::new();
+    if existing.is_none() {
+        operations.push(DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+            uri: test_uri,
+            options: None,
+            annotation_id: None,
+        })));
+    }
+    operations.push(DocumentChangeOperation::Edit(edit));
+
+    Ok(Some(CodeAction {
+        title: format!("Generate test for `{}`", cursor.function.name),
+        kind: Some(CodeActionKind::new("refactor.generate.test")),
+        edit: Some(WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            ..WorkspaceEdit::default()
+        }),
+        ..CodeAction::default()
+    }))
+}
+
+/// Position just past the last character of `text`, in UTF-16 code units.
+fn end_position(text: &str) -> Position {
+    let line = text.matches('\n').count();
+    let last = text.rsplit('\n').next().unwrap_or_default();
+    Position::new(line as u32, last.encode_utf16().count() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_test_action_creates_test_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"rp\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        let file = dir.path().join("src/lib.rs");
+        let source = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+
+        let action = generate_test_action(&file, source, 2).unwrap().unwrap();
+        assert_eq!(action.title, "Generate test for `add`");
+        let Some(DocumentChanges::Operations(ops)) = action.edit.unwrap().document_changes else {
+            panic!("expected document operations");
+        };
+        assert!(matches!(ops[0], DocumentChangeOperation::Op(ResourceOp::Create(_))));
+        assert!(generate_test_action(&file, source, 4).unwrap().is_none());
+
+        assert_eq!(end_position("a\nbé"), Position::new(1, 2));
+    }
+}
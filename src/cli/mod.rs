@@ -3,7 +3,19 @@ use tracing_subscriber;
 
 use clap::{Parser, Subcommand};
 
+mod analyze;
+mod completions;
+mod config;
+mod doctor;
+mod explain;
 mod generate;
+mod hook;
+mod interactive;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod report;
+mod run;
+mod stats;
 
 #[derive(Parser)]
 #[command(name = "autotest")]
@@ -12,22 +24,62 @@ mod generate;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Disable colored output (also disabled by setting NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Generate tests for a project
-    Generate(generate::GenerateArgs),
+    Generate(Box<generate::GenerateArgs>),
+
+    /// Analyze a project once and save the result for `generate --from-analysis`
+    Analyze(analyze::AnalyzeArgs),
+
+    /// Generate tests, then build and run them, reporting pass/fail per test
+    Run(run::RunArgs),
+
+    /// Inspect and validate configuration files
+    Config(config::ConfigArgs),
+
+    /// Show the extended explanation for a diagnostic code such as AT0102
+    Explain(explain::ExplainArgs),
+
+    /// Check the toolchain, configuration and project for problems, with fixes
+    Doctor(doctor::DoctorArgs),
+
+    /// Show per module how many functions get tests, are skipped or use unsupported types
+    Stats(stats::StatsArgs),
+
+    /// Render the report of the last `generate` run, e.g. as HTML for a CI artifact
+    Report(report::ReportArgs),
+
+    /// Run a language server offering "Generate test" code actions (feature `lsp`)
+    #[cfg(feature = "lsp")]
+    Lsp(lsp::LspArgs),
+
+    /// Install git hooks that generate tests for staged files
+    Hook(hook::HookArgs),
+
+    /// Print a shell completion script, e.g. `autotest completions bash > /etc/bash_completion.d/autotest`
+    Completions(completions::CompletionsArgs),
 }
 
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize structured logging
+    // Initialize structured logging, on stderr so stdout stays machine-readable
     tracing_subscriber::fmt()
         .json()
+        .with_writer(std::io::stderr)
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
     let cli: Cli = Cli::try_parse()?;
+    if cli.no_color {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
 
     info!(
         command = "cli_start",
@@ -36,7 +88,18 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     let result = match cli.command {
-        Commands::Generate(args) => generate::handle(args),
+        Commands::Generate(args) => generate::handle(*args),
+        Commands::Analyze(args) => analyze::handle(args),
+        Commands::Run(args) => run::handle(args),
+        Commands::Config(args) => config::handle(args),
+        Commands::Explain(args) => explain::handle(args),
+        Commands::Doctor(args) => doctor::handle(args),
+        Commands::Stats(args) => stats::handle(args),
+        Commands::Report(args) => report::handle(args),
+        Commands::Completions(args) => completions::handle(args),
+        Commands::Hook(args) => hook::handle(args),
+        #[cfg(feature = "lsp")]
+        Commands::Lsp(args) => lsp::handle(args),
     };
 
     match &result {
@@ -46,3 +109,57 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     result
 }
+
+/// Name the binary was invoked as (`auto_test` when installed with cargo).
+pub(crate) fn program_name() -> String {
+    std::env::args_os()
+        .next()
+        .and_then(|arg0| {
+            std::path::Path::new(&arg0)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "autotest".to_string())
+}
+
+/// Exit statuses of the `autotest` binary.
+pub mod exit_code {
+    /// Tests were generated without problems rejected by `--fail-on`.
+    pub const OK: u8 = 0;
+    /// Generation errors, or problems rejected by `--fail-on`.
+    pub const GENERATION: u8 = 1;
+    /// Invalid configuration or command-line input (also clap's usage errors).
+    pub const CONFIG: u8 = 2;
+    /// Generated tests failed to build or pass when verified.
+    pub const VERIFICATION: u8 = 3;
+}
+
+/// Exit status for an error returned by [`run`], see [`exit_code`].
+pub fn exit_code(error: &(dyn std::error::Error + 'static)) -> u8 {
+    match error.downcast_ref::<crate::error::AutoTestError>() {
+        Some(crate::error::AutoTestError::VerificationFailed { .. }) => exit_code::VERIFICATION,
+        Some(e) if e.is_config_error() => exit_code::CONFIG,
+        _ => exit_code::GENERATION,
+    }
+}
+
+/// Print an error the way rustc does: `error[AT0102]: ...` with its causes and a
+/// pointer to `autotest explain` when the error has a diagnostic code.
+pub fn report_error(error: &(dyn std::error::Error + 'static)) {
+    let code = error
+        .downcast_ref::<crate::error::AutoTestError>()
+        .map(crate::error::AutoTestError::code);
+
+    match code {
+        Some(code) => eprintln!("error[{}]: {}", code, error),
+        None => eprintln!("error: {}", error),
+    }
+    let mut source = error.source();
+    while let Some(cause) = source {
+        eprintln!("  caused by: {}", cause);
+        source = cause.source();
+    }
+    if let Some(code) = code {
+        eprintln!("For more information about this error, try `autotest explain {}`.", code);
+    }
+}
@@ -0,0 +1,86 @@
+use clap::{Parser, ValueEnum};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use super::generate::ConfigSource;
+use crate::config::find_project_root;
+use crate::core::report::GenerationReport;
+
+#[derive(Parser)]
+pub struct ReportArgs {
+    /// Path to the project root
+    #[arg(default_value = ".")]
+    pub path: String,
+
+    /// Report written by `autotest generate` (defaults to `generation.report_path`)
+    #[arg(long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Html)]
+    pub format: ReportFormat,
+
+    /// Write the report to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub out: Option<PathBuf>,
+
+    /// Base URL for links to source lines, e.g. https://github.com/org/repo/blob/main/
+    /// (defaults to file:// links into the project)
+    #[arg(long, value_name = "URL")]
+    pub source_url: Option<String>,
+
+    #[command(flatten)]
+    pub config_source: ConfigSource,
+}
+
+/// Formats `autotest report` renders the generation report in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// A single self-contained HTML page
+    Html,
+    /// The JSON report as written by `generate`
+    Json,
+}
+
+pub fn handle(args: ReportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let project_path = Path::new(&args.path);
+    let input = match args.input {
+        Some(input) => input,
+        None => {
+            let config =
+                args.config_source.load(project_path)?;
+            if config.generation.report_path.is_empty() {
+                return Err("generation.report_path is empty, so `generate` writes no report; pass --input".into());
+            }
+            find_project_root(project_path)?.join(&config.generation.report_path)
+        }
+    };
+    if !input.exists() {
+        return Err(format!("No report at {}; run `autotest generate` first", input.display()).into());
+    }
+    let mut report = GenerationReport::load(&input)?;
+
+    let source_url = match args.source_url {
+        Some(url) => url,
+        None => {
+            let root = find_project_root(Path::new(&report.project))
+                .or_else(|_| find_project_root(project_path))?;
+            format!("file://{}/", root.display())
+        }
+    };
+    match (args.format, &args.out) {
+        (ReportFormat::Html, Some(out)) => report.write_html(out, Some(&source_url))?,
+        (ReportFormat::Json, Some(out)) => report.write(out)?,
+        (ReportFormat::Html, None) => {
+            report.summarize();
+            std::io::stdout().write_all(report.to_html(Some(&source_url)).as_bytes())?;
+        }
+        (ReportFormat::Json, None) => {
+            report.summarize();
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+    if let Some(out) = &args.out {
+        eprintln!("Wrote {}", out.display());
+    }
+    Ok(())
+}
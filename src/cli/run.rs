@@ -0,0 +1,159 @@
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use super::generate::ConfigSource;
+use crate::config::find_project_root;
+use crate::core::runner::{self, TestStatus, TestTarget};
+use crate::error::AutoTestError;
+use crate::utils::fs::{FsUtils, RunLock};
+
+#[derive(Parser)]
+pub struct RunArgs {
+    /// Path to the project root
+    pub path: String,
+
+    #[command(flatten)]
+    pub config_source: ConfigSource,
+
+    /// Override any setting by its dotted path, e.g. `--set generation.strategy=property` (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
+    /// Write tests to the configured output directory instead of a throwaway copy of the project
+    #[arg(long)]
+    pub in_place: bool,
+
+    /// Don't take the project's `.auto_test/lock` when writing in place; concurrent
+    /// runs may then interleave their writes
+    #[arg(long)]
+    pub no_lock: bool,
+
+    /// Use `cargo test` even when cargo-nextest is installed
+    #[arg(long)]
+    pub no_nextest: bool,
+}
+
+pub fn handle(args: RunArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let project_path = PathBuf::from(&args.path);
+    let mut config = args.config_source.load(&project_path)?;
+    super::generate::apply_overrides(&mut config, &args.overrides)?;
+
+    // Held until the run returns; only an in-place run writes to the project
+    let _lock = if args.in_place && !args.no_lock {
+        Some(RunLock::acquire(&find_project_root(&project_path)?)?)
+    } else {
+        None
+    };
+
+    // The throwaway copy builds into the project's target directory so
+    // dependencies are only compiled once across runs
+    let scratch;
+    let (work_dir, target_dir) = if args.in_place {
+        (project_path.clone(), None)
+    } else {
+        scratch = tempfile::tempdir()?;
+        eprintln!("Copying project to {}", scratch.path().display());
+        FsUtils::copy_project(&project_path, scratch.path())?;
+        let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| project_path.join("target").join("autotest-run"));
+        (scratch.path().to_path_buf(), Some(std::path::absolute(target_dir)?))
+    };
+
+    let report = crate::generate_tests_for_project_with_report(&work_dir, &config)?;
+    let mut targets: Vec<TestTarget> = report
+        .files
+        .iter()
+        .filter_map(|file| TestTarget::for_file(&work_dir.join(file)))
+        .collect();
+    targets.dedup();
+    if targets.is_empty() {
+        eprintln!("No generated test targets to run");
+        return Ok(());
+    }
+
+    let nextest = !args.no_nextest && runner::nextest_available();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut ignored = 0;
+    let mut broken = Vec::new();
+    for target in &targets {
+        eprintln!(
+            "Running {} with {}",
+            target.name,
+            if nextest { "cargo nextest" } else { "cargo test" }
+        );
+        let run = runner::run_target(target, nextest, target_dir.as_deref())?;
+        if run.build_failed() {
+            eprintln!("{}", run.output.trim_end());
+            broken.push(display_target(&work_dir, target));
+            continue;
+        }
+        for outcome in &run.outcomes {
+            let label = match outcome.status {
+                TestStatus::Passed => {
+                    passed += 1;
+                    "ok"
+                }
+                TestStatus::Failed => {
+                    failed += 1;
+                    "FAILED"
+                }
+                TestStatus::Ignored => {
+                    ignored += 1;
+                    "ignored"
+                }
+            };
+            println!("{:<8} {}::{}", label, outcome.target, outcome.name);
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} ignored, {} target(s) failed to build",
+        passed,
+        failed,
+        ignored,
+        broken.len()
+    );
+
+    if failed > 0 || !broken.is_empty() {
+        let mut problems = Vec::new();
+        if failed > 0 {
+            problems.push(format!("{} of {} test(s) failed", failed, passed + failed));
+        }
+        if !broken.is_empty() {
+            problems.push(format!("{} did not compile", broken.join(", ")));
+        }
+        return Err(AutoTestError::VerificationFailed {
+            message: problems.join("; "),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Target as a path relative to the project, e.g. `tests/integration_tests.rs`.
+fn display_target(work_dir: &Path, target: &TestTarget) -> String {
+    let file = target.crate_dir.join("tests").join(format!("{}.rs", target.name));
+    file.strip_prefix(work_dir)
+        .unwrap_or(&file)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::create_test_project;
+
+    #[test]
+    fn test_in_place_run_is_refused_while_the_project_is_locked() {
+        let dir = create_test_project();
+        let project = dir.path().to_string_lossy().to_string();
+
+        let _held = RunLock::acquire(dir.path()).unwrap();
+        let args = RunArgs::try_parse_from(["run", project.as_str(), "--in-place"]).unwrap();
+        let error = handle(args).unwrap_err();
+        assert!(matches!(error.downcast_ref(), Some(AutoTestError::AlreadyRunning { .. })), "{}", error);
+        assert!(!dir.path().join("tests").exists());
+    }
+}
@@ -0,0 +1,131 @@
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use super::generate::ConfigSource;
+use crate::builder::AutoTest;
+use crate::core::report::{ModuleStats, RunMetrics};
+
+#[derive(Parser)]
+pub struct StatsArgs {
+    /// Path to the project root
+    #[arg(default_value = ".")]
+    pub path: String,
+
+    #[command(flatten)]
+    pub config_source: ConfigSource,
+
+    /// Include private functions with #[cfg(test)] access
+    #[arg(long)]
+    pub include_private: bool,
+
+    /// Do not respect .gitignore patterns
+    #[arg(long)]
+    pub no_gitignore: bool,
+
+    /// Also write the metrics as a Prometheus textfile, e.g. for node_exporter's
+    /// textfile collector
+    #[arg(long, value_name = "PATH")]
+    pub prometheus: Option<PathBuf>,
+}
+
+/// Print, per module, how many functions would get tests and why the others
+/// wouldn't, from a dry run of `generate`, followed by the run's metrics.
+pub fn handle(args: StatsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let project_path = Path::new(&args.path);
+    let mut config =
+        args.config_source.load(project_path)?;
+    if args.include_private {
+        config.include_private = true;
+    }
+    if args.no_gitignore {
+        config.respect_gitignore = false;
+    }
+
+    // Only the table goes out; the generator's progress and notes would drown it
+    crate::utils::log::set_quiet(true);
+    let mut run = AutoTest::builder()
+        .project(project_path)
+        .config(config)
+        .dry_run(true)
+        .run()?;
+
+    print!("{}", render_table(&run.report.module_stats()));
+    print!("\n{}", render_metrics(&run.report.metrics));
+    if let Some(path) = &args.prometheus {
+        run.report.write_prometheus(path)?;
+        eprintln!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Analysis size and phase durations, one line each.
+fn render_metrics(metrics: &RunMetrics) -> String {
+    format!(
+        "Analysis: {} function(s), {} parameter(s), ~{} MB estimated
+Types: {} reference(s) to {} distinct type(s), {} interned
+Time: {} ms analysis, {} ms generation, {} ms total
+",
+        metrics.functions, metrics.params, metrics.estimated_memory_mb,
+        metrics.type_refs, metrics.distinct_types, metrics.interned_types,
+        metrics.analysis_ms, metrics.generation_ms, metrics.total_ms
+    )
+}
+
+/// Module table with a total row; the crate root is shown as `(crate root)`.
+fn render_table(modules: &[ModuleStats]) -> String {
+    let total = modules.iter().fold(
+        ModuleStats { module: "Total".to_string(), ..ModuleStats::default() },
+        |mut total, m| {
+            total.functions += m.functions;
+            total.generated += m.generated;
+            total.skipped += m.skipped;
+            total.unsupported_types += m.unsupported_types;
+            total.failed += m.failed;
+            total
+        },
+    );
+    let name = |m: &ModuleStats| {
+        if m.module.is_empty() { "(crate root)".to_string() } else { m.module.clone() }
+    };
+    let width = modules.iter().map(|m| name(m).len()).chain([name(&total).len(), "Module".len()]).max().unwrap_or(0);
+
+    let mut table = format!(
+        "{:<width$}  {:>9}  {:>9}  {:>7}  {:>17}  {:>6}\n",
+        "Module", "Functions", "Generated", "Skipped", "Unsupported types", "Failed",
+        width = width
+    );
+    for m in modules.iter().chain([&total]) {
+        table.push_str(&format!(
+            "{:<width$}  {:>9}  {:>9}  {:>7}  {:>17}  {:>6}\n",
+            name(m), m.functions, m.generated, m.skipped, m.unsupported_types, m.failed,
+            width = width
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_has_a_row_per_module_and_a_total() {
+        let modules = [
+            ModuleStats { module: String::new(), functions: 2, generated: 2, ..ModuleStats::default() },
+            ModuleStats {
+                module: "storage::db".to_string(),
+                functions: 4,
+                generated: 1,
+                skipped: 1,
+                unsupported_types: 2,
+                failed: 0,
+            },
+        ];
+        let table = render_table(&modules);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Module        Functions  Generated  Skipped  Unsupported types  Failed");
+        assert_eq!(lines[1], "(crate root)          2          2        0                  0       0");
+        assert_eq!(lines[2], "storage::db           4          1        1                  2       0");
+        assert_eq!(lines[3], "Total                 6          3        1                  2       0");
+    }
+}
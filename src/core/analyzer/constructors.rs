@@ -0,0 +1,312 @@
+//! # Constructor Discovery
+//!
+//! Finds the associated functions that build each type defined in a project
+//! (`impl Foo { pub fn new(..) -> Self }`), so types the generator can't
+//! synthesize can get a ready-made type mapping suggestion. The public fields
+//! of each struct are kept too, for field-level assertions on returned values.
+
+use crate::core::analyzer::rust_analyzer::param_type;
+use crate::core::models::{ParamInfo, TypeIntern};
+use quote::ToTokens;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// A public associated function returning `Self` (or the type by name).
+#[derive(Debug, Clone)]
+pub struct Constructor {
+    /// Name of the constructed type, without generics.
+    pub type_name: String,
+    /// Name of the associated function, e.g. `new`.
+    pub name: String,
+    /// Parameters of the associated function.
+    pub params: Vec<ParamInfo>,
+}
+
+/// Types defined in a project together with their constructors.
+#[derive(Debug, Clone, Default)]
+pub struct ConstructorIndex {
+    constructors: HashMap<String, Vec<Constructor>>,
+    /// Traits derived or implemented by each type, by trait name.
+    traits: HashMap<String, HashSet<String>>,
+    types: HashSet<String>,
+    /// Public fields by struct name; empty when the name is defined twice.
+    fields: HashMap<String, Vec<ParamInfo>>,
+}
+
+impl ConstructorIndex {
+    /// Index every `.rs` file under `src/` of a project; unparsable files are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_root` - Path to the project root
+    pub fn scan(project_root: &Path) -> Self {
+        let mut index = Self::default();
+        let files = WalkDir::new(project_root.join("src"))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"));
+
+        for entry in files {
+            if let Some(ast) = std::fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|content| syn::parse_file(&content).ok())
+            {
+                index.add_items(&ast.items);
+            }
+        }
+        index
+    }
+
+    /// Index the items of a single parsed file.
+    pub fn add_items(&mut self, items: &[syn::Item]) {
+        for item in items {
+            match item {
+                syn::Item::Struct(s) => {
+                    self.add_type(&s.ident.to_string(), &s.attrs);
+                    self.add_fields(s);
+                }
+                syn::Item::Enum(e) => self.add_type(&e.ident.to_string(), &e.attrs),
+                syn::Item::Impl(imp) => self.add_impl(imp),
+                syn::Item::Mod(module) => {
+                    if let Some((_, items)) = &module.content {
+                        self.add_items(items);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn add_type(&mut self, name: &str, attrs: &[syn::Attribute]) {
+        self.types.insert(name.to_string());
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+            let derives = attr.meta.to_token_stream().to_string();
+            for word in derives.split(|c: char| !c.is_alphanumeric() && c != '_') {
+                if !word.is_empty() && word != "derive" {
+                    self.add_trait(name, word);
+                }
+            }
+        }
+    }
+
+    fn add_trait(&mut self, type_name: &str, trait_name: &str) {
+        self.traits
+            .entry(type_name.to_string())
+            .or_default()
+            .insert(trait_name.to_string());
+    }
+
+    fn add_fields(&mut self, s: &syn::ItemStruct) {
+        let fields = s
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| matches!(field.vis, syn::Visibility::Public(_)))
+            .map(|(i, field)| ParamInfo {
+                name: field
+                    .ident
+                    .as_ref()
+                    .map_or_else(|| i.to_string(), |ident| ident.to_string()),
+                typ: TypeIntern::new(&field.ty.to_token_stream().to_string()),
+            })
+            .collect();
+        // Same-named structs in different modules can't be told apart by name
+        self.fields
+            .entry(s.ident.to_string())
+            .and_modify(|known| known.clear())
+            .or_insert(fields);
+    }
+
+    fn add_impl(&mut self, imp: &syn::ItemImpl) {
+        let Some(type_name) = type_base_name(&imp.self_ty) else {
+            return;
+        };
+
+        if let Some((_, trait_path, _)) = &imp.trait_ {
+            if let Some(last) = trait_path.segments.last() {
+                self.add_trait(&type_name, &last.ident.to_string());
+            }
+            return;
+        }
+
+        for item in &imp.items {
+            let syn::ImplItem::Fn(method) = item else {
+                continue;
+            };
+            if !matches!(method.vis, syn::Visibility::Public(_))
+                || method.sig.receiver().is_some()
+                || !returns_type(&method.sig.output, &type_name)
+            {
+                continue;
+            }
+
+            let params = method
+                .sig
+                .inputs
+                .iter()
+                .filter_map(|input| match input {
+                    syn::FnArg::Typed(pat_type) => Some(ParamInfo {
+                        name: pat_type.pat.to_token_stream().to_string(),
+                        typ: TypeIntern::new(&param_type(&pat_type.ty)),
+                    }),
+                    syn::FnArg::Receiver(_) => None,
+                })
+                .collect();
+
+            self.constructors
+                .entry(type_name.clone())
+                .or_default()
+                .push(Constructor {
+                    type_name: type_name.clone(),
+                    name: method.sig.ident.to_string(),
+                    params,
+                });
+        }
+    }
+
+    /// Whether the type is a struct or enum defined in the project.
+    pub fn defines(&self, type_name: &str) -> bool {
+        self.types.contains(type_name)
+    }
+
+    /// Whether the type derives or implements `Default`.
+    pub fn implements_default(&self, type_name: &str) -> bool {
+        self.implements(type_name, "Default")
+    }
+
+    /// A project type implementing the trait and `Default`, to stand in for a
+    /// `dyn Trait` value; the first by name when several do.
+    pub fn implementor_of(&self, trait_name: &str) -> Option<&str> {
+        self.traits
+            .iter()
+            .filter(|(_, traits)| traits.contains(trait_name) && traits.contains("Default"))
+            .map(|(type_name, _)| type_name.as_str())
+            .min()
+    }
+
+    /// Whether the type derives or implements `PartialEq` and `Debug`, as
+    /// `assert_eq!` on its values requires.
+    pub fn implements_eq(&self, type_name: &str) -> bool {
+        self.implements(type_name, "PartialEq") && self.implements(type_name, "Debug")
+    }
+
+    fn implements(&self, type_name: &str, trait_name: &str) -> bool {
+        self.traits
+            .get(type_name)
+            .is_some_and(|traits| traits.contains(trait_name))
+    }
+
+    /// Public fields of a struct defined once in the project, in declaration
+    /// order; tuple struct fields are named by index.
+    pub fn fields_of(&self, type_name: &str) -> &[ParamInfo] {
+        self.fields.get(type_name).map_or(&[], Vec::as_slice)
+    }
+
+    /// The most convenient constructor: `new` if there is one, otherwise the
+    /// one with the fewest parameters.
+    pub fn best_for(&self, type_name: &str) -> Option<&Constructor> {
+        self.constructors
+            .get(type_name)?
+            .iter()
+            .min_by_key(|c| (c.name != "new", c.params.len(), c.name.clone()))
+    }
+}
+
+/// Last path segment of a type without generics, e.g. `Foo` for `crate::a::Foo<T>`.
+fn type_base_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        syn::Type::Reference(r) => type_base_name(&r.elem),
+        _ => None,
+    }
+}
+
+/// `-> Self`, `-> Foo`, or either wrapped in `Result`/`Option`.
+fn returns_type(output: &syn::ReturnType, type_name: &str) -> bool {
+    let syn::ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    let syn::Type::Path(path) = &**ty else {
+        return false;
+    };
+    let Some(last) = path.path.segments.last() else {
+        return false;
+    };
+
+    if last.ident == "Self" || last.ident == type_name {
+        return true;
+    }
+    if last.ident == "Result" || last.ident == "Option" {
+        if let syn::PathArguments::AngleBracketed(args) = &last.arguments {
+            if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                return type_base_name(inner).is_some_and(|n| n == "Self" || n == type_name);
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indexes_constructors_and_default_impls() {
+        let ast: syn::File = syn::parse_str(
+            r#"
+            #[derive(Debug, Default)]
+            pub struct Config { name: String }
+
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct Token(pub String);
+
+            pub struct Client { url: String }
+
+            pub struct Span { pub start: usize, len: usize, pub label: Option<String> }
+            pub struct Id(pub u64, String);
+
+            impl Client {
+                pub fn connect(url: &str, retries: u32) -> Result<Self, Error> { todo!() }
+                pub fn new(url: String) -> Client { todo!() }
+                pub fn url(&self) -> &str { &self.url }
+                fn secret() -> Self { todo!() }
+            }
+
+            mod inner {
+                pub enum Mode { A }
+                impl Default for Mode { fn default() -> Self { Mode::A } }
+                impl Mode { pub fn fast() -> Self { Mode::A } }
+            }
+            "#,
+        )
+        .unwrap();
+        let mut index = ConstructorIndex::default();
+        index.add_items(&ast.items);
+
+        let client = index.best_for("Client").unwrap();
+        assert_eq!(client.name, "new");
+        assert_eq!(client.params[0].typ.as_str(), "String");
+        assert_eq!(index.constructors["Client"].len(), 2);
+
+        assert_eq!(index.best_for("Mode").unwrap().name, "fast");
+        assert!(index.best_for("Config").is_none());
+        assert!(index.implements_default("Config"));
+        assert!(index.implements_default("Mode"));
+        assert!(!index.implements_default("Client"));
+        assert!(!index.implements_eq("Config"));
+        assert!(index.defines("Mode"));
+        assert!(!index.defines("Error"));
+
+        let span: Vec<(&str, &str)> = index
+            .fields_of("Span")
+            .iter()
+            .map(|f| (f.name.as_str(), f.typ.as_str()))
+            .collect();
+        assert_eq!(span, [("start", "usize"), ("label", "Option < String >")]);
+        assert_eq!(index.fields_of("Id")[0].name, "0");
+        assert!(index.fields_of("Client").is_empty());
+        assert!(index.fields_of("Unknown").is_empty());
+        assert!(index.implements_eq("Token"));
+    }
+}
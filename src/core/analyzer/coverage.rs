@@ -0,0 +1,228 @@
+//! # Coverage Reports
+//!
+//! Reads line coverage from an lcov tracefile or a `cargo llvm-cov --json`
+//! export so generation can be limited to functions no test executes yet.
+
+use crate::core::models::FunctionInfo;
+use crate::error::{AutoTestError, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Per-file line execution counts.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    /// Source path as written in the report -> line number -> execution count.
+    files: BTreeMap<String, BTreeMap<usize, u64>>,
+}
+
+impl CoverageReport {
+    /// Load a coverage report, detecting the format from its content.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to an lcov tracefile or an llvm-cov JSON export
+    ///
+    /// # Returns
+    ///
+    /// The parsed report, or an error if the file can't be read or parsed
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| AutoTestError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let parsed = if contents.trim_start().starts_with('{') {
+            Self::parse_llvm_cov_json(&contents)
+        } else {
+            Self::parse_lcov(&contents)
+        };
+
+        parsed.map_err(|message| AutoTestError::Coverage {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// Parse an lcov tracefile (`SF:`, `DA:` and `end_of_record` lines).
+    pub fn parse_lcov(contents: &str) -> std::result::Result<Self, String> {
+        let mut report = Self::default();
+        let mut current: Option<String> = None;
+
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if let Some(file) = line.strip_prefix("SF:") {
+                current = Some(file.to_string());
+                report.files.entry(file.to_string()).or_default();
+            } else if let Some(data) = line.strip_prefix("DA:") {
+                let file = current
+                    .as_ref()
+                    .ok_or_else(|| format!("line {}: DA record before SF", index + 1))?;
+                let mut parts = data.split(',');
+                let line_no = parts.next().and_then(|n| n.parse::<usize>().ok());
+                let hits = parts.next().and_then(|n| n.parse::<u64>().ok());
+                match (line_no, hits) {
+                    (Some(line_no), Some(hits)) => {
+                        let lines = report.files.entry(file.clone()).or_default();
+                        let entry = lines.entry(line_no).or_default();
+                        *entry = (*entry).max(hits);
+                    }
+                    _ => return Err(format!("line {}: malformed DA record", index + 1)),
+                }
+            } else if line == "end_of_record" {
+                current = None;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Parse a `cargo llvm-cov --json` export using its function regions.
+    pub fn parse_llvm_cov_json(contents: &str) -> std::result::Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        let data = value
+            .get("data")
+            .and_then(|d| d.as_array())
+            .ok_or("missing 'data' array")?;
+
+        let mut report = Self::default();
+        for export in data {
+            let functions = export
+                .get("functions")
+                .and_then(|f| f.as_array())
+                .into_iter()
+                .flatten();
+
+            for function in functions {
+                let filenames: Vec<&str> = function
+                    .get("filenames")
+                    .and_then(|f| f.as_array())
+                    .map(|f| f.iter().filter_map(|n| n.as_str()).collect())
+                    .unwrap_or_default();
+                let regions = function
+                    .get("regions")
+                    .and_then(|r| r.as_array())
+                    .into_iter()
+                    .flatten();
+
+                // Region layout: [line_start, col_start, line_end, col_end, count, file_id, ...]
+                for region in regions.filter_map(|r| r.as_array()) {
+                    let field = |i: usize| region.get(i).and_then(|v| v.as_u64());
+                    let (Some(start), Some(end), Some(count), Some(file_id)) =
+                        (field(0), field(2), field(4), field(5))
+                    else {
+                        continue;
+                    };
+                    let Some(file) = filenames.get(file_id as usize) else {
+                        continue;
+                    };
+
+                    let lines = report.files.entry(file.to_string()).or_default();
+                    for line in start..=end {
+                        let entry = lines.entry(line as usize).or_default();
+                        *entry = (*entry).max(count);
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Line counts for a source file, from the report path sharing the most
+    /// trailing components with it (the first in path order on a tie).
+    fn lines_for(&self, file: &str) -> Option<&BTreeMap<usize, u64>> {
+        self.files
+            .iter()
+            .filter(|(path, _)| same_source_file(path, file))
+            .min_by_key(|(path, _)| std::cmp::Reverse(shared_suffix_len(path, file)))
+            .map(|(_, lines)| lines)
+    }
+
+    /// Whether no line inside the function's span was executed.
+    ///
+    /// Functions in files missing from the report count as uncovered.
+    pub fn is_uncovered(&self, func: &FunctionInfo) -> bool {
+        match self.lines_for(&func.file) {
+            None => true,
+            Some(lines) => lines
+                .range(func.line..=func.end_line.max(func.line))
+                .all(|(_, hits)| *hits == 0),
+        }
+    }
+}
+
+/// Whether two paths name the same source file, matching by path suffix so
+/// absolute report paths line up with project-relative analysis paths.
+pub(super) fn same_source_file(a: &str, b: &str) -> bool {
+    let (a, b) = (components(a), components(b));
+    let shortest = a.len().min(b.len());
+    shortest > 0 && a[a.len() - shortest..] == b[b.len() - shortest..]
+}
+
+/// Number of trailing path components two paths have in common.
+fn shared_suffix_len(a: &str, b: &str) -> usize {
+    let (a, b) = (components(a), components(b));
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+fn components(path: &str) -> Vec<String> {
+    Path::new(path)
+        .components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func(file: &str, line: usize, end_line: usize) -> FunctionInfo {
+        FunctionInfo {
+            name: "f".to_string(),
+            file: file.to_string(),
+            line,
+            end_line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_lcov_zero_coverage_detection() {
+        let lcov = "SF:/work/project/src/lib.rs\nDA:2,1\nDA:3,1\nDA:10,0\nDA:11,0\nend_of_record\n";
+        let report = CoverageReport::parse_lcov(lcov).unwrap();
+
+        assert!(!report.is_uncovered(&func("./src/lib.rs", 1, 4)));
+        assert!(report.is_uncovered(&func("./src/lib.rs", 9, 12)));
+        assert!(report.is_uncovered(&func("./src/other.rs", 1, 4)));
+    }
+
+    #[test]
+    fn test_llvm_cov_json_regions() {
+        let json = r#"{"data":[{"functions":[
+            {"name":"a","count":3,"filenames":["src/lib.rs"],"regions":[[1,1,4,2,3,0,0,0]]},
+            {"name":"b","count":0,"filenames":["src/lib.rs"],"regions":[[6,1,8,2,0,0,0,0]]}
+        ]}]}"#;
+        let report = CoverageReport::parse_llvm_cov_json(json).unwrap();
+
+        assert!(!report.is_uncovered(&func("src/lib.rs", 1, 4)));
+        assert!(report.is_uncovered(&func("src/lib.rs", 6, 8)));
+    }
+
+    #[test]
+    fn test_file_matching_prefers_the_longest_suffix() {
+        let lcov = "SF:/work/src/lib.rs\nDA:2,0\nend_of_record\n\
+                    SF:/work/crates/util/src/lib.rs\nDA:2,1\nend_of_record\n\
+                    SF:/other/crates/util/src/lib.rs\nDA:2,0\nend_of_record\n";
+        let report = CoverageReport::parse_lcov(lcov).unwrap();
+
+        assert!(!report.is_uncovered(&func("/work/crates/util/src/lib.rs", 1, 3)));
+        assert!(report.is_uncovered(&func("/work/src/lib.rs", 1, 3)));
+        // Equally long matches resolve to the first path in order
+        assert!(report.is_uncovered(&func("crates/util/src/lib.rs", 1, 3)));
+    }
+}
@@ -0,0 +1,518 @@
+//! # Existing Tests
+//!
+//! Finds the functions a project's hand-written tests already call, so
+//! regeneration can leave well-tested code alone.
+
+use super::{CrateManifest, ModuleTree};
+use crate::core::generator::unit;
+use crate::core::models::FunctionInfo;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+use walkdir::WalkDir;
+
+/// Functions called from a project's existing tests, by the paths they're
+/// called with.
+#[derive(Debug, Default)]
+pub struct InvokedFunctions {
+    /// Free functions by their path from the crate root, e.g. `["codec", "parse"]`.
+    paths: HashSet<Vec<String>>,
+    /// Methods and associated functions by type and name, e.g. `("Store", "get")`.
+    associated: HashSet<(String, String)>,
+}
+
+impl InvokedFunctions {
+    /// Whether a test calls `func`, defined in `module_path`: a free function
+    /// through its module path or its public path, a method through its type.
+    pub fn calls(&self, func: &FunctionInfo, module_path: &str) -> bool {
+        if let Some(self_type) = &func.self_type {
+            return self
+                .associated
+                .contains(&(type_name(self_type), func.name.clone()));
+        }
+        let mut defined = split_path(module_path);
+        defined.push(func.name.clone());
+        self.paths.contains(&defined)
+            || func
+                .public_path
+                .as_deref()
+                .is_some_and(|path| self.paths.contains(&split_path(path)))
+    }
+}
+
+/// Functions invoked from the project's existing tests.
+///
+/// Looks at every `.rs` file under `tests/` and at `#[cfg(test)]` modules and
+/// `#[test]` functions under `src/`, except the unit-test modules auto_test
+/// generates for private functions (see `generator::unit`). Calls are resolved
+/// through the test's `use` declarations: `my_crate::codec::parse(..)` or a
+/// `parse(..)` imported from there only counts for `codec::parse`, and a method
+/// only counts when its receiver's type is known from the test, e.g. from
+/// `let store = Store::new();`. Calls into other crates, like `Vec::new()`,
+/// never match.
+///
+/// # Arguments
+///
+/// * `project_root` - Path to the project root
+/// * `exclude` - Files or directories to ignore, e.g. tests previously written by auto_test
+pub fn invoked_functions(project_root: &Path, exclude: &HashSet<PathBuf>) -> InvokedFunctions {
+    let crate_name = CrateManifest::load(project_root)
+        .ok()
+        .flatten()
+        .and_then(|manifest| manifest.name)
+        .map(|name| name.replace('-', "_"));
+    let modules = ModuleTree::build(project_root);
+    let mut collector = CallCollector {
+        crate_name,
+        ..CallCollector::default()
+    };
+
+    for (dir, only_test_code) in [("tests", false), ("src", true)] {
+        let files = WalkDir::new(project_root.join(dir))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "rs"))
+            .filter(|p| !exclude.iter().any(|e| p.starts_with(e)));
+
+        for path in files {
+            let Some(ast) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| syn::parse_file(&content).ok())
+            else {
+                continue;
+            };
+
+            collector.in_crate = only_test_code;
+            collector.module = if only_test_code {
+                modules.module_path(&path).map(split_path)
+            } else {
+                Some(Vec::new())
+            };
+            collector.imports.clear();
+            collector.globs.clear();
+            collector.collect_uses(&ast.items);
+            if only_test_code {
+                for item in &ast.items {
+                    collect_test_items(item, &mut collector);
+                }
+            } else {
+                collector.visit_file(&ast);
+            }
+        }
+    }
+
+    collector.invoked
+}
+
+/// Visit `#[cfg(test)]` modules and `#[test]` functions within `item`.
+fn collect_test_items(item: &syn::Item, collector: &mut CallCollector) {
+    match item {
+        syn::Item::Mod(module) if module.ident == unit::MODULE_NAME => {}
+        syn::Item::Mod(module) if has_attr(&module.attrs, is_cfg_test) => {
+            collector.visit_item_mod(module)
+        }
+        syn::Item::Mod(module) => {
+            let items = module.content.as_ref().map_or(&[][..], |(_, items)| items);
+            collector.in_module(&module.ident, items, |collector| {
+                for item in items {
+                    collect_test_items(item, collector);
+                }
+            });
+        }
+        syn::Item::Fn(func) if has_attr(&func.attrs, |a| a.path().is_ident("test")) => {
+            collector.visit_item_fn(func)
+        }
+        _ => {}
+    }
+}
+
+fn has_attr(attrs: &[syn::Attribute], pred: impl Fn(&syn::Attribute) -> bool) -> bool {
+    attrs.iter().any(pred)
+}
+
+fn is_cfg_test(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident("cfg")
+        && attr
+            .parse_args::<syn::Ident>()
+            .is_ok_and(|ident| ident == "test")
+}
+
+/// `a::b` as `["a", "b"]`; the crate root is empty.
+fn split_path(path: &str) -> Vec<String> {
+    path.split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Name of a type as written, without references and generic arguments:
+/// `Store` for `& mut store :: Store < T >`.
+fn type_name(type_str: &str) -> String {
+    let compact: String = type_str.chars().filter(|c| !c.is_whitespace()).collect();
+    let path = compact.trim_start_matches('&').trim_start_matches("mut");
+    let path = path.split('<').next().unwrap_or(path);
+    path.rsplit("::").next().unwrap_or(path).to_string()
+}
+
+/// Whether a path segment names a type rather than a module, by Rust's naming
+/// convention.
+fn is_type_name(segment: &str) -> bool {
+    segment.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+#[derive(Default)]
+struct CallCollector {
+    /// Library name of the crate, as tests in `tests/` refer to it.
+    crate_name: Option<String>,
+    /// Whether the visited code is inside the crate (`src/`) rather than in `tests/`.
+    in_crate: bool,
+    /// Module of the visited code from the crate root; `None` when the file
+    /// isn't reachable from a crate root.
+    module: Option<Vec<String>>,
+    /// Names brought into scope by `use`, with their paths from the crate root.
+    imports: HashMap<String, Vec<String>>,
+    /// Modules of the crate glob-imported into scope.
+    globs: Vec<Vec<String>>,
+    /// Types of the visited function's local bindings, where known.
+    locals: HashMap<String, String>,
+    invoked: InvokedFunctions,
+}
+
+impl CallCollector {
+    /// Run `visit` inside module `name` holding `items`, with that module's imports.
+    fn in_module(&mut self, name: &syn::Ident, items: &[syn::Item], visit: impl FnOnce(&mut Self)) {
+        let saved = (self.module.clone(), self.imports.clone(), self.globs.clone());
+        if let Some(module) = &mut self.module {
+            module.push(name.to_string());
+        }
+        self.collect_uses(items);
+        visit(self);
+        (self.module, self.imports, self.globs) = saved;
+    }
+
+    /// Record the `use` declarations among `items`.
+    fn collect_uses(&mut self, items: &[syn::Item]) {
+        for item in items {
+            if let syn::Item::Use(item) = item {
+                self.add_use(&item.tree, Vec::new());
+            }
+        }
+    }
+
+    fn add_use(&mut self, tree: &syn::UseTree, mut prefix: Vec<String>) {
+        match tree {
+            syn::UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.add_use(&path.tree, prefix);
+            }
+            syn::UseTree::Name(name) => {
+                let bound = if name.ident == "self" {
+                    prefix.last().cloned()
+                } else {
+                    prefix.push(name.ident.to_string());
+                    Some(name.ident.to_string())
+                };
+                if let (Some(bound), Some(path)) = (bound, self.resolve_prefix(&prefix)) {
+                    self.imports.insert(bound, path);
+                }
+            }
+            syn::UseTree::Rename(rename) => {
+                prefix.push(rename.ident.to_string());
+                if let Some(path) = self.resolve_prefix(&prefix) {
+                    self.imports.insert(rename.rename.to_string(), path);
+                }
+            }
+            syn::UseTree::Glob(_) => {
+                if let Some(path) = self.resolve_prefix(&prefix) {
+                    self.globs.push(path);
+                }
+            }
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.add_use(tree, prefix.clone());
+                }
+            }
+        }
+    }
+
+    /// The path from the crate root that `path` names through an import, the
+    /// crate's name, or `crate`/`self`/`super`; `None` for other crates' items.
+    fn resolve_prefix(&self, path: &[String]) -> Option<Vec<String>> {
+        let (first, rest) = path.split_first()?;
+        let mut resolved = if let Some(imported) = self.imports.get(first) {
+            imported.clone()
+        } else if self.crate_name.as_ref() == Some(first) {
+            Vec::new()
+        } else if !self.in_crate {
+            return None;
+        } else {
+            match first.as_str() {
+                "crate" => Vec::new(),
+                "self" => self.module.clone()?,
+                "super" => {
+                    let mut module = self.module.clone()?;
+                    module.pop()?;
+                    let supers = rest.iter().take_while(|s| *s == "super").count();
+                    for _ in 0..supers {
+                        module.pop()?;
+                    }
+                    module.extend(rest[supers..].iter().cloned());
+                    return Some(module);
+                }
+                _ => return None,
+            }
+        };
+        resolved.extend(rest.iter().cloned());
+        Some(resolved)
+    }
+
+    /// Paths from the crate root that a called `path` may name: one starting
+    /// with an unknown name may come from any glob import.
+    fn resolve(&self, path: &[String]) -> Vec<Vec<String>> {
+        if let Some(resolved) = self.resolve_prefix(path) {
+            return vec![resolved];
+        }
+        self.globs
+            .iter()
+            .map(|glob| {
+                let mut resolved = glob.clone();
+                resolved.extend(path.iter().cloned());
+                resolved
+            })
+            .collect()
+    }
+
+    /// A type named in a path, following a renaming import such as `use store::Store as S;`.
+    fn imported_type(&self, name: &str) -> String {
+        self.imports
+            .get(name)
+            .and_then(|path| path.last())
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Type of a method call's receiver, when the test makes it evident: a
+    /// binding with a known type, a constructor call `Type::new(..)` or a struct literal.
+    fn receiver_type(&self, expr: &syn::Expr) -> Option<String> {
+        match expr {
+            syn::Expr::Path(path) => path
+                .path
+                .get_ident()
+                .and_then(|ident| self.locals.get(&ident.to_string()))
+                .cloned(),
+            syn::Expr::Reference(reference) => self.receiver_type(&reference.expr),
+            syn::Expr::Paren(paren) => self.receiver_type(&paren.expr),
+            syn::Expr::Call(call) => match &*call.func {
+                syn::Expr::Path(path) => {
+                    let segments = &path.path.segments;
+                    let owner = segments.iter().rev().nth(1)?.ident.to_string();
+                    is_type_name(&owner).then(|| self.imported_type(&owner))
+                }
+                _ => None,
+            },
+            syn::Expr::Struct(literal) => literal
+                .path
+                .segments
+                .last()
+                .map(|s| self.imported_type(&s.ident.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        let items = node.content.as_ref().map_or(&[][..], |(_, items)| items);
+        self.in_module(&node.ident, items, |collector| visit::visit_item_mod(collector, node));
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        let saved = std::mem::take(&mut self.locals);
+        visit::visit_item_fn(self, node);
+        self.locals = saved;
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        visit::visit_local(self, node);
+        let (pat, typ) = match &node.pat {
+            syn::Pat::Type(typed) => (&*typed.pat, Some(&*typed.ty)),
+            pat => (pat, None),
+        };
+        let syn::Pat::Ident(binding) = pat else {
+            return;
+        };
+        let mut typ = typ;
+        while let Some(syn::Type::Reference(reference)) = typ {
+            typ = Some(&reference.elem);
+        }
+        let known = match typ {
+            Some(syn::Type::Path(path)) => path
+                .path
+                .segments
+                .last()
+                .map(|s| self.imported_type(&s.ident.to_string())),
+            Some(_) => None,
+            None => node
+                .init
+                .as_ref()
+                .and_then(|init| self.receiver_type(&init.expr)),
+        };
+        match known {
+            Some(known) => self.locals.insert(binding.ident.to_string(), known),
+            None => self.locals.remove(&binding.ident.to_string()),
+        };
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = &*node.func {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect();
+            let owner = match &path.qself {
+                Some(qself) => match &*qself.ty {
+                    syn::Type::Path(typ) => typ.path.segments.last().map(|s| s.ident.to_string()),
+                    _ => None,
+                },
+                None => segments
+                    .iter()
+                    .rev()
+                    .nth(1)
+                    .filter(|owner| is_type_name(owner))
+                    .cloned(),
+            };
+            if let (Some(owner), Some(name)) = (owner, segments.last()) {
+                self.invoked
+                    .associated
+                    .insert((self.imported_type(&owner), name.clone()));
+            } else if path.qself.is_none() {
+                for resolved in self.resolve(&segments) {
+                    self.invoked.paths.insert(resolved);
+                }
+            }
+        }
+        visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if let Some(receiver) = self.receiver_type(&node.receiver) {
+            self.invoked
+                .associated
+                .insert((receiver, node.method.to_string()));
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        // assert!/assert_eq! arguments are expressions; other macros are skipped
+        // if their tokens don't parse that way
+        let args = node.parse_body_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        );
+        if let Ok(args) = args {
+            for expr in &args {
+                self.visit_expr(expr);
+            }
+        }
+        visit::visit_macro(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::analyzer::analyze_function;
+
+    fn function(source: &str) -> FunctionInfo {
+        analyze_function(&syn::parse_str(source).unwrap(), "src/lib.rs")
+    }
+
+    #[test]
+    fn test_invoked_functions_from_tests_and_cfg_test_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("tests")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"my-crate\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("tests/api.rs"),
+            "#[test] fn t() { assert_eq!(my_crate::parse(\"1\"), Ok(1)); }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("tests/lib_tests.rs"),
+            "#[test] fn t() { render(); }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub mod codec;
+             pub fn format() { helper(); }
+             #[cfg(test)] mod tests { #[test] fn t() { let v = super::validate(); v.check(); } }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/codec.rs"),
+            "#[cfg(test)] mod tests { use super::*; #[test] fn t() { encode(1); } }",
+        )
+        .unwrap();
+
+        let exclude = HashSet::from([dir.path().join("tests/lib_tests.rs")]);
+        let invoked = invoked_functions(dir.path(), &exclude);
+        let calls = |source: &str, module: &str| invoked.calls(&function(source), module);
+        assert!(calls("pub fn parse() {}", ""));
+        assert!(calls("pub fn validate() {}", ""));
+        assert!(calls("pub fn encode(x: u8) {}", "codec"));
+        assert!(!calls("pub fn encode(x: u8) {}", ""));
+        assert!(!calls("pub fn parse() {}", "codec"));
+        assert!(!calls("pub fn check() {}", ""));
+        assert!(!calls("pub fn helper() {}", ""));
+        assert!(!calls("pub fn render() {}", ""));
+    }
+
+    #[test]
+    fn test_calls_into_other_crates_and_unknown_receivers_match_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("tests")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"shop\"\n").unwrap();
+        std::fs::write(
+            dir.path().join("tests/cart.rs"),
+            "use shop::cart::{self, Store as S};
+             use shop::prelude::*;
+             #[test] fn t<T: Default>() {
+                 let v = Vec::new();
+                 let n = \"1\".parse::<u8>().unwrap();
+                 let _ = (v.len(), T::default(), n);
+                 let store = S::open();
+                 store.get(1);
+                 cart::total(&store);
+                 checkout();
+                 pay::card();
+             }",
+        )
+        .unwrap();
+
+        let invoked = invoked_functions(dir.path(), &HashSet::new());
+        let calls = |source: &str, module: &str| invoked.calls(&function(source), module);
+        for free in ["new", "len", "parse", "default", "open", "get"] {
+            assert!(!calls(&format!("pub fn {}() {{}}", free), ""), "{}", free);
+        }
+        assert!(calls("pub fn total() {}", "cart"));
+        assert!(calls("pub fn checkout() {}", "prelude"));
+        assert!(calls("pub fn card() {}", "prelude::pay"));
+        assert!(!calls("pub fn card() {}", "pay"));
+
+        let mut get = function("fn get(&self, id: u32) {}");
+        get.self_type = Some("Store".to_string());
+        assert!(invoked.calls(&get, "cart"));
+        get.self_type = Some("Basket".to_string());
+        assert!(!invoked.calls(&get, "cart"));
+    }
+}
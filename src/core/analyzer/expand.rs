@@ -0,0 +1,276 @@
+//! # Macro Expansion
+//!
+//! Functions generated by macros (`macro_rules!` APIs, derive-like attribute
+//! macros) don't exist in the source `syn` reads. With `--expand` the crate is
+//! expanded by `cargo expand`, or by `cargo rustc -Zunpretty=expanded` on a
+//! nightly toolchain, and the functions found only in the expansion are added
+//! to the direct analysis.
+
+use super::rust_analyzer::async_trait_methods;
+use crate::config::Config;
+use crate::core::analyzer::{analyze_function, CrateManifest};
+use crate::core::models::{FunctionInfo, ProjectInfo};
+use crate::error::{AutoTestError, Result};
+use quote::ToTokens;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Commands tried in order to print the expanded library.
+const EXPAND_COMMANDS: &[&[&str]] = &[
+    &["expand", "--lib", "--ugly"],
+    &[
+        "rustc",
+        "--lib",
+        "--profile=check",
+        "--",
+        "-Zunpretty=expanded",
+    ],
+    &[
+        "+nightly",
+        "rustc",
+        "--lib",
+        "--profile=check",
+        "--",
+        "-Zunpretty=expanded",
+    ],
+];
+
+/// Expanded source of a crate's library target.
+///
+/// # Errors
+///
+/// `AT0006` when neither `cargo expand` nor a nightly `cargo rustc` can expand
+/// the crate, with each command's error.
+pub fn expand_crate(project_root: &Path) -> Result<String> {
+    let mut failures = Vec::new();
+    for args in EXPAND_COMMANDS {
+        let output = Command::new("cargo")
+            .args(*args)
+            .current_dir(project_root)
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let reason = stderr
+                    .lines()
+                    .find(|line| line.starts_with("error"))
+                    .unwrap_or_else(|| stderr.trim());
+                failures.push(format!("`cargo {}`: {}", args.join(" "), reason));
+            }
+            Err(e) => failures.push(format!("`cargo {}`: {}", args.join(" "), e)),
+        }
+    }
+    Err(AutoTestError::ExpandFailed {
+        message: failures.join("; "),
+    })
+}
+
+/// Expand the crate at `project_root` and add the functions found only in the
+/// expansion to `project`; returns how many were added.
+///
+/// # Errors
+///
+/// `AT0006` when the crate can't be expanded, `AT0001` when the expansion
+/// doesn't parse.
+pub fn add_expanded_functions(
+    project: &mut ProjectInfo,
+    project_root: &Path,
+    config: &Config,
+) -> Result<usize> {
+    let lib_path = CrateManifest::load(project_root)?
+        .and_then(|manifest| manifest.lib_path)
+        .unwrap_or_else(|| "src/lib.rs".to_string());
+    let expanded = expand_crate(project_root)?;
+    let functions = analyze_expanded(&expanded, &project_root.join(lib_path), config)?;
+    Ok(merge_expanded(project, functions))
+}
+
+/// Functions of the expanded source, with the module paths of the inline
+/// modules they're in. Visibility and skip filtering follow the direct analysis.
+///
+/// # Arguments
+///
+/// * `expanded` - Output of [`expand_crate`]
+/// * `crate_root` - Library root file recorded as the functions' file
+/// * `config` - Configuration for visibility and skip filtering
+pub fn analyze_expanded(
+    expanded: &str,
+    crate_root: &Path,
+    config: &Config,
+) -> Result<Vec<FunctionInfo>> {
+    let ast = syn::parse_file(expanded).map_err(|e| AutoTestError::ParseFailed {
+        path: PathBuf::from("<expanded>"),
+        source: e,
+    })?;
+    let mut functions = Vec::new();
+    collect_functions(
+        &ast.items,
+        "",
+        true,
+        &crate_root.to_string_lossy(),
+        config,
+        &mut functions,
+    );
+    Ok(functions)
+}
+
+/// Add the functions of the expansion missing from `project`, e.g. because a
+/// macro generated them; returns how many were added.
+pub fn merge_expanded(project: &mut ProjectInfo, expanded: Vec<FunctionInfo>) -> usize {
+    let known: HashSet<(String, Option<String>, String)> =
+        project.functions.iter().map(merge_key).collect();
+    let before = project.functions.len();
+    project.functions.extend(
+        expanded
+            .into_iter()
+            .filter(|f| !known.contains(&merge_key(f))),
+    );
+    project.functions.len() - before
+}
+
+fn merge_key(f: &FunctionInfo) -> (String, Option<String>, String) {
+    (
+        f.module_path.clone().unwrap_or_default(),
+        f.self_type.clone(),
+        f.name.clone(),
+    )
+}
+
+fn collect_functions(
+    items: &[syn::Item],
+    module: &str,
+    public: bool,
+    file: &str,
+    config: &Config,
+    functions: &mut Vec<FunctionInfo>,
+) {
+    for item in items {
+        match item {
+            syn::Item::Fn(func) => {
+                let is_public = func.vis.to_token_stream().to_string() == "pub";
+                if (!is_public && !config.include_private)
+                    || config.should_skip_function(&func.sig.ident.to_string())
+                {
+                    continue;
+                }
+                let mut info = analyze_function(func, file);
+                info.module_path = Some(module.to_string());
+                info.public_path = (public && is_public).then(|| match module {
+                    "" => info.name.clone(),
+                    module => format!("{}::{}", module, info.name),
+                });
+                functions.push(info);
+            }
+            syn::Item::Impl(imp) => {
+                for mut info in async_trait_methods(imp, file, config, &HashSet::new()) {
+                    info.module_path = Some(module.to_string());
+                    functions.push(info);
+                }
+            }
+            syn::Item::Mod(item_mod) => {
+                let Some((_, items)) = &item_mod.content else {
+                    continue;
+                };
+                // #[cfg(test)] modules are already stripped from the expansion
+                let name = item_mod.ident.to_string();
+                let child = match module {
+                    "" => name,
+                    module => format!("{}::{}", module, name),
+                };
+                let is_public = matches!(item_mod.vis, syn::Visibility::Public(_));
+                collect_functions(items, &child, public && is_public, file, config, functions);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expanded_functions_fill_in_macro_generated_api() {
+        let expanded = r#"
+            #![feature(prelude_import)]
+            #[prelude_import]
+            use std::prelude::rust_2021::*;
+            #[macro_use]
+            extern crate std;
+            pub mod codecs {
+                pub fn encode_u8(value: u8) -> Vec<u8> { ::alloc::vec::Vec::new() }
+                pub fn encode_u16(value: u16) -> Vec<u8> { ::alloc::vec::Vec::new() }
+                fn helper() {}
+            }
+            mod internal {
+                pub fn hidden() {}
+            }
+            pub fn written_by_hand() {}
+        "#;
+        let config = Config::default();
+        let functions = analyze_expanded(expanded, Path::new("src/lib.rs"), &config).unwrap();
+        let names: Vec<(&str, Option<&str>)> = functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.public_path.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("encode_u8", Some("codecs::encode_u8")),
+                ("encode_u16", Some("codecs::encode_u16")),
+                ("hidden", None),
+                ("written_by_hand", Some("written_by_hand")),
+            ]
+        );
+
+        let mut project = ProjectInfo {
+            schema_version: crate::core::models::SCHEMA_VERSION,
+            language: "rust".to_string(),
+            root: String::new(),
+            functions: vec![FunctionInfo {
+                name: "written_by_hand".to_string(),
+                module_path: Some(String::new()),
+                line: 7,
+                ..Default::default()
+            }],
+        };
+        assert_eq!(merge_expanded(&mut project, functions), 3);
+        assert_eq!(project.functions[0].line, 7);
+    }
+
+    #[test]
+    fn test_expanded_async_trait_methods_are_async() {
+        let expanded = r#"
+            pub mod store {
+                pub struct Store;
+                impl Repository for Store {
+                    #[allow(clippy::type_complexity)]
+                    fn count<'life0, 'async_trait>(
+                        &'life0 self,
+                    ) -> ::core::pin::Pin<
+                        Box<dyn ::core::future::Future<Output = usize> + ::core::marker::Send + 'async_trait>,
+                    >
+                    where
+                        'life0: 'async_trait,
+                        Self: 'async_trait,
+                    {
+                        Box::pin(async move { 0 })
+                    }
+                }
+            }
+        "#;
+        let functions =
+            analyze_expanded(expanded, Path::new("src/lib.rs"), &Config::default()).unwrap();
+        assert_eq!(functions.len(), 1);
+        let count = &functions[0];
+        assert!(count.is_async);
+        assert_eq!(count.returns.as_str(), "usize");
+        assert_eq!(count.params[0].typ.as_str(), "&Store");
+        assert_eq!(count.module_path.as_deref(), Some("store"));
+        assert_eq!(count.trait_path.as_deref(), Some("Repository"));
+    }
+}
@@ -0,0 +1,301 @@
+//! # Cargo Manifest
+//!
+//! Lightweight view of the analyzed crate's `Cargo.toml`, used to adapt the
+//! generated tests to the crate's features and targets.
+
+use super::path_segments;
+use crate::error::{AutoTestError, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// The parts of a `Cargo.toml` that influence test generation.
+#[derive(Debug, Clone, Default)]
+pub struct CrateManifest {
+    /// Package name from `[package] name`.
+    pub name: Option<String>,
+    /// `[package] edition`; `None` when unset, which Cargo treats as 2015.
+    pub edition: Option<String>,
+    /// `edition.workspace = true`, taking the edition from the workspace root.
+    pub inherits_edition: bool,
+    /// Declared `[features]` and the features/dependencies each one enables.
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Optional dependencies, which implicitly define a feature of the same name.
+    pub optional_dependencies: BTreeSet<String>,
+    /// Names of all `[dependencies]`.
+    pub dependencies: BTreeSet<String>,
+    /// Names of all `[dev-dependencies]`.
+    pub dev_dependencies: BTreeSet<String>,
+    /// `[lib] crate-type` entries, e.g. `cdylib`.
+    pub lib_crate_types: Vec<String>,
+    /// `[lib] path`, when the library root isn't `src/lib.rs`.
+    pub lib_path: Option<String>,
+    /// `[lib] proc-macro = true`.
+    pub is_proc_macro: bool,
+    /// Whether the manifest has a `[lib]` section.
+    pub has_lib_section: bool,
+    /// `[[bin]] path` entries, for binaries outside `src/main.rs` and `src/bin/`.
+    pub bin_paths: Vec<String>,
+    /// `[workspace] members` entries, which may be globs like `crates/*`.
+    pub workspace_members: Vec<String>,
+    /// `[workspace] exclude` entries.
+    pub workspace_exclude: Vec<String>,
+    /// `[workspace.package] edition`, inherited by members.
+    pub workspace_edition: Option<String>,
+}
+
+impl CrateManifest {
+    /// Load `Cargo.toml` from a project root.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(None)` when the project has no manifest, or an error if it cannot be parsed
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join("Cargo.toml");
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(&path).map_err(|e| AutoTestError::FileRead {
+            path: path.clone(),
+            source: e,
+        })?;
+
+        Self::parse(&contents)
+            .map(Some)
+            .map_err(|e| AutoTestError::Manifest {
+                path,
+                message: e.to_string(),
+            })
+    }
+
+    /// Parse manifest contents.
+    pub fn parse(contents: &str) -> std::result::Result<Self, toml::de::Error> {
+        let value: toml::Value = toml::from_str(contents)?;
+
+        let name = value
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string);
+
+        let workspace_edition = value
+            .get("workspace")
+            .and_then(|w| w.get("package"))
+            .and_then(|p| p.get("edition"))
+            .and_then(|e| e.as_str())
+            .map(str::to_string);
+        let edition = value.get("package").and_then(|p| p.get("edition"));
+        let inherits_edition = edition
+            .and_then(|e| e.get("workspace"))
+            .and_then(|w| w.as_bool())
+            == Some(true);
+        let edition = match edition.and_then(|e| e.as_str()) {
+            Some(edition) => Some(edition.to_string()),
+            None if inherits_edition => workspace_edition.clone(),
+            None => None,
+        };
+
+        let features = value
+            .get("features")
+            .and_then(|f| f.as_table())
+            .map(|table| {
+                table
+                    .iter()
+                    .map(|(feature, enables)| {
+                        let enables = enables
+                            .as_array()
+                            .map(|items| {
+                                items
+                                    .iter()
+                                    .filter_map(|i| i.as_str().map(str::to_string))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (feature.clone(), enables)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let optional_dependencies = value
+            .get("dependencies")
+            .and_then(|d| d.as_table())
+            .map(|table| {
+                table
+                    .iter()
+                    .filter(|(_, spec)| {
+                        spec.get("optional").and_then(|o| o.as_bool()) == Some(true)
+                    })
+                    .map(|(dep, _)| dep.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let table_keys = |key: &str| -> BTreeSet<String> {
+            value
+                .get(key)
+                .and_then(|d| d.as_table())
+                .map(|table| table.keys().cloned().collect())
+                .unwrap_or_default()
+        };
+
+        let lib_crate_types = value
+            .get("lib")
+            .and_then(|l| l.get("crate-type"))
+            .and_then(|c| c.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let string_array = |table: &str, key: &str| -> Vec<String> {
+            value
+                .get(table)
+                .and_then(|t| t.get(key))
+                .and_then(|a| a.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|i| i.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let lib_path = value
+            .get("lib")
+            .and_then(|l| l.get("path"))
+            .and_then(|p| p.as_str())
+            .map(str::to_string);
+        let is_proc_macro = value
+            .get("lib")
+            .and_then(|l| l.get("proc-macro"))
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false);
+        let bin_paths = value
+            .get("bin")
+            .and_then(|b| b.as_array())
+            .map(|bins| {
+                bins.iter()
+                    .filter_map(|bin| bin.get("path").and_then(|p| p.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            name,
+            edition,
+            inherits_edition,
+            features,
+            optional_dependencies,
+            dependencies: table_keys("dependencies"),
+            dev_dependencies: table_keys("dev-dependencies"),
+            lib_crate_types,
+            lib_path,
+            is_proc_macro,
+            has_lib_section: value.get("lib").is_some(),
+            bin_paths,
+            workspace_members: string_array("workspace", "members"),
+            workspace_exclude: string_array("workspace", "exclude"),
+            workspace_edition,
+        })
+    }
+
+    /// The crate's edition, defaulting to 2015 like Cargo.
+    pub fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or("2015")
+    }
+
+    /// Check whether `feature` can be enabled for this crate, either as a declared
+    /// feature or as the implicit feature of an optional dependency.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.contains_key(feature) || self.optional_dependencies.contains(feature)
+    }
+
+    /// Whether the crate has a library target: a `[lib]` section or `src/lib.rs`.
+    pub fn has_lib_target(&self, root: &Path) -> bool {
+        self.has_lib_section || root.join("src/lib.rs").is_file()
+    }
+
+    /// Whether the crate only builds binaries, leaving tests in `tests/` no
+    /// library to import.
+    pub fn is_binary_only(&self, root: &Path) -> bool {
+        !self.has_lib_target(root)
+            && (root.join("src/main.rs").is_file()
+                || root.join("src/bin").is_dir()
+                || !self.bin_paths.is_empty())
+    }
+
+    /// Whether `file`, a path under `root`, belongs to a binary rather than the
+    /// library: `src/main.rs`, anything in `src/bin/`, or a `[[bin]] path`.
+    pub fn is_binary_source(&self, root: &Path, file: &Path) -> bool {
+        let file = path_segments(file);
+        let root = path_segments(root);
+        let file = file.strip_prefix(root.as_slice()).unwrap_or(&file);
+        let is = |path: &str| file == path_segments(Path::new(path)).as_slice();
+        (file.len() > 2 && file[0] == "src" && file[1] == "bin")
+            || is("src/main.rs")
+            || self.bin_paths.iter().any(|path| is(path))
+    }
+
+    /// Whether this is a workspace root listing member crates.
+    pub fn is_workspace(&self) -> bool {
+        !self.workspace_members.is_empty()
+    }
+
+    /// Directories of the workspace members, with globs expanded, excluded
+    /// paths removed, and only directories containing a `Cargo.toml` kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The workspace root this manifest was loaded from
+    pub fn member_dirs(&self, root: &Path) -> Vec<std::path::PathBuf> {
+        let excluded: Vec<_> = self
+            .workspace_exclude
+            .iter()
+            .map(|e| root.join(e))
+            .collect();
+
+        let mut dirs: Vec<_> = self
+            .workspace_members
+            .iter()
+            .flat_map(|member| {
+                let pattern = root.join(member).to_string_lossy().to_string();
+                glob::glob(&pattern)
+                    .map(|paths| paths.filter_map(|p| p.ok()).collect::<Vec<_>>())
+                    .unwrap_or_default()
+            })
+            .filter(|dir| dir.join("Cargo.toml").is_file())
+            .filter(|dir| !excluded.iter().any(|e| dir.starts_with(e)))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    /// Whether the crate targets wasm through wasm-bindgen: it depends on
+    /// `wasm-bindgen` and builds a `cdylib`.
+    pub fn is_wasm_bindgen_crate(&self) -> bool {
+        self.dependencies.contains("wasm-bindgen")
+            && self.lib_crate_types.iter().any(|t| t == "cdylib")
+    }
+
+    /// Whether the crate depends on `name`, as a regular or dev-dependency.
+    pub fn depends_on(&self, name: &str) -> bool {
+        self.dependencies.contains(name) || self.dev_dependencies.contains(name)
+    }
+
+    /// Runtime async tests can use: `tokio` or `async-std` when the crate depends
+    /// on one, otherwise a `block_on` executor (`pollster` if the crate has it,
+    /// `futures` by default).
+    pub fn async_runtime(&self) -> &'static str {
+        ["tokio", "async-std", "pollster"]
+            .into_iter()
+            .find(|runtime| self.depends_on(runtime))
+            .unwrap_or("futures")
+    }
+}
@@ -1,12 +1,40 @@
+mod call_graph;
+mod complexity;
+mod constructors;
+mod coverage;
+mod existing_tests;
+mod expand;
+mod manifest;
+mod module_tree;
+mod mutants;
+mod purity;
 mod rust_analyzer;
 mod ts_analyzer;
 
 // Public exports
+pub use call_graph::{called_paths, CallGraph};
+pub use complexity::cyclomatic_complexity;
+pub use constructors::{Constructor, ConstructorIndex};
+pub use coverage::CoverageReport;
+pub use existing_tests::{invoked_functions, InvokedFunctions};
+pub use expand::{add_expanded_functions, analyze_expanded, expand_crate, merge_expanded};
+pub use manifest::CrateManifest;
+pub use module_tree::ModuleTree;
+pub use mutants::{MutantsReport, SurvivingMutant};
+pub use purity::is_pure;
 pub use rust_analyzer::{
+    analyze_function,
     analyze_rust_file,
+    analyze_rust_source,
     analyze_rust_project,
     analyze_rust_project_filtered,
+    project_rust_files,
+    discover_files,
     should_skip_file,
+    matches_skip_pattern,
+    SkipPatterns,
     is_standard_ignored_path,
+    is_no_std_crate,
 };
+pub(crate) use rust_analyzer::path_segments;
 pub use ts_analyzer::analyze_ts_files;
@@ -0,0 +1,447 @@
+//! # Module Tree
+//!
+//! Maps source files to their module paths by following `mod` declarations
+//! from the crate roots, the way rustc resolves them: `foo.rs` and `foo/mod.rs`
+//! layouts, `#[path]` attributes, and out-of-line modules declared inside
+//! inline `mod` blocks. Guessing modules from file paths alone gets all of
+//! these wrong, and can't tell library modules from binary ones.
+//!
+//! The library's `pub mod`s and `pub use` re-exports are recorded too, so tests
+//! can call a function by its shortest public path (`crate_name::foo` for
+//! `pub use internal::foo;`) instead of a private internal one.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use syn::{Item, Meta, UseTree, Visibility};
+
+/// Module paths of the files reachable from a crate's roots.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleTree {
+    /// Module path of every file, `""` for the crate roots.
+    modules: HashMap<PathBuf, String>,
+    /// Files only reachable from a binary root (`src/main.rs`, `src/bin/`).
+    binary: HashMap<PathBuf, bool>,
+    /// Library modules declared `pub`, by module path.
+    public_modules: HashSet<String>,
+    /// `pub use` items of the library.
+    reexports: Vec<Reexport>,
+}
+
+/// One name a `pub use` item brings into a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reexport {
+    /// Module path of the module containing the `pub use`.
+    module: String,
+    /// Name in that module, `None` for a glob.
+    name: Option<String>,
+    /// Absolute path of the item, or of the module for a glob.
+    target: String,
+}
+
+impl ModuleTree {
+    /// Follow the `mod` declarations of a crate, starting at `src/lib.rs`, then
+    /// `src/main.rs` and `src/bin/`. A file reachable from the library keeps its
+    /// library module path. Missing or unparsable files are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_root` - Directory containing the crate's `Cargo.toml`
+    pub fn build(crate_root: &Path) -> Self {
+        let src = crate_root.join("src");
+        let mut tree = Self::default();
+        tree.add_root(&src.join("lib.rs"), false);
+        tree.add_root(&src.join("main.rs"), true);
+
+        let mut bins: Vec<PathBuf> = std::fs::read_dir(src.join("bin"))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter_map(|path| {
+                if path.extension().is_some_and(|ext| ext == "rs") {
+                    Some(path)
+                } else {
+                    Some(path.join("main.rs")).filter(|main| main.is_file())
+                }
+            })
+            .collect();
+        bins.sort();
+        for bin in bins {
+            tree.add_root(&bin, true);
+        }
+        tree
+    }
+
+    /// Module path of a source file, `""` for a crate root, or `None` if no
+    /// `mod` declaration reaches it.
+    pub fn module_path(&self, file: &Path) -> Option<&str> {
+        self.modules.get(&normalize(file)).map(String::as_str)
+    }
+
+    /// Whether a file belongs to a binary and not to the library, so
+    /// integration tests can't call into it.
+    pub fn is_binary(&self, file: &Path) -> bool {
+        self.binary.get(&normalize(file)).copied().unwrap_or(false)
+    }
+
+    /// Shortest public path of the item `name` defined in `module`, relative to
+    /// the crate root, following `pub use` re-exports: `foo` for a function in a
+    /// private `internal` module re-exported with `pub use internal::foo;`.
+    /// `None` if no path to it is public.
+    pub fn public_path(&self, module: &str, name: &str) -> Option<String> {
+        // Every path naming the item, grown until no re-export adds one
+        let mut paths = vec![join(module, name)];
+        let mut known: HashSet<String> = paths.iter().cloned().collect();
+        let mut i = 0;
+        while i < paths.len() {
+            let (parent, item) = split(&paths[i]);
+            let (parent, item) = (parent.to_string(), item.to_string());
+            for reexport in &self.reexports {
+                let alias = match &reexport.name {
+                    Some(alias) if reexport.target == paths[i] => join(&reexport.module, alias),
+                    None if reexport.target == parent => join(&reexport.module, &item),
+                    _ => continue,
+                };
+                if known.insert(alias.clone()) {
+                    paths.push(alias);
+                }
+            }
+            i += 1;
+        }
+
+        paths
+            .into_iter()
+            .filter(|path| self.is_public_module(split(path).0))
+            .min_by(|a, b| {
+                a.matches("::")
+                    .count()
+                    .cmp(&b.matches("::").count())
+                    .then(a.cmp(b))
+            })
+    }
+
+    /// Whether a library module and all its ancestors are declared `pub`.
+    fn is_public_module(&self, module: &str) -> bool {
+        let mut prefix = String::new();
+        module
+            .split("::")
+            .filter(|part| !part.is_empty())
+            .all(|part| {
+                prefix = join(&prefix, part);
+                self.public_modules.contains(&prefix)
+            })
+    }
+
+    /// Number of files in the tree.
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Whether no crate root was found.
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    fn add_root(&mut self, root: &Path, binary: bool) {
+        if let Some(dir) = root.parent() {
+            self.add_file(root, String::new(), dir.to_path_buf(), binary);
+        }
+    }
+
+    /// Record `file` as `module` and follow its declarations; `dir` is where
+    /// its child modules live.
+    fn add_file(&mut self, file: &Path, module: String, dir: PathBuf, binary: bool) {
+        let file = normalize(file);
+        if self.modules.contains_key(&file) {
+            return;
+        }
+        let Ok(source) = std::fs::read_to_string(&file) else {
+            return;
+        };
+        let Ok(ast) = syn::parse_file(&source) else {
+            return;
+        };
+        self.modules.insert(file.clone(), module.clone());
+        self.binary.insert(file.clone(), binary);
+        let file_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.add_items(&ast.items, &module, &dir, Some(&file_dir), binary);
+    }
+
+    /// Follow the `mod` items of one module scope. `path_base` is the directory
+    /// `#[path]` is relative to at the top level of a file, `None` inside inline
+    /// modules where it is relative to `dir`.
+    fn add_items(
+        &mut self,
+        items: &[Item],
+        module: &str,
+        dir: &Path,
+        path_base: Option<&Path>,
+        binary: bool,
+    ) {
+        for item in items {
+            if let Item::Use(item_use) = item {
+                if !binary && matches!(item_use.vis, Visibility::Public(_)) {
+                    self.add_reexports(module, &item_use.tree, Vec::new());
+                }
+                continue;
+            }
+            let Item::Mod(item_mod) = item else {
+                continue;
+            };
+            let name = item_mod.ident.to_string();
+            let name = name.strip_prefix("r#").unwrap_or(&name);
+            let child = join(module, name);
+            if !binary && matches!(item_mod.vis, Visibility::Public(_)) {
+                self.public_modules.insert(child.clone());
+            }
+            let path_attr = path_attribute(&item_mod.attrs);
+
+            match &item_mod.content {
+                Some((_, items)) => {
+                    let child_dir = match &path_attr {
+                        Some(path) => dir.join(path),
+                        None => dir.join(name),
+                    };
+                    self.add_items(items, &child, &child_dir, None, binary);
+                }
+                None => {
+                    let file = match &path_attr {
+                        Some(path) => path_base.unwrap_or(dir).join(path),
+                        None if dir.join(format!("{}.rs", name)).is_file() => {
+                            dir.join(format!("{}.rs", name))
+                        }
+                        None => dir.join(name).join("mod.rs"),
+                    };
+                    // Modules in `foo.rs` live in `foo/`, like those of `foo/mod.rs`;
+                    // files named by #[path] count as mod.rs files
+                    let child_dir =
+                        if path_attr.is_none() && file.file_name().is_some_and(|f| f != "mod.rs") {
+                            dir.join(name)
+                        } else {
+                            file.parent().map(Path::to_path_buf).unwrap_or_default()
+                        };
+                    self.add_file(&file, child, child_dir, binary);
+                }
+            }
+        }
+    }
+
+    /// Record the names a `pub use` tree in `module` brings in; `prefix` holds
+    /// the path segments above `tree`.
+    fn add_reexports(&mut self, module: &str, tree: &UseTree, mut prefix: Vec<String>) {
+        let (name, last) = match tree {
+            UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                return self.add_reexports(module, &path.tree, prefix);
+            }
+            UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.add_reexports(module, tree, prefix.clone());
+                }
+                return;
+            }
+            UseTree::Name(name) => (Some(name.ident.to_string()), Some(name.ident.to_string())),
+            UseTree::Rename(rename) => (
+                Some(rename.rename.to_string()),
+                Some(rename.ident.to_string()),
+            ),
+            UseTree::Glob(_) => (None, None),
+        };
+        // `pub use foo::{self}` re-exports the module `foo`
+        if last.as_deref() == Some("self") {
+            let Some(module_name) = prefix.last().cloned() else {
+                return;
+            };
+            let name = name.filter(|n| n != "self").unwrap_or(module_name);
+            if let Some(target) = resolve(module, &prefix) {
+                self.reexports.push(Reexport {
+                    module: module.to_string(),
+                    name: Some(name),
+                    target,
+                });
+            }
+            return;
+        }
+        prefix.extend(last);
+        if let Some(target) = resolve(module, &prefix) {
+            self.reexports.push(Reexport {
+                module: module.to_string(),
+                name,
+                target,
+            });
+        }
+    }
+}
+
+/// Absolute module path of `segments` used in `module`: `crate::`, `self::` and
+/// `super::` are resolved, anything else is taken as relative to `module`.
+/// `None` for paths leaving the crate (`super` above the root).
+fn resolve(module: &str, segments: &[String]) -> Option<String> {
+    let mut path: Vec<&str> = match segments.first().map(String::as_str) {
+        Some("crate") => Vec::new(),
+        _ => module.split("::").filter(|part| !part.is_empty()).collect(),
+    };
+    for (i, segment) in segments.iter().enumerate() {
+        match segment.as_str() {
+            "crate" if i == 0 => {}
+            "self" if i == 0 => {}
+            "super" => {
+                path.pop()?;
+            }
+            segment => path.push(segment),
+        }
+    }
+    Some(path.join("::"))
+}
+
+/// `module::name`, or `name` at the crate root.
+fn join(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module, name)
+    }
+}
+
+/// Parent module path and last segment of a path.
+fn split(path: &str) -> (&str, &str) {
+    path.rsplit_once("::").unwrap_or(("", path))
+}
+
+/// Value of a `#[path = "..."]` attribute.
+fn path_attribute(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Resolve `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_tree_follows_mod_declarations() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "src/lib.rs",
+                "mod a;\n#[path = \"weird/b_impl.rs\"]\npub mod b;\npub mod c {\n    pub mod d;\n}\n",
+            ),
+            ("src/a.rs", "pub mod inner;\n"),
+            ("src/a/inner.rs", "pub fn f() {}\n"),
+            ("src/weird/b_impl.rs", "pub mod sibling;\n"),
+            ("src/weird/sibling.rs", ""),
+            ("src/c/d.rs", ""),
+            ("src/main.rs", "mod cli;\nmod a;\n"),
+            ("src/cli/mod.rs", ""),
+            ("src/orphan.rs", ""),
+        ];
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let tree = ModuleTree::build(dir.path());
+        let module = |path: &str| tree.module_path(&dir.path().join(path));
+        assert_eq!(module("src/lib.rs"), Some(""));
+        assert_eq!(module("src/a/inner.rs"), Some("a::inner"));
+        assert_eq!(module("src/weird/b_impl.rs"), Some("b"));
+        assert_eq!(module("src/weird/sibling.rs"), Some("b::sibling"));
+        assert_eq!(module("src/c/d.rs"), Some("c::d"));
+        assert_eq!(module("src/cli/mod.rs"), Some("cli"));
+        assert_eq!(module("src/./a/../orphan.rs"), None);
+        assert_eq!(tree.len(), 8);
+
+        assert!(tree.is_binary(&dir.path().join("src/cli/mod.rs")));
+        assert!(!tree.is_binary(&dir.path().join("src/a.rs")));
+    }
+
+    #[test]
+    fn test_public_path_follows_reexports() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "src/lib.rs",
+                "mod internal;
+pub mod api;
+pub use internal::{parse, render as draw};
+",
+            ),
+            (
+                "src/internal.rs",
+                "pub mod deep;
+pub fn parse() {}
+pub fn render() {}
+",
+            ),
+            (
+                "src/internal/deep.rs",
+                "pub fn hidden() {}
+pub fn nested() {}
+",
+            ),
+            (
+                "src/api.rs",
+                "pub use crate::internal::deep::*;
+pub mod v1;
+",
+            ),
+            (
+                "src/api/v1.rs",
+                "pub use super::super::internal::deep::nested;
+pub fn list() {}
+",
+            ),
+        ];
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let tree = ModuleTree::build(dir.path());
+        assert_eq!(
+            tree.public_path("internal", "parse").as_deref(),
+            Some("parse")
+        );
+        assert_eq!(
+            tree.public_path("internal", "render").as_deref(),
+            Some("draw")
+        );
+        assert_eq!(
+            tree.public_path("internal::deep", "hidden").as_deref(),
+            Some("api::hidden")
+        );
+        assert_eq!(
+            tree.public_path("internal::deep", "nested").as_deref(),
+            Some("api::nested")
+        );
+        assert_eq!(
+            tree.public_path("api::v1", "list").as_deref(),
+            Some("api::v1::list")
+        );
+        assert_eq!(tree.public_path("internal", "private"), None);
+    }
+}
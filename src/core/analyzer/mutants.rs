@@ -0,0 +1,175 @@
+//! # Mutation Testing Results
+//!
+//! Reads the surviving ("missed") mutants from a
+//! [cargo-mutants](https://mutants.rs) run so tests for those functions can get
+//! stronger assertions than a plain `is_ok()`.
+
+use super::coverage::same_source_file;
+use crate::core::models::FunctionInfo;
+use crate::error::{AutoTestError, Result};
+
+/// A mutant that no existing test caught.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurvivingMutant {
+    /// Source file as written by cargo-mutants.
+    pub file: String,
+    /// Function name, without any `Type::` qualifier.
+    pub function: String,
+    /// Human-readable description, e.g. `replace add -> i32 with 0`.
+    pub description: String,
+}
+
+/// Surviving mutants from a cargo-mutants run.
+#[derive(Debug, Clone, Default)]
+pub struct MutantsReport {
+    survivors: Vec<SurvivingMutant>,
+}
+
+impl MutantsReport {
+    /// Load a cargo-mutants result file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - `mutants.out/outcomes.json` or `mutants.out/missed.txt`
+    ///
+    /// # Returns
+    ///
+    /// The surviving mutants, or an error if the file can't be read or parsed
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| AutoTestError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let parsed = if contents.trim_start().starts_with('{') {
+            Self::parse_outcomes_json(&contents)
+        } else {
+            Ok(Self::parse_missed_txt(&contents))
+        };
+
+        parsed.map_err(|message| AutoTestError::Mutants {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// Parse `outcomes.json`, keeping only `MissedMutant` outcomes.
+    pub fn parse_outcomes_json(contents: &str) -> std::result::Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        let outcomes = value
+            .get("outcomes")
+            .and_then(|o| o.as_array())
+            .ok_or("missing 'outcomes' array")?;
+
+        let mut survivors = Vec::new();
+        for outcome in outcomes {
+            if outcome.get("summary").and_then(|s| s.as_str()) != Some("MissedMutant") {
+                continue;
+            }
+            let Some(mutant) = outcome.get("scenario").and_then(|s| s.get("Mutant")) else {
+                continue;
+            };
+
+            let file = mutant
+                .get("file")
+                .and_then(|f| f.as_str())
+                .unwrap_or_default();
+            // Older releases store the function as a plain string
+            let function = mutant
+                .get("function")
+                .and_then(|f| f.get("function_name").or(Some(f)))
+                .and_then(|f| f.as_str())
+                .unwrap_or_default();
+            let replacement = mutant
+                .get("replacement")
+                .and_then(|r| r.as_str())
+                .unwrap_or_default();
+            if file.is_empty() || function.is_empty() {
+                continue;
+            }
+
+            survivors.push(SurvivingMutant {
+                file: file.to_string(),
+                function: unqualified(function).to_string(),
+                description: format!("replace {} with {}", function, replacement),
+            });
+        }
+
+        Ok(Self { survivors })
+    }
+
+    /// Parse `missed.txt`, one `file:line:col: replace <fn> ... with <value>` per line.
+    pub fn parse_missed_txt(contents: &str) -> Self {
+        let survivors = contents
+            .lines()
+            .filter_map(|line| {
+                let (location, description) = line.trim().split_once(": ")?;
+                let file = location.split(':').next()?;
+                let target = description.strip_prefix("replace ")?;
+                let end = [" -> ", " with "]
+                    .iter()
+                    .filter_map(|sep| target.find(sep))
+                    .min()?;
+                Some(SurvivingMutant {
+                    file: file.to_string(),
+                    function: unqualified(&target[..end]).to_string(),
+                    description: description.to_string(),
+                })
+            })
+            .collect();
+
+        Self { survivors }
+    }
+
+    /// Descriptions of the mutants that survived in `func`.
+    pub fn survivors_for(&self, func: &FunctionInfo) -> Vec<String> {
+        self.survivors
+            .iter()
+            .filter(|m| m.function == func.name && same_source_file(&m.file, &func.file))
+            .map(|m| m.description.clone())
+            .collect()
+    }
+
+    /// Whether the run left no surviving mutants.
+    pub fn is_empty(&self) -> bool {
+        self.survivors.is_empty()
+    }
+}
+
+/// `<impl Display for Foo>::fmt` -> `fmt`.
+fn unqualified(function: &str) -> &str {
+    function.rsplit("::").next().unwrap_or(function).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_missed_mutants() {
+        let json = r#"{"outcomes":[
+            {"scenario":"Baseline","summary":"Success"},
+            {"scenario":{"Mutant":{"file":"src/math.rs","function":{"function_name":"Calc::add"},"replacement":"0"}},"summary":"MissedMutant"},
+            {"scenario":{"Mutant":{"file":"src/math.rs","function":{"function_name":"sub"},"replacement":"1"}},"summary":"CaughtMutant"}
+        ]}"#;
+        let report = MutantsReport::parse_outcomes_json(json).unwrap();
+        let add = FunctionInfo {
+            name: "add".to_string(),
+            file: "./src/math.rs".to_string(),
+            ..Default::default()
+        };
+        let sub = FunctionInfo {
+            name: "sub".to_string(),
+            ..add.clone()
+        };
+        assert_eq!(report.survivors_for(&add), vec!["replace Calc::add with 0"]);
+        assert!(report.survivors_for(&sub).is_empty());
+
+        let txt = "src/math.rs:3:5: replace sub -> i32 with 1\n";
+        let report = MutantsReport::parse_missed_txt(txt);
+        assert_eq!(
+            report.survivors_for(&sub),
+            vec!["replace sub -> i32 with 1"]
+        );
+    }
+}
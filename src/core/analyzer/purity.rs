@@ -0,0 +1,172 @@
+//! # Purity Detection
+//!
+//! Conservative check for functions without obvious side effects. A function
+//! counts as pure when it takes no `&mut` arguments, doesn't touch `static`
+//! items, performs no IO, randomness or clock reads, and has no `unsafe` code.
+//! Anything the check can't see through (e.g. calls into other crates) is
+//! assumed pure, so the result is a heuristic rather than a guarantee.
+
+use std::collections::HashSet;
+use syn::visit::{self, Visit};
+
+/// Path segments that indicate IO, process state, randomness or time.
+const IMPURE_PATH_SEGMENTS: &[&str] = &[
+    "fs",
+    "io",
+    "net",
+    "process",
+    "env",
+    "thread",
+    "File",
+    "OpenOptions",
+    "TcpStream",
+    "UdpSocket",
+    "Command",
+    "stdin",
+    "stdout",
+    "stderr",
+    "SystemTime",
+    "Instant",
+    "Utc",
+    "Local",
+    "thread_rng",
+    "random",
+    "OsRng",
+];
+
+/// Method names that mutate through shared references or perform IO.
+const IMPURE_METHODS: &[&str] = &[
+    "borrow_mut",
+    "lock",
+    "write",
+    "write_all",
+    "read",
+    "read_to_string",
+    "read_line",
+    "flush",
+    "send",
+    "recv",
+    "fetch_add",
+    "fetch_sub",
+    "store",
+    "swap",
+    "set",
+];
+
+/// Macros that write to stdout/stderr or a writer.
+const IMPURE_MACROS: &[&str] = &[
+    "println", "print", "eprintln", "eprint", "write", "writeln", "dbg", "log", "info", "warn",
+    "error", "debug", "trace",
+];
+
+/// Whether a function has no obvious side effects.
+///
+/// # Arguments
+///
+/// * `sig` - The function signature
+/// * `block` - The function body
+/// * `statics` - Names of `static` items declared in the same file
+pub fn is_pure(sig: &syn::Signature, block: &syn::Block, statics: &HashSet<String>) -> bool {
+    if sig.unsafety.is_some() {
+        return false;
+    }
+
+    let has_mut_arg = sig.inputs.iter().any(|input| match input {
+        syn::FnArg::Receiver(receiver) => receiver.mutability.is_some(),
+        syn::FnArg::Typed(pat_type) => {
+            matches!(&*pat_type.ty, syn::Type::Reference(r) if r.mutability.is_some())
+        }
+    });
+    if has_mut_arg {
+        return false;
+    }
+
+    let mut finder = SideEffectFinder {
+        statics,
+        found: false,
+    };
+    finder.visit_block(block);
+    !finder.found
+}
+
+struct SideEffectFinder<'a> {
+    statics: &'a HashSet<String>,
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for SideEffectFinder<'_> {
+    fn visit_path(&mut self, node: &'ast syn::Path) {
+        let touches_static =
+            node.segments.len() == 1 && self.statics.contains(&node.segments[0].ident.to_string());
+        // A single segment is usually a local or the function's own name; only
+        // qualified paths like `std::fs::read` identify IO
+        let is_impure_api = node.segments.len() > 1
+            && node
+                .segments
+                .iter()
+                .any(|s| IMPURE_PATH_SEGMENTS.contains(&s.ident.to_string().as_str()));
+        if touches_static || is_impure_api {
+            self.found = true;
+        }
+        visit::visit_path(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if IMPURE_METHODS.contains(&node.method.to_string().as_str()) {
+            self.found = true;
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if node
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| IMPURE_MACROS.contains(&s.ident.to_string().as_str()))
+        {
+            self.found = true;
+        }
+        visit::visit_macro(self, node);
+    }
+
+    fn visit_expr_unsafe(&mut self, _node: &'ast syn::ExprUnsafe) {
+        self.found = true;
+    }
+
+    fn visit_item_fn(&mut self, _node: &'ast syn::ItemFn) {
+        // Nested functions only matter if called, which the call site already shows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn purity_of(src: &str, statics: &[&str]) -> bool {
+        let func: syn::ItemFn = syn::parse_str(src).unwrap();
+        let statics = statics.iter().map(|s| s.to_string()).collect();
+        is_pure(&func.sig, &func.block, &statics)
+    }
+
+    #[test]
+    fn test_purity_detection() {
+        assert!(purity_of(
+            "fn add(a: u32, b: u32) -> u32 { let sum = a + b; sum.max(1) }",
+            &[]
+        ));
+        assert!(purity_of("fn len(s: &str) -> usize { s.len() }", &[]));
+
+        assert!(!purity_of("fn push(v: &mut Vec<u8>) { v.push(1) }", &[]));
+        assert!(!purity_of(
+            "fn load(p: &str) -> String { std::fs::read_to_string(p).unwrap() }",
+            &[]
+        ));
+        assert!(!purity_of("fn log(s: &str) { println!(\"{}\", s) }", &[]));
+        assert!(!purity_of("fn next() -> u32 { COUNTER + 1 }", &["COUNTER"]));
+        assert!(!purity_of(
+            "fn now() -> u64 { Instant::now().elapsed().as_secs() }",
+            &[]
+        ));
+    }
+}
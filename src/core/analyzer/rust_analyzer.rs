@@ -1,13 +1,17 @@
-use syn::{File, Item, FnArg, Pat, Type};
+use syn::{File, Item, ItemFn, ItemImpl, ImplItem, ImplItemFn, FnArg, Pat, Type};
 use quote::ToTokens;
 use walkdir::WalkDir;
-use glob::Pattern;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use crate::config::Config;
-use crate::core::models::{FunctionInfo, ParamInfo, ProjectInfo, TypeIntern};
-use crate::error::Result;
+use crate::core::analyzer::{called_paths, cyclomatic_complexity, is_pure, ModuleTree};
+use crate::core::models::{
+    FunctionInfo, GenericKind, GenericParam, ParamInfo, ProjectInfo, TypeIntern, SCHEMA_VERSION,
+};
+use crate::error::{AutoTestError, Result};
+use crate::utils::log::status;
 
 /// Analyze a single Rust file and return public functions with parameters & return types.
 pub fn analyze_rust_file(file_path: &str) -> Vec<FunctionInfo> {
@@ -18,11 +22,12 @@ pub fn analyze_rust_file(file_path: &str) -> Vec<FunctionInfo> {
         .unwrap_or_else(|_| panic!("Failed to parse rust file: {}", file_path));
 
     let mut functions = Vec::new();
+    let statics = static_names(&ast);
 
     for item in ast.items {
         if let Item::Fn(func) = item {
-            // keep only pub functions
-            if func.vis.to_token_stream().to_string() != "pub" {
+            // keep only pub functions outside test code
+            if func.vis.to_token_stream().to_string() != "pub" || is_test_only(&func.attrs) {
                 continue;
             }
 
@@ -41,13 +46,7 @@ pub fn analyze_rust_file(file_path: &str) -> Vec<FunctionInfo> {
                             _ => "_".to_string(),
                         };
                         // extract type as token string with interning
-                        let typ_str = match &*pat_type.ty {
-                            Type::Reference(r) => {
-                                // keep the & prefix for reference types
-                                format!("&{}", r.elem.to_token_stream())
-                            }
-                            other => other.to_token_stream().to_string(),
-                        };
+                        let typ_str = param_type(&pat_type.ty);
                         params.push(ParamInfo { name, typ: TypeIntern::new(&typ_str) });
                     }
                 }
@@ -59,12 +58,30 @@ pub fn analyze_rust_file(file_path: &str) -> Vec<FunctionInfo> {
                 syn::ReturnType::Type(_, ty) => ty.to_token_stream().to_string(),
             };
 
+            let (generics, where_predicates) = generic_params(&func.sig.generics);
             functions.push(FunctionInfo {
                 name: func.sig.ident.to_string(),
                 params,
                 returns: TypeIntern::new(&returns_str),
                 file: file_path.to_string(),
                 is_async: func.sig.asyncness.is_some(),
+                is_const: func.sig.constness.is_some(),
+                cfg_features: cfg_features(&func.attrs),
+                line: func.sig.fn_token.span.start().line,
+                end_line: func.block.brace_token.span.close().end().line,
+                complexity: cyclomatic_complexity(&func.block),
+                is_pure: is_pure(&func.sig, &func.block, &statics),
+                surviving_mutants: Vec::new(),
+                docs: doc_comment(&func.attrs),
+                attrs: attributes(&func.attrs),
+                module_path: None,
+                public_path: None,
+                is_private: false,
+                calls: called_paths(&func.block),
+                self_type: None,
+                trait_path: None,
+                generics,
+                where_predicates,
             });
         }
     }
@@ -87,6 +104,7 @@ pub fn analyze_rust_project(root: &str) -> ProjectInfo {
     }
 
     ProjectInfo {
+        schema_version: SCHEMA_VERSION,
         language: "rust".into(),
         root: root.into(),
         functions: all_functions,
@@ -95,119 +113,299 @@ pub fn analyze_rust_project(root: &str) -> ProjectInfo {
 
 /// Check if a file path should be skipped based on configuration
 pub fn should_skip_file(file_path: &Path, config: &Config) -> bool {
-    let path_str = file_path.to_string_lossy();
-
     // Skip standard ignored paths
-    if is_standard_ignored_path(file_path) {
-        return true;
-    }
+    is_standard_ignored_path(file_path) || matches_skip_pattern(file_path, config)
+}
+
+/// Check if a file path matches one of the configured `skip_patterns`
+///
+/// This compiles the patterns on every call; checking many paths, compile them
+/// once with [`SkipPatterns::new`].
+pub fn matches_skip_pattern(file_path: &Path, config: &Config) -> bool {
+    SkipPatterns::new(&config.skip_patterns).matches(file_path)
+}
+
+/// `skip_patterns` compiled into one matcher, so that a walk over tens of
+/// thousands of files doesn't recompile every pattern for every file.
+#[derive(Debug, Clone)]
+pub struct SkipPatterns {
+    set: GlobSet,
+}
 
-    // Skip configured patterns
-    for skip_pattern in &config.skip_patterns {
-        if let Ok(pattern) = Pattern::new(skip_pattern) {
-            if pattern.matches(&path_str) {
-                return true;
+impl SkipPatterns {
+    /// Compile the patterns; an invalid pattern matches nothing.
+    pub fn new(patterns: &[String]) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            if let Ok(glob) = Glob::new(pattern) {
+                builder.add(glob);
             }
         }
+        Self { set: builder.build().unwrap_or_else(|_| GlobSet::empty()) }
     }
 
-    false
+    /// Whether the path matches any of the patterns.
+    ///
+    /// The path is matched with `/` separators whatever the platform, so the same
+    /// patterns work for `src\gen\x.rs` on Windows.
+    pub fn matches(&self, path: &Path) -> bool {
+        !self.set.is_empty() && self.set.is_match(path.to_string_lossy().replace('\\', "/"))
+    }
 }
 
-/// Check if a path is in standard ignored locations
+/// Check if a path is in standard ignored locations: a `target`, `.git`,
+/// `node_modules`, `build` or `dist` directory
 pub fn is_standard_ignored_path(path: &Path) -> bool {
-    let path_str = path.to_string_lossy();
-    path_str.contains("/target/") ||
-    path_str.contains("/.git/") ||
-    path_str.contains("/node_modules/") ||
-    path.starts_with(".") ||
-    path_str.contains("/build/") ||
-    path_str.contains("/dist/")
+    let segments = path_segments(path);
+    let Some((_, dirs)) = segments.split_last() else {
+        return false;
+    };
+    dirs.iter()
+        .any(|dir| matches!(dir.as_str(), "target" | ".git" | "node_modules" | "build" | "dist"))
 }
 
-/// Walk project root with filtering and analyze files respecting config
-pub fn analyze_rust_project_filtered(project_root: &Path, config: &Config) -> Result<ProjectInfo> {
-    let mut all_functions = Vec::new();
-    let mut processed_files = HashSet::new();
+/// The names a path is made of, without its root, prefix or `.` components:
+/// `["src", "a", "mod.rs"]` for `./src/a/mod.rs`. Both `/` and `\` separate
+/// names, so a path recorded on Windows splits the same on every platform.
+pub(crate) fn path_segments(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            std::path::Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .flat_map(|name| {
+            name.split('\\')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Rust files of a project that analysis looks at: `.gitignore` is respected
+/// unless disabled, and `skip_patterns`, standard ignored paths and the
+/// directories tests are generated into are left out.
+pub fn project_rust_files(project_root: &Path, config: &Config) -> Vec<PathBuf> {
+    let output_dirs = output_dirs(project_root, config);
+    let skip_patterns = SkipPatterns::new(&config.skip_patterns);
+    discover_files(project_root, config, "rs")
+        .into_iter()
+        .filter(|path| {
+            // Ignored directories count inside the project only, wherever it's checked out
+            let relative = path.strip_prefix(project_root).unwrap_or(path);
+            !is_standard_ignored_path(relative)
+                && !skip_patterns.matches(path)
+                && !output_dirs.iter().any(|dir| path.starts_with(dir))
+        })
+        .collect()
+}
 
-    let walker: Vec<PathBuf> = if config.respect_gitignore {
+/// Files under `root` with the given extension, in path order.
+///
+/// `.gitignore` is respected unless disabled. Symbolic links are skipped unless
+/// `filesystem.follow_symlinks` is set; links that loop back to an ancestor are
+/// reported and not entered. A file reached through several links, hard links
+/// included, is returned once, under the first path that reaches it.
+pub fn discover_files(root: &Path, config: &Config, extension: &str) -> Vec<PathBuf> {
+    let follow = config.filesystem.follow_symlinks;
+    let entries: Vec<(PathBuf, bool)> = if config.respect_gitignore {
         // Use ignore crate to respect .gitignore
-        WalkBuilder::new(project_root)
+        WalkBuilder::new(root)
             .hidden(false) // Don't skip hidden files by default
             .git_ignore(true)
             .git_global(true)
+            .follow_links(follow)
+            .sort_by_file_name(|a, b| a.cmp(b))
             .build()
-            .filter_map(|e| e.ok())
-            .map(|e| e.path().to_path_buf())
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some((entry.path().to_path_buf(), entry.path_is_symlink())),
+                Err(e) => {
+                    if is_symlink_loop(&e) {
+                        status!("Warning: Skipping symlink loop: {}", e);
+                    }
+                    None
+                }
+            })
             .collect()
     } else {
         // Use walkdir without gitignore
-        WalkDir::new(project_root)
+        WalkDir::new(root)
+            .follow_links(follow)
+            .sort_by_file_name()
             .into_iter()
-            .filter_map(|e| e.ok())
-            .map(|e| e.path().to_path_buf())
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some((entry.path().to_path_buf(), entry.path_is_symlink())),
+                Err(e) => {
+                    if e.loop_ancestor().is_some() {
+                        status!("Warning: Skipping symlink loop: {}", e);
+                    }
+                    None
+                }
+            })
             .collect()
     };
 
-    for entry in walker {
-        let path = &entry;
+    let mut seen = HashSet::new();
+    entries
+        .into_iter()
+        .filter(|(path, is_symlink)| {
+            (follow || !is_symlink)
+                && path.is_file()
+                && path.extension().and_then(|s| s.to_str()) == Some(extension)
+        })
+        // The same file through another link or hard link is analyzed once
+        .filter(|(path, _)| file_identity(path).is_none_or(|id| seen.insert(id)))
+        .map(|(path, _)| path)
+        .collect()
+}
 
-        // Skip directories
-        if path.is_dir() {
-            continue;
-        }
+/// Whether a walk error is a symbolic link pointing back to one of its ancestors.
+fn is_symlink_loop(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_symlink_loop(err),
+        _ => false,
+    }
+}
 
-        // Skip non-Rust files
-        if path.extension().and_then(|s| s.to_str()) != Some("rs") {
-            continue;
-        }
+/// Device and inode of the file a path leads to.
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
 
-        // Skip based on config
-        if should_skip_file(path, config) {
-            continue;
-        }
+/// The canonical path of a file; hard links can't be told apart here.
+#[cfg(not(unix))]
+fn file_identity(path: &Path) -> Option<PathBuf> {
+    path.canonicalize().ok()
+}
+
+/// Directories generated tests are written to: `output_dir` and the output
+/// directories of `[modules]` sections. One that is the project root itself is
+/// left out, as it would exclude every file.
+fn output_dirs(project_root: &Path, config: &Config) -> Vec<PathBuf> {
+    std::iter::once(config.output_dir.as_str())
+        .chain(config.modules.values().filter_map(|m| m.output_dir.as_deref()))
+        .filter(|dir| Path::new(dir).components().any(|c| c != std::path::Component::CurDir))
+        .map(|dir| project_root.join(dir))
+        .collect()
+}
 
-        // Avoid processing the same file multiple times
+/// Walk project root with filtering and analyze files respecting config
+pub fn analyze_rust_project_filtered(project_root: &Path, config: &Config) -> Result<ProjectInfo> {
+    let mut all_functions = Vec::new();
+
+    for path in project_rust_files(project_root, config) {
         let path_str = path.to_string_lossy().to_string();
-        if processed_files.contains(&path_str) {
-            continue;
-        }
-        processed_files.insert(path_str.clone());
-
-        // Analyze the file
-        match std::fs::read_to_string(path) {
-            Ok(content) => {
-                match syn::parse_file(&content) {
-                    Ok(ast) => {
-                        let functions = extract_functions_from_ast(&ast, &path_str, config);
-                        all_functions.extend(functions);
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to parse {}: {}", path_str, e);
-                        // Continue processing other files
-                    }
-                }
+
+        // The source is dropped as soon as it's parsed and the AST once its
+        // functions are extracted, so only one file is held at a time
+        let parsed = SourceText::load(&path, config)
+            .and_then(|source| source.as_str().map(syn::parse_file));
+        match parsed {
+            Ok(Ok(ast)) => {
+                let functions = extract_functions_from_ast(&ast, &path_str, config);
+                all_functions.extend(functions);
+            }
+            Ok(Err(e)) => {
+                status!("Warning: Failed to parse {}: {}", path_str, e);
+                // Continue processing other files
             }
             Err(e) => {
-                eprintln!("Warning: Could not read {}: {}", path_str, e);
+                status!("Warning: Could not read {}: {}", path_str, e);
                 // Continue processing other files
             }
         }
     }
 
+    let modules = ModuleTree::build(project_root);
+    for func in &mut all_functions {
+        func.module_path = modules.module_path(Path::new(&func.file)).map(String::from);
+        func.public_path = func
+            .module_path
+            .as_deref()
+            .filter(|_| !modules.is_binary(Path::new(&func.file)) && func.self_type.is_none())
+            .and_then(|module| modules.public_path(module, &func.name));
+    }
+
     Ok(ProjectInfo {
+        schema_version: SCHEMA_VERSION,
         language: "rust".into(),
         root: project_root.to_string_lossy().to_string(),
         functions: all_functions,
     })
 }
 
+/// The contents of a source file: read into memory, or memory-mapped when it's
+/// at least `performance.mmap_threshold_kb` so that multi-megabyte generated
+/// sources are paged in from the file instead of copied onto the heap.
+enum SourceText {
+    Read(String),
+    Mapped(memmap2::Mmap),
+}
+
+impl SourceText {
+    fn load(path: &Path, config: &Config) -> std::io::Result<Self> {
+        let mut file = std::fs::File::open(path)?;
+        if let Some(threshold_kb) = config.performance.mmap_threshold_kb {
+            if file.metadata()?.len() >= threshold_kb.saturating_mul(1024) {
+                // SAFETY: the map is only read while the file is parsed; as with any
+                // mapping, truncating the file meanwhile may crash the process,
+                // which is why mapping is opt-in
+                let map = unsafe { memmap2::Mmap::map(&file)? };
+                return Ok(Self::Mapped(map));
+            }
+        }
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut file, &mut content)?;
+        Ok(Self::Read(content))
+    }
+
+    /// The text, or an error like reading the file gives when it isn't UTF-8.
+    fn as_str(&self) -> std::io::Result<&str> {
+        match self {
+            Self::Read(content) => Ok(content),
+            Self::Mapped(map) => std::str::from_utf8(map)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        }
+    }
+}
+
+/// Analyze Rust source that isn't read from disk, e.g. piped through stdin.
+///
+/// # Arguments
+///
+/// * `content` - The source code
+/// * `file_path` - Name recorded as the functions' file
+/// * `config` - Configuration for visibility and skip filtering
+///
+/// # Returns
+///
+/// The functions found, or a parse error
+pub fn analyze_rust_source(content: &str, file_path: &str, config: &Config) -> Result<Vec<FunctionInfo>> {
+    let ast = syn::parse_file(content).map_err(|e| AutoTestError::ParseFailed {
+        path: PathBuf::from(file_path),
+        source: e,
+    })?;
+    Ok(extract_functions_from_ast(&ast, file_path, config))
+}
+
 /// Extract functions from AST with configuration filtering
 fn extract_functions_from_ast(ast: &File, file_path: &str, config: &Config) -> Vec<FunctionInfo> {
     let mut functions = Vec::new();
+    let statics = static_names(ast);
 
     for item in &ast.items {
         if let Item::Fn(func) = item {
+            // Tests and test helpers aren't part of the API under test
+            if is_test_only(&func.attrs) {
+                continue;
+            }
+
             // Check visibility based on config
             let is_public = func.vis.to_token_stream().to_string() == "pub";
             if !is_public && !config.include_private {
@@ -220,49 +418,390 @@ fn extract_functions_from_ast(ast: &File, file_path: &str, config: &Config) -> V
                 continue;
             }
 
-            // Extract parameters
-            let mut params: Vec<ParamInfo> = Vec::new();
-            for input in func.sig.inputs.iter() {
-                match input {
-                    FnArg::Receiver(_) => {
-                        params.push(ParamInfo {
-                            name: "self".into(),
-                            typ: "Self".into(),
-                        });
-                    }
-                    FnArg::Typed(pat_type) => {
-                        let name = match &*pat_type.pat {
-                            Pat::Ident(ident) => ident.ident.to_string(),
-                            _ => "_".to_string(),
-                        };
+            functions.push(function_info(func, file_path, &statics));
+        } else if let Item::Impl(imp) = item {
+            if is_test_only(&imp.attrs) {
+                continue;
+            }
+            functions.extend(async_trait_methods(imp, file_path, config, &statics));
+        }
+    }
 
-                        let typ_str = match &*pat_type.ty {
-                            Type::Reference(r) => {
-                                format!("&{}", r.elem.to_token_stream())
-                            }
-                            other => other.to_token_stream().to_string(),
-                        };
+    functions
+}
 
-                        params.push(ParamInfo { name, typ: TypeIntern::new(&typ_str) });
-                    }
-                }
+/// Analyze a single function item, e.g. one an IDE plugin has already parsed.
+///
+/// Unlike project analysis this applies no visibility or skip filtering. Statics
+/// of the surrounding file are unknown, so writes to them don't make the function
+/// impure.
+///
+/// # Arguments
+///
+/// * `func` - The parsed function
+/// * `file_path` - Name recorded as the function's file
+pub fn analyze_function(func: &ItemFn, file_path: &str) -> FunctionInfo {
+    function_info(func, file_path, &HashSet::new())
+}
+
+/// Signature and body facts of one function.
+fn function_info(func: &ItemFn, file_path: &str, statics: &HashSet<String>) -> FunctionInfo {
+    // Extract parameters
+    let mut params: Vec<ParamInfo> = Vec::new();
+    for input in func.sig.inputs.iter() {
+        match input {
+            FnArg::Receiver(_) => {
+                params.push(ParamInfo {
+                    name: "self".into(),
+                    typ: "Self".into(),
+                });
             }
+            FnArg::Typed(pat_type) => {
+                let name = match &*pat_type.pat {
+                    Pat::Ident(ident) => ident.ident.to_string(),
+                    _ => "_".to_string(),
+                };
 
-            // Extract return type with interning
-            let returns_str = match &func.sig.output {
-                syn::ReturnType::Default => "()".to_string(),
-                syn::ReturnType::Type(_, ty) => ty.to_token_stream().to_string(),
+                let typ_str = param_type(&pat_type.ty);
+
+                params.push(ParamInfo { name, typ: TypeIntern::new(&typ_str) });
+            }
+        }
+    }
+
+    // Extract return type with interning; a returned future (e.g. a method
+    // desugared by `#[async_trait]`) makes the function async
+    let (returns_str, returns_future) = match &func.sig.output {
+        syn::ReturnType::Default => ("()".to_string(), false),
+        syn::ReturnType::Type(_, ty) => match future_output(ty) {
+            Some(output) => (output.to_token_stream().to_string(), true),
+            None => (ty.to_token_stream().to_string(), false),
+        },
+    };
+
+    let (generics, where_predicates) = generic_params(&func.sig.generics);
+    FunctionInfo {
+        name: func.sig.ident.to_string(),
+        params,
+        returns: TypeIntern::new(&returns_str),
+        file: file_path.to_string(),
+        is_async: func.sig.asyncness.is_some() || returns_future,
+        is_const: func.sig.constness.is_some(),
+        cfg_features: cfg_features(&func.attrs),
+        line: func.sig.fn_token.span.start().line,
+        end_line: func.block.brace_token.span.close().end().line,
+        complexity: cyclomatic_complexity(&func.block),
+        is_pure: is_pure(&func.sig, &func.block, statics),
+        surviving_mutants: Vec::new(),
+        docs: doc_comment(&func.attrs),
+        attrs: attributes(&func.attrs),
+        module_path: None,
+        public_path: None,
+        is_private: !matches!(func.vis, syn::Visibility::Public(_)),
+        calls: called_paths(&func.block),
+        self_type: None,
+        trait_path: None,
+        generics,
+        where_predicates,
+    }
+}
+
+/// Methods of an `#[async_trait]` impl, or of a trait impl `async_trait` has
+/// already desugared (as in `--expand` output), all marked async. The receiver
+/// becomes the first parameter, typed with the implementing type. Other impls
+/// yield nothing.
+pub(crate) fn async_trait_methods(
+    imp: &ItemImpl,
+    file_path: &str,
+    config: &Config,
+    statics: &HashSet<String>,
+) -> Vec<FunctionInfo> {
+    let Some((_, trait_path, _)) = &imp.trait_ else {
+        return Vec::new();
+    };
+    let methods: Vec<&ImplItemFn> = imp
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .collect();
+    let attributed = imp
+        .attrs
+        .iter()
+        .any(|a| a.path().segments.last().is_some_and(|s| s.ident == "async_trait"));
+    let desugared = methods.iter().any(|m| {
+        matches!(&m.sig.output, syn::ReturnType::Type(_, ty) if future_output(ty).is_some())
+    });
+    if !attributed && !desugared {
+        return Vec::new();
+    }
+
+    let self_type = imp.self_ty.to_token_stream().to_string();
+    methods
+        .into_iter()
+        .filter(|m| !is_test_only(&m.attrs) && !config.should_skip_function(&m.sig.ident.to_string()))
+        .map(|method| {
+            let func = ItemFn {
+                attrs: method.attrs.clone(),
+                vis: method.vis.clone(),
+                sig: method.sig.clone(),
+                block: Box::new(method.block.clone()),
             };
+            let mut info = function_info(&func, file_path, statics);
+            info.is_async |= attributed;
+            if let Some(FnArg::Receiver(receiver)) = method.sig.inputs.first() {
+                let typ = match (&receiver.reference, &receiver.mutability) {
+                    (Some(_), Some(_)) => format!("&mut {}", self_type),
+                    (Some(_), None) => format!("&{}", self_type),
+                    (None, _) => self_type.clone(),
+                };
+                info.params[0].typ = TypeIntern::new(&typ);
+            }
+            info.self_type = Some(self_type.clone());
+            info.trait_path = Some(trait_path.to_token_stream().to_string());
+            info
+        })
+        .collect()
+}
 
-            functions.push(FunctionInfo {
-                name: func_name,
-                params,
-                returns: TypeIntern::new(&returns_str),
-                file: file_path.to_string(),
-                is_async: func.sig.asyncness.is_some(),
-            });
+/// A parameter type as written, without the lifetime of a reference: `&T` or
+/// `&mut T`.
+pub(crate) fn param_type(ty: &Type) -> String {
+    match ty {
+        Type::Reference(r) if r.mutability.is_some() => format!("&mut {}", r.elem.to_token_stream()),
+        Type::Reference(r) => format!("&{}", r.elem.to_token_stream()),
+        other => other.to_token_stream().to_string(),
+    }
+}
+
+/// `T` of a returned `impl Future<Output = T>` or `Pin<Box<dyn Future<Output = T>>>`.
+fn future_output(ty: &Type) -> Option<&Type> {
+    let bounds = match ty {
+        Type::ImplTrait(t) => &t.bounds,
+        Type::Path(path) => {
+            let boxed = single_type_argument(&path.path, "Pin")?;
+            let Type::Path(boxed) = boxed else {
+                return None;
+            };
+            let Type::TraitObject(object) = single_type_argument(&boxed.path, "Box")? else {
+                return None;
+            };
+            &object.bounds
+        }
+        _ => return None,
+    };
+    bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(bound) = bound else {
+            return None;
+        };
+        let last = bound.path.segments.last()?;
+        let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+            return None;
+        };
+        if last.ident != "Future" {
+            return None;
+        }
+        args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::AssocType(assoc) if assoc.ident == "Output" => Some(&assoc.ty),
+            _ => None,
+        })
+    })
+}
+
+/// The type argument of `path` when its last segment is `name<T>`.
+fn single_type_argument<'a>(path: &'a syn::Path, name: &str) -> Option<&'a Type> {
+    let last = path.segments.last().filter(|s| s.ident == name)?;
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Generic parameters of a signature with the bounds of `where` predicates on a
+/// single parameter merged in, and the remaining predicates as written.
+fn generic_params(generics: &syn::Generics) -> (Vec<GenericParam>, Vec<String>) {
+    let tokens = |bound: &dyn ToTokens| bound.to_token_stream().to_string();
+    let mut params: Vec<GenericParam> = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Lifetime(l) => GenericParam {
+                name: l.lifetime.to_string(),
+                kind: GenericKind::Lifetime,
+                bounds: l.bounds.iter().map(|b| tokens(b)).collect(),
+            },
+            syn::GenericParam::Type(t) => GenericParam {
+                name: t.ident.to_string(),
+                kind: GenericKind::Type,
+                bounds: t.bounds.iter().map(|b| tokens(b)).collect(),
+            },
+            syn::GenericParam::Const(c) => GenericParam {
+                name: c.ident.to_string(),
+                kind: GenericKind::Const,
+                bounds: vec![tokens(&c.ty)],
+            },
+        })
+        .collect();
+
+    let mut other = Vec::new();
+    for predicate in generics.where_clause.iter().flat_map(|w| &w.predicates) {
+        let (name, bounds): (String, Vec<String>) = match predicate {
+            syn::WherePredicate::Type(p) if p.lifetimes.is_none() => {
+                (tokens(&p.bounded_ty), p.bounds.iter().map(|b| tokens(b)).collect())
+            }
+            syn::WherePredicate::Lifetime(p) => {
+                (p.lifetime.to_string(), p.bounds.iter().map(|b| tokens(b)).collect())
+            }
+            _ => (String::new(), Vec::new()),
+        };
+        match params.iter_mut().find(|p| p.kind != GenericKind::Const && p.name == name) {
+            Some(param) => param.bounds.extend(bounds),
+            None => other.push(tokens(predicate)),
         }
     }
+    (params, other)
+}
 
-    functions
+/// Attributes other than doc comments, written as `#[path(args)]`.
+fn attributes(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|a| !a.path().is_ident("doc"))
+        .map(|a| {
+            let path = a
+                .path()
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            match &a.meta {
+                syn::Meta::Path(_) => format!("#[{}]", path),
+                syn::Meta::List(list) => format!("#[{}({})]", path, list.tokens),
+                syn::Meta::NameValue(nv) => {
+                    format!("#[{} = {}]", path, nv.value.to_token_stream())
+                }
+            }
+        })
+        .collect()
+}
+
+/// Text of the `///` (or `#[doc = "..."]`) comments among `attrs`, one line per
+/// attribute with the space after `///` removed.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.split('\n')
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let docs = lines.join("\n");
+    let docs = docs.trim();
+    (!docs.is_empty()).then(|| docs.to_string())
+}
+
+/// Names of the `static` items declared at the top level of a file.
+fn static_names(ast: &File) -> HashSet<String> {
+    ast.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Static(s) => Some(s.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check whether the crate at `project_root` is `#![no_std]`.
+///
+/// Looks at the inner attributes of `src/lib.rs` (or `src/main.rs`), including
+/// conditional forms like `#![cfg_attr(not(feature = "std"), no_std)]`.
+pub fn is_no_std_crate(project_root: &Path) -> bool {
+    let root_file = ["src/lib.rs", "src/main.rs"]
+        .iter()
+        .map(|f| project_root.join(f))
+        .find(|p| p.exists());
+
+    let Some(root_file) = root_file else {
+        return false;
+    };
+    let Ok(content) = std::fs::read_to_string(&root_file) else {
+        return false;
+    };
+    let Ok(ast) = syn::parse_file(&content) else {
+        return false;
+    };
+
+    ast.attrs.iter().any(|attr| {
+        if attr.path().is_ident("no_std") {
+            return true;
+        }
+        attr.path().is_ident("cfg_attr")
+            && attr
+                .meta
+                .to_token_stream()
+                .into_iter()
+                .any(|tt| tt.to_string().contains("no_std"))
+    })
+}
+
+/// Whether an item only exists in test builds: `#[cfg(test)]`, or a test such
+/// as `#[test]` or `#[tokio::test]`.
+fn is_test_only(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().segments.last().is_some_and(|s| s.ident == "test")
+            || (attr.path().is_ident("cfg")
+                && attr.parse_args::<syn::Ident>().is_ok_and(|ident| ident == "test"))
+    })
+}
+
+/// Collect the Cargo features a `#[cfg(...)]` attribute requires.
+///
+/// Only features that must be enabled are collected: `feature = "x"` at the top
+/// level or inside `all(...)`. Features under `any(...)`/`not(...)` don't make
+/// a single feature set mandatory and are ignored.
+fn cfg_features(attrs: &[syn::Attribute]) -> Vec<String> {
+    fn collect(meta: &syn::Meta, features: &mut Vec<String>) {
+        match meta {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("feature") => {
+                if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = &nv.value {
+                    if !features.contains(&s.value()) {
+                        features.push(s.value());
+                    }
+                }
+            }
+            syn::Meta::List(list) if list.path.is_ident("all") => {
+                if let Ok(nested) = list.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                ) {
+                    for meta in &nested {
+                        collect(meta, features);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut features = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("cfg")) {
+        if let Ok(meta) = attr.parse_args::<syn::Meta>() {
+            collect(&meta, &mut features);
+        }
+    }
+    features
 }
@@ -0,0 +1,119 @@
+//! # Const Evaluation Tests
+//!
+//! A `const fn` that quietly loses its `const` still passes every runtime
+//! test, while callers using it in `const` items stop compiling. Const
+//! functions whose arguments can be written as literals get an extra test that
+//! evaluates the call in an inline `const` block and compares it with the
+//! runtime result.
+
+use crate::core::models::FunctionInfo;
+
+/// Generator for tests evaluating `const fn`s at compile time.
+pub struct ConstGenerator;
+
+impl ConstGenerator {
+    /// Whether `func` can get a const evaluation test: it's a free `const fn`
+    /// whose parameters all take literals.
+    pub fn supports(func: &FunctionInfo) -> bool {
+        func.is_const
+            && !func.is_async
+            && func.self_type.is_none()
+            && func
+                .params
+                .iter()
+                .all(|p| Self::literal_for(p.typ.as_str()).is_some())
+    }
+
+    /// Render a test evaluating `func` in a `const` block. Primitive results are
+    /// compared with a runtime call; others only have to evaluate.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - A function for which [`ConstGenerator::supports`] holds
+    /// * `test_stem` - Module-qualified name used in the test name
+    /// * `call_path` - Path used to call the function from the test
+    pub fn render(func: &FunctionInfo, test_stem: &str, call_path: &str) -> String {
+        let args: Vec<&str> = func
+            .params
+            .iter()
+            .map(|p| Self::literal_for(p.typ.as_str()).unwrap_or("()"))
+            .collect();
+        let call = format!("{}({})", call_path, args.join(", "));
+        let returns: String = func.returns.as_str().split_whitespace().collect();
+        let check = if Self::literal_for(&returns).is_some() {
+            format!("assert_eq!(result, {});", call)
+        } else {
+            "let _ = result;".to_string()
+        };
+
+        format!(
+            "    #[test]
+    fn test_{name}_const() {{
+        // Stops compiling if `{function}` is no longer a `const fn`
+        let result = const {{ {call} }};
+        {check}
+    }}",
+            name = test_stem,
+            function = func.name,
+            call = call,
+            check = check,
+        )
+    }
+
+    /// Literal argument for a parameter type usable in const context.
+    fn literal_for(type_str: &str) -> Option<&'static str> {
+        let compact: String = type_str.chars().filter(|c| !c.is_whitespace()).collect();
+        let literal = match compact.as_str() {
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+            | "u128" | "usize" => "0",
+            "f32" | "f64" => "0.0",
+            "bool" => "false",
+            "char" => "'a'",
+            "&str" | "&'staticstr" => "\"test\"",
+            "()" => "()",
+            _ => return None,
+        };
+        Some(literal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::ParamInfo;
+
+    fn const_fn(params: &[&str], returns: &str) -> FunctionInfo {
+        FunctionInfo {
+            name: "scale".to_string(),
+            params: params
+                .iter()
+                .map(|typ| ParamInfo {
+                    name: "x".to_string(),
+                    typ: (*typ).into(),
+                })
+                .collect(),
+            returns: returns.into(),
+            is_const: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_const_fn_is_evaluated_in_const_block() {
+        let scale = const_fn(&["u32", "bool"], "u64");
+        assert!(ConstGenerator::supports(&scale));
+        let rendered = ConstGenerator::render(&scale, "math_scale", "math::scale");
+        assert!(rendered.contains("fn test_math_scale_const()"));
+        assert!(rendered.contains("let result = const { math::scale(0, false) };"));
+        assert!(rendered.contains("assert_eq!(result, math::scale(0, false));"));
+
+        let point = const_fn(&["f64"], "Point");
+        assert!(ConstGenerator::render(&point, "origin", "origin").contains("let _ = result;"));
+
+        assert!(!ConstGenerator::supports(&const_fn(&["&Config"], "u32")));
+        assert!(!ConstGenerator::supports(&FunctionInfo {
+            is_const: false,
+            ..scale
+        }));
+    }
+}
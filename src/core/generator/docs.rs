@@ -0,0 +1,103 @@
+//! # Doc Comments
+//!
+//! Turns the doc comment of an analyzed function into comments above its
+//! generated test: a one-line summary, and TODO hints for the conditions its
+//! `# Errors` and `# Panics` sections describe, so whoever fills in the stub
+//! knows which cases the function promises to handle.
+
+/// Longest summary or hint kept, in characters; longer text is cut at a word.
+const MAX_LEN: usize = 100;
+
+/// First paragraph of `docs` on one line, shortened to [`MAX_LEN`].
+pub fn summary(docs: &str) -> Option<String> {
+    paragraph(docs.lines())
+}
+
+/// First paragraph of the `# <name>` section of `docs` on one line, e.g. of
+/// `# Errors`.
+pub fn section(docs: &str, name: &str) -> Option<String> {
+    let mut lines = docs.lines();
+    lines
+        .by_ref()
+        .find(|line| heading(line).is_some_and(|h| h.eq_ignore_ascii_case(name)))?;
+    paragraph(lines.skip_while(|line| line.trim().is_empty()))
+}
+
+/// Comment lines for the test of a function documented with `docs`, indented
+/// like the tests in a test file.
+pub fn test_comments(docs: &str) -> String {
+    let mut comments = Vec::new();
+    if let Some(summary) = summary(docs) {
+        comments.push(summary);
+    }
+    if let Some(errors) = section(docs, "Errors") {
+        comments.push(format!("TODO: cover the documented errors: {}", errors));
+    }
+    if let Some(panics) = section(docs, "Panics") {
+        comments.push(format!(
+            "TODO: cover the documented panics with #[should_panic]: {}",
+            panics
+        ));
+    }
+    comments
+        .iter()
+        .map(|comment| format!("    // {}\n", comment))
+        .collect()
+}
+
+/// Text of a Markdown heading line, without the `#`s.
+fn heading(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.starts_with('#')
+        .then(|| line.trim_start_matches('#').trim())
+}
+
+/// Lines up to the first blank line, heading or code block, joined and shortened.
+fn paragraph<'a>(lines: impl Iterator<Item = &'a str>) -> Option<String> {
+    let words: Vec<&str> = lines
+        .take_while(|line| {
+            !line.trim().is_empty() && heading(line).is_none() && !line.trim().starts_with("```")
+        })
+        .flat_map(str::split_whitespace)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut text = String::new();
+    for word in words {
+        if text.chars().count() + word.chars().count() + 1 > MAX_LEN {
+            text.push_str("...");
+            break;
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_summarize_docs_and_hint_at_errors_and_panics() {
+        let docs = "Parses a port number\nfrom a string.\n\nMore details.\n\n\
+                    # Errors\n\nReturns `ParseError` if `s` is not a number.\n\n\
+                    # Panics\nPanics if `s` is empty.\n\n```\nlet p = parse(\"80\");\n```";
+        assert_eq!(
+            test_comments(docs),
+            "    // Parses a port number from a string.\n\
+             \x20   // TODO: cover the documented errors: Returns `ParseError` if `s` is not a number.\n\
+             \x20   // TODO: cover the documented panics with #[should_panic]: Panics if `s` is empty.\n"
+        );
+
+        let long = "word ".repeat(40);
+        let summary = summary(&long).unwrap();
+        assert!(summary.ends_with("word..."));
+        assert!(summary.len() <= MAX_LEN + 3);
+        assert_eq!(test_comments("# Examples\n```\nf();\n```"), "");
+    }
+}
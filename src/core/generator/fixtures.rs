@@ -0,0 +1,134 @@
+//! # Shared Fixtures
+//!
+//! Arrange values needed by tests in several generated files are emitted once
+//! into `tests/common/mod.rs` as helper constructors, and each test file calls
+//! them through `mod common;` instead of repeating the setup. Values needed by
+//! several tests of one file get the same kind of helper in that file.
+
+use std::collections::BTreeMap;
+
+/// A helper constructor emitted into the shared fixtures module.
+#[derive(Debug, Clone)]
+pub struct FixtureHelper {
+    /// Name of the helper function, e.g. `sample_config`.
+    pub fn_name: String,
+    /// Type returned by the helper, as written in the analyzed signature.
+    pub type_name: String,
+    /// Expression producing the value.
+    pub expr: String,
+    /// Defined in the test file using it rather than in `tests/common/mod.rs`.
+    pub local: bool,
+}
+
+/// Set of shared fixtures keyed by the (dereferenced) parameter type.
+#[derive(Debug, Clone, Default)]
+pub struct SharedFixtures {
+    helpers: BTreeMap<String, FixtureHelper>,
+}
+
+impl SharedFixtures {
+    /// Register a helper for `type_name` producing `expr`.
+    pub fn insert(&mut self, type_name: &str, expr: String) {
+        self.add(type_name, expr, false);
+    }
+
+    /// Register a helper for `type_name` producing `expr`, defined in the test
+    /// file itself (see [`SharedFixtures::render_local`]).
+    pub fn insert_local(&mut self, type_name: &str, expr: String) {
+        self.add(type_name, expr, true);
+    }
+
+    fn add(&mut self, type_name: &str, expr: String, local: bool) {
+        let type_name = type_name.trim().to_string();
+        let fn_name = helper_name(&type_name);
+        self.helpers.insert(
+            type_name.clone(),
+            FixtureHelper {
+                fn_name,
+                type_name,
+                expr,
+                local,
+            },
+        );
+    }
+
+    /// Call expression for the helper producing `type_name`, if one exists.
+    pub fn call_for(&self, type_name: &str) -> Option<String> {
+        self.helpers.get(type_name.trim()).map(|helper| {
+            if helper.local {
+                format!("{}()", helper.fn_name)
+            } else {
+                format!("common::{}()", helper.fn_name)
+            }
+        })
+    }
+
+    /// Whether no helpers were registered.
+    pub fn is_empty(&self) -> bool {
+        self.helpers.is_empty()
+    }
+
+    /// Iterate over the registered helpers in type order.
+    pub fn helpers(&self) -> impl Iterator<Item = &FixtureHelper> {
+        self.helpers.values()
+    }
+
+    /// Render the content of `tests/common/mod.rs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_import` - The `use` line importing the crate under test
+    pub fn render_module(&self, crate_import: &str) -> String {
+        let mut content = String::from("//! Shared fixtures for generated tests.\n");
+        content.push_str("#![allow(dead_code)]\n\n");
+        content.push_str(crate_import);
+        content.push_str("\n\n");
+
+        for helper in self.helpers().filter(|helper| !helper.local) {
+            content.push_str(&format!(
+                "pub fn {}() -> {} {{\n    {}\n}}\n\n",
+                helper.fn_name, helper.type_name, helper.expr
+            ));
+        }
+
+        content.truncate(content.trim_end().len());
+        content.push('\n');
+        content
+    }
+
+    /// Render the helpers defined in the test file itself, each followed by a
+    /// blank line; empty without local helpers.
+    pub fn render_local(&self) -> String {
+        self.helpers()
+            .filter(|helper| helper.local)
+            .map(|helper| {
+                format!(
+                    "fn {}() -> {} {{\n    {}\n}}\n\n",
+                    helper.fn_name, helper.type_name, helper.expr
+                )
+            })
+            .collect()
+    }
+}
+
+/// Derive a helper function name from a type, e.g. `Vec < PathBuf >` -> `sample_vec_path_buf`.
+pub fn helper_name(type_name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev: Option<char> = None;
+
+    for ch in type_name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            let word_boundary = ch.is_ascii_uppercase()
+                && prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit());
+            if word_boundary {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        } else if !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+        prev = Some(ch);
+    }
+
+    format!("sample_{}", snake.trim_end_matches('_'))
+}
@@ -0,0 +1,290 @@
+//! # Generic Instantiation
+//!
+//! A generic function can't be called from a test until its parameters are
+//! concrete: `fn parse<'a, T: FromStr>(s: &'a str) -> Option<T>` becomes
+//! `parse::<i32>(s)` with `s: &str` and an `Option<i32>` result. Each type
+//! parameter gets the first of a few common types (`i32`, `String`, `f64`,
+//! `bool`, `char`, `Vec<u8>`) implementing all of its bounds, const parameters
+//! get a small value of their type, and lifetimes become `'_`.
+//!
+//! Only std traits are known: a bound on another trait, or a `where` predicate
+//! on something other than a parameter, leaves the function generic.
+
+use crate::core::models::{FunctionInfo, GenericKind, TypeIntern};
+
+/// Types tried for a type parameter, in order of preference.
+const CANDIDATES: [&str; 6] = ["i32", "String", "f64", "bool", "char", "Vec<u8>"];
+
+/// Traits whose associated types may be projected from a type parameter
+/// (`T::Err`), with the path they're qualified by.
+const PROJECTIONS: [(&str, &str, &[&str]); 8] = [
+    ("FromStr", "std::str::FromStr", &["Err"]),
+    ("Add", "std::ops::Add", &["Output"]),
+    ("Sub", "std::ops::Sub", &["Output"]),
+    ("Mul", "std::ops::Mul", &["Output"]),
+    ("Div", "std::ops::Div", &["Output"]),
+    ("Rem", "std::ops::Rem", &["Output"]),
+    ("Neg", "std::ops::Neg", &["Output"]),
+    (
+        "IntoIterator",
+        "std::iter::IntoIterator",
+        &["Item", "IntoIter"],
+    ),
+];
+
+/// Concrete arguments for the generic parameters of a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instantiation {
+    /// Type and const parameters with their arguments, in declaration order.
+    args: Vec<(String, String)>,
+    /// Projections such as `T::Err`, by parameter and associated type.
+    projections: Vec<(String, String, String)>,
+    lifetimes: Vec<String>,
+}
+
+impl Instantiation {
+    /// Arguments for every generic parameter of `func`; `None` when it has
+    /// none, or one can't be instantiated.
+    pub fn of(func: &FunctionInfo) -> Option<Self> {
+        if func.generics.is_empty() || !func.where_predicates.is_empty() {
+            return None;
+        }
+        let mut instantiation = Self {
+            args: Vec::new(),
+            projections: Vec::new(),
+            lifetimes: Vec::new(),
+        };
+        for param in &func.generics {
+            match param.kind {
+                GenericKind::Lifetime => instantiation.lifetimes.push(param.name.clone()),
+                GenericKind::Const => {
+                    let value = const_value(param.bounds.first()?)?;
+                    instantiation.args.push((param.name.clone(), value));
+                }
+                GenericKind::Type => {
+                    let bounds: Vec<(String, String)> = param
+                        .bounds
+                        .iter()
+                        .filter_map(|bound| split_bound(bound))
+                        .collect();
+                    let ty = CANDIDATES.iter().find(|ty| {
+                        bounds
+                            .iter()
+                            .all(|(name, args)| implements(ty, name, args) == Some(true))
+                    })?;
+                    for (name, _) in &bounds {
+                        if let Some((_, path, items)) = PROJECTIONS
+                            .iter()
+                            .find(|(trait_name, ..)| trait_name == name)
+                        {
+                            for item in items.iter() {
+                                instantiation.projections.push((
+                                    param.name.clone(),
+                                    item.to_string(),
+                                    format!("<{} as {}>::{}", ty, path, item),
+                                ));
+                            }
+                        }
+                    }
+                    instantiation
+                        .args
+                        .push((param.name.clone(), ty.to_string()));
+                }
+            }
+        }
+        Some(instantiation)
+    }
+
+    /// `func` with the arguments substituted into its parameter and return types.
+    pub fn apply(&self, func: &FunctionInfo) -> FunctionInfo {
+        let mut instantiated = func.clone();
+        for param in &mut instantiated.params {
+            param.typ = TypeIntern::new(&self.substitute(param.typ.as_str()));
+        }
+        instantiated.returns = TypeIntern::new(&self.substitute(func.returns.as_str()));
+        instantiated
+    }
+
+    /// Explicit generic arguments for the call, e.g. `::<i32, 4>`; empty when
+    /// the function only has lifetime parameters.
+    pub fn turbofish(&self) -> String {
+        if self.args.is_empty() {
+            return String::new();
+        }
+        let args: Vec<&str> = self.args.iter().map(|(_, arg)| arg.as_str()).collect();
+        format!("::<{}>", args.join(", "))
+    }
+
+    /// Replace the generic parameters in a type as written (`Option < T >`,
+    /// `& 'a [T ; N]`, `Result < T , T :: Err >`).
+    fn substitute(&self, type_str: &str) -> String {
+        let tokens = tokenize(type_str);
+        let mut out = String::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = tokens[i].as_str();
+            let after_path = out.trim_end().ends_with("::");
+            if let Some(lifetime) = token.strip_prefix('\'') {
+                if self.lifetimes.iter().any(|l| l[1..] == *lifetime) {
+                    out.push_str("'_");
+                } else {
+                    out.push_str(token);
+                }
+            } else if let Some((_, arg)) = self
+                .args
+                .iter()
+                .find(|(name, _)| name == token && !after_path)
+            {
+                // `T::Err` names the associated type through T's bound
+                let next =
+                    |from: usize| (from..tokens.len()).find(|&j| !tokens[j].trim().is_empty());
+                let item = next(i + 1)
+                    .filter(|&j| tokens[j] == "::")
+                    .and_then(|j| next(j + 1));
+                let projection = item.and_then(|j| {
+                    self.projections
+                        .iter()
+                        .find(|(param, assoc, _)| param == token && *assoc == tokens[j])
+                        .map(|(.., qualified)| (j, qualified))
+                });
+                match projection {
+                    Some((j, qualified)) => {
+                        out.push_str(qualified);
+                        i = j;
+                    }
+                    None => out.push_str(arg),
+                }
+            } else {
+                out.push_str(token);
+            }
+            i += 1;
+        }
+        out
+    }
+}
+
+/// A type string as identifiers, lifetimes, `::`, whitespace and single
+/// punctuation characters.
+fn tokenize(type_str: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = type_str.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut token = c.to_string();
+        if c == '\'' || c.is_alphanumeric() || c == '_' {
+            while let Some(&next) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_') {
+                    break;
+                }
+                token.push(next);
+                chars.next();
+            }
+        } else if c == ':' && chars.peek() == Some(&':') {
+            token.push(':');
+            chars.next();
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// A bound as its trait's name and generic arguments without whitespace, e.g.
+/// `("Into", "String")` for `Into < String >`. Lifetimes and `?Sized` always
+/// hold and give `None`.
+fn split_bound(bound: &str) -> Option<(String, String)> {
+    let compact: String = bound.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.starts_with('\'') || compact.starts_with('?') {
+        return None;
+    }
+    let (path, args) = match compact.split_once('<') {
+        Some((path, args)) => (path, args.strip_suffix('>').unwrap_or(args)),
+        None => (compact.as_str(), ""),
+    };
+    let name = path.rsplit("::").next().unwrap_or(path);
+    Some((name.to_string(), args.to_string()))
+}
+
+/// Whether `ty`, one of [`CANDIDATES`], implements the std trait `name<args>`;
+/// `None` for traits outside std.
+fn implements(ty: &str, name: &str, args: &str) -> Option<bool> {
+    let numeric = matches!(ty, "i32" | "f64");
+    Some(match name {
+        "Sized" | "Send" | "Sync" | "Unpin" | "Any" | "Clone" | "Debug" | "PartialEq"
+        | "PartialOrd" => true,
+        "Copy" => matches!(ty, "i32" | "f64" | "bool" | "char"),
+        "Eq" | "Ord" | "Hash" => ty != "f64",
+        "Default" => ty != "char",
+        "Display" | "ToString" | "FromStr" => ty != "Vec<u8>",
+        "Add" | "Sub" | "Mul" | "Div" | "Rem" | "Neg" | "AddAssign" | "SubAssign" | "MulAssign"
+        | "DivAssign" | "RemAssign" | "Sum" | "Product" => numeric,
+        "AsRef" | "Borrow" => match args {
+            "str" => ty == "String",
+            "[u8]" => ty == "Vec<u8>",
+            _ => false,
+        },
+        "Into" => args == ty || (ty == "i32" && matches!(args, "i64" | "i128" | "f64")),
+        "From" => args == ty || (ty == "String" && args == "&str"),
+        "IntoIterator" => ty == "Vec<u8>" && matches!(args, "" | "Item=u8"),
+        _ => return None,
+    })
+}
+
+/// A value for a const parameter of this type.
+fn const_value(type_str: &str) -> Option<String> {
+    let value = match type_str.trim() {
+        "usize" | "u8" | "u16" | "u32" | "u64" | "u128" | "isize" | "i8" | "i16" | "i32"
+        | "i64" | "i128" => "1",
+        "bool" => "false",
+        "char" => "'a'",
+        _ => return None,
+    };
+    Some(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::analyzer::analyze_function;
+
+    fn analyze(source: &str) -> FunctionInfo {
+        analyze_function(&syn::parse_str(source).unwrap(), "src/lib.rs")
+    }
+
+    #[test]
+    fn test_type_params_get_a_type_meeting_their_bounds() {
+        let parse = analyze(
+            "pub fn parse<'a, T: std::str::FromStr>(s: &'a str, d: Cow<'a, str>) -> Result<T, T::Err> { todo!() }",
+        );
+        let instantiation = Instantiation::of(&parse).unwrap();
+        assert_eq!(instantiation.turbofish(), "::<i32>");
+        let parse = instantiation.apply(&parse);
+        assert_eq!(parse.params[1].typ.as_str(), "Cow < '_ , str >");
+        assert_eq!(
+            parse.returns.as_str(),
+            "Result < i32 , <i32 as std::str::FromStr>::Err >"
+        );
+
+        let join = analyze(
+            "pub fn join<S, const N: usize>(parts: [S; N]) -> String where S: AsRef<str> { todo!() }",
+        );
+        let instantiation = Instantiation::of(&join).unwrap();
+        assert_eq!(instantiation.turbofish(), "::<String, 1>");
+        assert_eq!(
+            instantiation.apply(&join).params[0].typ.as_str(),
+            "[String ; 1]"
+        );
+
+        let hash = analyze("pub fn key<K: Hash + Copy + Into<i64>>(k: K) -> u64 { 0 }");
+        assert_eq!(Instantiation::of(&hash).unwrap().turbofish(), "::<i32>");
+    }
+
+    #[test]
+    fn test_unknown_bounds_and_predicates_stay_generic() {
+        for source in [
+            "pub fn area<S: Shape>(s: &S) -> f64 { 0.0 }",
+            "pub fn show<T: Display>(v: Vec<T>) where Vec<T>: Debug {}",
+            "pub fn plain(v: u8) -> u8 { v }",
+        ] {
+            assert_eq!(Instantiation::of(&analyze(source)), None, "{}", source);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! # Nested Output Layout
+//!
+//! With `generation.layout = "nested"`, generated tests live in their own
+//! subtree instead of next to hand-written integration tests:
+//!
+//! ```text
+//! tests/generated.rs              // test crate root: `mod crate_root; mod models;`
+//! tests/generated/crate_root.rs   // tests for the crate root
+//! tests/generated/models/mod.rs   // tests for `models`, plus `mod user;`
+//! tests/generated/models/user.rs  // tests for `models::user`
+//! ```
+
+use crate::core::models::TestFile;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Name of the generated subtree and of its crate root file.
+pub const GENERATED_DIR: &str = "generated";
+
+/// Module name used for tests of the crate root. When the crate has a module
+/// of that name, `crate_root_` is used: module names never end in `_` (see
+/// [`NestedLayout::segments`]).
+const ROOT_MODULE: &str = "crate_root";
+
+/// File placement for the nested layout, relative to the output directory.
+#[derive(Debug, Clone, Default)]
+pub struct NestedLayout {
+    /// Analyzed module path -> file holding its tests.
+    files: HashMap<String, PathBuf>,
+    /// Module file -> child modules it must declare.
+    children: BTreeMap<PathBuf, BTreeSet<String>>,
+}
+
+impl NestedLayout {
+    /// Plan file placement for the given analyzed module paths.
+    pub fn new<'a>(module_paths: impl Iterator<Item = &'a String>) -> Self {
+        let module_paths: Vec<&String> = module_paths.collect();
+        let mut root_module = ROOT_MODULE.to_string();
+        if module_paths
+            .iter()
+            .any(|m| Self::segments(m, "").first() == Some(&root_module))
+        {
+            root_module.push('_');
+        }
+        let modules: Vec<(&String, Vec<String>)> = module_paths
+            .into_iter()
+            .map(|m| (m, Self::segments(m, &root_module)))
+            .collect();
+
+        // Every module and its ancestors become nodes of the tree
+        let mut nodes: BTreeSet<Vec<String>> = BTreeSet::new();
+        for (_, segments) in &modules {
+            for end in 1..=segments.len() {
+                nodes.insert(segments[..end].to_vec());
+            }
+        }
+
+        let has_children = |node: &[String]| {
+            nodes
+                .iter()
+                .any(|n| n.len() > node.len() && n.starts_with(node))
+        };
+        let node_file = |node: &[String]| -> PathBuf {
+            let mut path = PathBuf::from(GENERATED_DIR);
+            path.extend(node);
+            if has_children(node) {
+                path.join("mod.rs")
+            } else {
+                path.with_extension("rs")
+            }
+        };
+
+        let mut layout = Self::default();
+        for node in &nodes {
+            let parent_file = if node.len() == 1 {
+                Self::root_file()
+            } else {
+                node_file(&node[..node.len() - 1])
+            };
+            layout
+                .children
+                .entry(parent_file)
+                .or_default()
+                .insert(node[node.len() - 1].clone());
+        }
+        for (module_path, segments) in &modules {
+            layout
+                .files
+                .insert((*module_path).clone(), node_file(segments));
+        }
+        layout
+    }
+
+    /// The crate root of the generated tests, `generated.rs`.
+    pub fn root_file() -> PathBuf {
+        PathBuf::from(format!("{}.rs", GENERATED_DIR))
+    }
+
+    /// File holding the tests for `module_path`, relative to the output directory.
+    pub fn file_for(&self, module_path: &str) -> Option<&Path> {
+        self.files.get(module_path).map(PathBuf::as_path)
+    }
+
+    /// Add `mod` declarations to module files, creating glue files for modules
+    /// that have no tests of their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The generated test files
+    /// * `output_dir` - Output directory the relative paths are joined to
+    /// * `root_preamble` - Extra items for the crate root, e.g. the fixtures module
+    pub fn add_glue(&self, files: &mut Vec<TestFile>, output_dir: &Path, root_preamble: &str) {
+        for (module_file, children) in &self.children {
+            let mut decls: String = children.iter().map(|c| format!("mod {};\n", c)).collect();
+            if *module_file == Self::root_file() {
+                decls = format!("{}{}", root_preamble, decls);
+            }
+
+            let path = output_dir.join(module_file).to_string_lossy().to_string();
+            match files.iter_mut().find(|f| f.path == path) {
+                Some(file) => file.content = format!("{}\n{}", decls, file.content),
+                None => files.push(TestFile {
+                    path,
+                    content: decls,
+                }),
+            }
+        }
+    }
+
+    /// Module path as file-system-safe module names without leading or trailing
+    /// `_`; the crate root is `root_module`.
+    fn segments(module_path: &str, root_module: &str) -> Vec<String> {
+        let segments: Vec<String> = module_path
+            .split("::")
+            .map(|segment| {
+                let name: String = segment
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+                let name = name.trim_matches('_').to_lowercase();
+                if name.starts_with(|c: char| c.is_ascii_digit()) {
+                    format!("m_{}", name)
+                } else {
+                    name
+                }
+            })
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if segments.is_empty() {
+            vec![root_module.to_string()]
+        } else {
+            segments
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_layout_places_modules_and_glue() {
+        let modules = [
+            "".to_string(),
+            "models".to_string(),
+            "models::user".to_string(),
+            "net::http".to_string(),
+        ];
+        let layout = NestedLayout::new(modules.iter());
+
+        assert_eq!(
+            layout.file_for(""),
+            Some(Path::new("generated/crate_root.rs"))
+        );
+        assert_eq!(
+            layout.file_for("models"),
+            Some(Path::new("generated/models/mod.rs"))
+        );
+        assert_eq!(
+            layout.file_for("models::user"),
+            Some(Path::new("generated/models/user.rs"))
+        );
+
+        let out = Path::new("tests");
+        let mut files = vec![TestFile {
+            path: "tests/generated/models/mod.rs".to_string(),
+            content: "#[test] fn t() {}\n".to_string(),
+        }];
+        layout.add_glue(
+            &mut files,
+            out,
+            "#[path = \"common/mod.rs\"]\nmod common;\n",
+        );
+
+        let content = |p: &str| files.iter().find(|f| f.path == p).unwrap().content.clone();
+        assert_eq!(
+            content("tests/generated.rs"),
+            "#[path = \"common/mod.rs\"]\nmod common;\nmod crate_root;\nmod models;\nmod net;\n"
+        );
+        assert_eq!(
+            content("tests/generated/models/mod.rs"),
+            "mod user;\n\n#[test] fn t() {}\n"
+        );
+        assert_eq!(content("tests/generated/net/mod.rs"), "mod http;\n");
+    }
+
+    #[test]
+    fn test_crate_root_module_avoids_a_module_of_that_name() {
+        let modules = ["".to_string(), "crate_root".to_string()];
+        let layout = NestedLayout::new(modules.iter());
+
+        assert_eq!(
+            layout.file_for(""),
+            Some(Path::new("generated/crate_root_.rs"))
+        );
+        assert_eq!(
+            layout.file_for("crate_root"),
+            Some(Path::new("generated/crate_root.rs"))
+        );
+    }
+}
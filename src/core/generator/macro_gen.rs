@@ -0,0 +1,337 @@
+//! # Proc-Macro Test Generation
+//!
+//! Proc-macro crates export macros rather than callable functions, so ordinary
+//! call stubs don't apply. For these crates a [trybuild](https://docs.rs/trybuild)
+//! harness is generated instead, with one pass and one compile-fail case
+//! skeleton per exported macro.
+
+use crate::config::Config;
+use crate::core::analyzer::CrateManifest;
+use crate::core::models::TestFile;
+use crate::error::{AutoTestError, Result};
+use crate::utils::log::status;
+use std::path::Path;
+use syn::Item;
+
+/// The kind of procedural macro exported by the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroKind {
+    /// `#[proc_macro]`, invoked as `name!(...)`.
+    FunctionLike,
+    /// `#[proc_macro_derive(Name)]`, used as `#[derive(Name)]`.
+    Derive,
+    /// `#[proc_macro_attribute]`, used as `#[name]`.
+    Attribute,
+}
+
+/// A procedural macro found in the crate root.
+#[derive(Debug, Clone)]
+pub struct MacroInfo {
+    /// Name used at call sites (the derive name for derive macros).
+    pub name: String,
+    /// How the macro is invoked.
+    pub kind: MacroKind,
+}
+
+/// Generator for trybuild-based tests of proc-macro crates.
+pub struct MacroGenerator;
+
+impl MacroGenerator {
+    /// Generate `tests/ui.rs` and per-macro case skeletons for a proc-macro crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - Path to the project root
+    /// * `config` - Configuration for generation behavior
+    /// * `manifest` - The crate's parsed `Cargo.toml`
+    ///
+    /// # Returns
+    ///
+    /// The harness and case files, or an error if the crate root can't be parsed
+    pub fn generate(
+        project_path: &Path,
+        config: &Config,
+        manifest: &CrateManifest,
+    ) -> Result<Vec<TestFile>> {
+        let lib_path = project_path.join(manifest.lib_path.as_deref().unwrap_or("src/lib.rs"));
+        let content = std::fs::read_to_string(&lib_path).map_err(|e| AutoTestError::FileRead {
+            path: lib_path.clone(),
+            source: e,
+        })?;
+        let ast = syn::parse_file(&content).map_err(|e| AutoTestError::ParseFailed {
+            path: lib_path.clone(),
+            source: e,
+        })?;
+
+        let macros = Self::find_macros(&ast);
+        if macros.is_empty() {
+            status!("No procedural macros found in {}", lib_path.display());
+            return Ok(Vec::new());
+        }
+
+        let crate_name = manifest
+            .name
+            .as_deref()
+            .unwrap_or("test_project")
+            .replace('-', "_");
+        let output_dir = project_path.join(&config.output_dir);
+
+        let mut files = vec![TestFile {
+            path: output_dir.join("ui.rs").to_string_lossy().to_string(),
+            content: Self::render_harness(&config.output_dir),
+        }];
+
+        for (mac, file_name) in macros.iter().zip(Self::case_file_names(&macros)) {
+            files.push(TestFile {
+                path: output_dir
+                    .join("ui")
+                    .join("pass")
+                    .join(&file_name)
+                    .to_string_lossy()
+                    .to_string(),
+                content: Self::render_pass_case(mac, &crate_name),
+            });
+            files.push(TestFile {
+                path: output_dir
+                    .join("ui")
+                    .join("fail")
+                    .join(&file_name)
+                    .to_string_lossy()
+                    .to_string(),
+                content: Self::render_fail_case(mac, &crate_name),
+            });
+        }
+
+        Ok(files)
+    }
+
+    /// Case file name of each macro, e.g. `builder_derive.rs` for
+    /// `#[derive(Builder)]` and `builder_macro.rs` for `builder!`, numbering
+    /// names that still collide (macros differing only by case) in crate order.
+    fn case_file_names(macros: &[MacroInfo]) -> Vec<String> {
+        let mut taken = std::collections::HashSet::new();
+        macros
+            .iter()
+            .map(|mac| {
+                let kind = match mac.kind {
+                    MacroKind::FunctionLike => "macro",
+                    MacroKind::Derive => "derive",
+                    MacroKind::Attribute => "attribute",
+                };
+                let stem = format!("{}_{}", mac.name.to_lowercase(), kind);
+                let mut name = format!("{}.rs", stem);
+                let mut n = 2;
+                while !taken.insert(name.clone()) {
+                    name = format!("{}_{}.rs", stem, n);
+                    n += 1;
+                }
+                name
+            })
+            .collect()
+    }
+
+    /// Find exported procedural macros among the crate root's items.
+    pub fn find_macros(ast: &syn::File) -> Vec<MacroInfo> {
+        let mut macros = Vec::new();
+
+        for item in &ast.items {
+            let Item::Fn(func) = item else {
+                continue;
+            };
+
+            for attr in &func.attrs {
+                let path = attr.path();
+                if path.is_ident("proc_macro") {
+                    macros.push(MacroInfo {
+                        name: func.sig.ident.to_string(),
+                        kind: MacroKind::FunctionLike,
+                    });
+                } else if path.is_ident("proc_macro_attribute") {
+                    macros.push(MacroInfo {
+                        name: func.sig.ident.to_string(),
+                        kind: MacroKind::Attribute,
+                    });
+                } else if path.is_ident("proc_macro_derive") {
+                    // The derive name is the first argument: #[proc_macro_derive(Name, attributes(..))]
+                    let name = attr
+                        .parse_args_with(
+                            syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                        )
+                        .ok()
+                        .and_then(|args| args.first().and_then(|m| m.path().get_ident().cloned()))
+                        .map(|ident| ident.to_string())
+                        .unwrap_or_else(|| func.sig.ident.to_string());
+                    macros.push(MacroInfo {
+                        name,
+                        kind: MacroKind::Derive,
+                    });
+                }
+            }
+        }
+
+        macros
+    }
+
+    /// The trybuild driver test; case globs are relative to the crate root.
+    fn render_harness(output_dir: &str) -> String {
+        let dir = output_dir.trim_end_matches('/');
+        format!(
+            "#[test]
+fn ui() {{
+    let t = trybuild::TestCases::new();
+    t.pass(\"{dir}/ui/pass/*.rs\");
+    t.compile_fail(\"{dir}/ui/fail/*.rs\");
+}}
+"
+        )
+    }
+
+    /// A case that must compile.
+    fn render_pass_case(mac: &MacroInfo, crate_name: &str) -> String {
+        match mac.kind {
+            MacroKind::Derive => format!(
+                "use {krate}::{name};
+
+#[derive({name})]
+struct Sample {{
+    // TODO: Add fields exercising the derive
+    value: u32,
+}}
+
+fn main() {{
+    let _ = Sample {{ value: 0 }};
+}}
+",
+                krate = crate_name,
+                name = mac.name
+            ),
+            MacroKind::Attribute => format!(
+                "#[{krate}::{name}]
+fn sample() {{
+    // TODO: Add an item body exercising the attribute
+}}
+
+fn main() {{
+    sample();
+}}
+",
+                krate = crate_name,
+                name = mac.name
+            ),
+            MacroKind::FunctionLike => format!(
+                "fn main() {{
+    // TODO: Provide valid input for the macro
+    {krate}::{name}!();
+}}
+",
+                krate = crate_name,
+                name = mac.name
+            ),
+        }
+    }
+
+    /// A case that must be rejected at compile time.
+    fn render_fail_case(mac: &MacroInfo, crate_name: &str) -> String {
+        match mac.kind {
+            MacroKind::Derive => format!(
+                "use {krate}::{name};
+
+// TODO: Replace with an input the derive must reject
+#[derive({name})]
+union Unsupported {{
+    a: u32,
+    b: f32,
+}}
+
+fn main() {{}}
+",
+                krate = crate_name,
+                name = mac.name
+            ),
+            MacroKind::Attribute => format!(
+                "// TODO: Replace with arguments or an item the attribute must reject
+#[{krate}::{name}(invalid arguments)]
+struct Unsupported;
+
+fn main() {{}}
+",
+                krate = crate_name,
+                name = mac.name
+            ),
+            MacroKind::FunctionLike => format!(
+                "fn main() {{
+    // TODO: Replace with input the macro must reject
+    {krate}::{name}!(@invalid input);
+}}
+",
+                krate = crate_name,
+                name = mac.name
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_macros_and_render_cases() {
+        let ast: syn::File = syn::parse_str(
+            r#"
+            #[proc_macro_derive(Builder, attributes(builder))]
+            pub fn derive_builder(input: TokenStream) -> TokenStream { input }
+
+            #[proc_macro_attribute]
+            pub fn traced(_attr: TokenStream, item: TokenStream) -> TokenStream { item }
+
+            #[proc_macro]
+            pub fn sql(input: TokenStream) -> TokenStream { input }
+
+            pub fn helper() {}
+            "#,
+        )
+        .unwrap();
+
+        let macros = MacroGenerator::find_macros(&ast);
+        let names: Vec<_> = macros
+            .iter()
+            .map(|m| (m.name.as_str(), m.kind.clone()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Builder", MacroKind::Derive),
+                ("traced", MacroKind::Attribute),
+                ("sql", MacroKind::FunctionLike),
+            ]
+        );
+
+        let pass = MacroGenerator::render_pass_case(&macros[0], "my_macros");
+        assert!(pass.contains("use my_macros::Builder;\n\n#[derive(Builder)]"));
+        let fail = MacroGenerator::render_fail_case(&macros[2], "my_macros");
+        assert!(fail.contains("my_macros::sql!(@invalid input);"));
+    }
+
+    #[test]
+    fn test_case_files_of_same_named_macros_are_distinct() {
+        let mac = |name: &str, kind| MacroInfo {
+            name: name.to_string(),
+            kind,
+        };
+        let macros = [
+            mac("Builder", MacroKind::Derive),
+            mac("builder", MacroKind::FunctionLike),
+            mac("BUILDER", MacroKind::Derive),
+        ];
+
+        assert_eq!(
+            MacroGenerator::case_file_names(&macros),
+            vec![
+                "builder_derive.rs",
+                "builder_macro.rs",
+                "builder_derive_2.rs"
+            ]
+        );
+    }
+}
@@ -1,2 +1,16 @@
+pub mod const_gen;
+pub mod docs;
+pub mod fixtures;
+pub mod generics;
+pub mod layout;
+pub mod macro_gen;
+pub mod nextest;
+pub mod plugin;
+pub mod property_gen;
+pub mod roundtrip_gen;
 pub mod rust_gen;
+pub mod suggestions;
+pub mod table_gen;
+pub mod unit;
 pub mod v_gen;
+pub mod workspace;
@@ -0,0 +1,155 @@
+//! # Nextest Profile
+//!
+//! Writes a `[profile.<name>]` to `.config/nextest.toml` whose `default-filter`
+//! selects exactly the generated test binaries, so CI can run the generated
+//! suite on its own (`cargo nextest run --profile autogen`) with its own retry
+//! and timeout settings, or exclude it with `-E 'not (...)'`.
+
+use crate::core::analyzer::CrateManifest;
+use crate::core::runner::TestTarget;
+use crate::error::{AutoTestError, Result};
+use std::path::Path;
+
+/// Nextest config file, relative to the workspace root.
+pub const NEXTEST_CONFIG: &str = ".config/nextest.toml";
+
+/// Filterset matching the given test targets, e.g.
+/// `binary_id(mycrate::integration_tests) | binary_id(mycrate::utils_tests)`.
+pub fn filter_expression(targets: &[TestTarget]) -> String {
+    let mut binaries: Vec<String> = targets
+        .iter()
+        .map(|target| {
+            let package = CrateManifest::load(&target.crate_dir)
+                .ok()
+                .flatten()
+                .and_then(|manifest| manifest.name);
+            match package {
+                Some(package) => format!("binary_id({}::{})", package, target.name),
+                None => format!("binary(={})", target.name),
+            }
+        })
+        .collect();
+    binaries.sort();
+    binaries.dedup();
+    binaries.join(" | ")
+}
+
+/// Set `default-filter` of `[profile.<profile>]` in a nextest config, keeping
+/// everything else (retries, timeouts, comments) as it is.
+///
+/// # Arguments
+///
+/// * `contents` - Current config file contents (empty if there is none)
+/// * `profile` - Profile name, e.g. `autogen`
+/// * `filter` - Filterset selecting the generated tests
+pub fn update_config(contents: &str, profile: &str, filter: &str) -> Result<String> {
+    let mut doc: toml_edit::DocumentMut =
+        contents.parse().map_err(|e| AutoTestError::InvalidConfig {
+            message: format!("{}: {}", NEXTEST_CONFIG, e),
+        })?;
+
+    let profiles = doc
+        .entry("profile")
+        .or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            toml_edit::Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| AutoTestError::InvalidConfig {
+            message: format!("{}: `profile` is not a table", NEXTEST_CONFIG),
+        })?;
+    let section = profiles
+        .entry(profile)
+        .or_insert_with(toml_edit::table)
+        .as_table_mut()
+        .ok_or_else(|| AutoTestError::InvalidConfig {
+            message: format!("{}: `profile.{}` is not a table", NEXTEST_CONFIG, profile),
+        })?;
+    section["default-filter"] = toml_edit::value(filter);
+
+    Ok(doc.to_string())
+}
+
+/// Write the profile for the generated test files of a run.
+///
+/// # Arguments
+///
+/// * `project_path` - Project (or workspace) root holding `.config/nextest.toml`
+/// * `profile` - Profile name
+/// * `files` - Generated test files, relative to the project root
+///
+/// # Returns
+///
+/// The filterset written, or `None` when no test targets were generated
+pub fn write_profile(
+    project_path: &Path,
+    profile: &str,
+    files: &[String],
+) -> Result<Option<String>> {
+    let targets: Vec<TestTarget> = files
+        .iter()
+        .filter_map(|file| TestTarget::for_file(&project_path.join(file)))
+        .collect();
+    if targets.is_empty() {
+        return Ok(None);
+    }
+
+    let filter = filter_expression(&targets);
+    let path = project_path.join(NEXTEST_CONFIG);
+    let contents = if path.exists() {
+        std::fs::read_to_string(&path).map_err(|e| AutoTestError::FileRead {
+            path: path.clone(),
+            source: e,
+        })?
+    } else {
+        String::new()
+    };
+    let updated = update_config(&contents, profile, &filter)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AutoTestError::FileWrite {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+    std::fs::write(&path, updated).map_err(|e| AutoTestError::FileWrite { path, source: e })?;
+    Ok(Some(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_filters_generated_binaries_and_keeps_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"rp\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let targets = vec![
+            TestTarget::for_file(&dir.path().join("tests/utils_tests.rs")).unwrap(),
+            TestTarget::for_file(&dir.path().join("tests/integration_tests.rs")).unwrap(),
+        ];
+        let filter = filter_expression(&targets);
+        assert_eq!(
+            filter,
+            "binary_id(rp::integration_tests) | binary_id(rp::utils_tests)"
+        );
+
+        let existing = "# CI settings\n[profile.autogen]\nretries = 2\n\n[profile.default]\nslow-timeout = \"30s\"\n";
+        let updated = update_config(existing, "autogen", &filter).unwrap();
+        assert_eq!(
+            updated,
+            "# CI settings\n[profile.autogen]\nretries = 2\ndefault-filter = \"binary_id(rp::integration_tests) | binary_id(rp::utils_tests)\"\n\n[profile.default]\nslow-timeout = \"30s\"\n"
+        );
+
+        let fresh = update_config("", "autogen", "binary(=a)").unwrap();
+        assert_eq!(
+            fresh,
+            "[profile.autogen]\ndefault-filter = \"binary(=a)\"\n"
+        );
+    }
+}
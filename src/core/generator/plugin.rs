@@ -0,0 +1,297 @@
+//! # Generator Plugins
+//!
+//! WASM modules that replace the built-in test body for the functions they
+//! handle, so organizations can ship their own generation logic without forking
+//! the crate. Plugins are every `*.wasm` (or `*.wat`) file in `plugins.dir`
+//! (default `plugins/`) plus `plugins.paths`, tried in that order.
+//!
+//! A plugin is a core WASM module exporting:
+//!
+//! - `memory`: its linear memory
+//! - `alloc(len: i32) -> i32`: a buffer of `len` bytes for the request
+//! - `generate(ptr: i32, len: i32) -> i64`: handles the JSON request at
+//!   `ptr..ptr + len` and returns the test as UTF-8, packed as
+//!   `(out_ptr << 32) | out_len`. An empty result leaves the function to the
+//!   next plugin, and finally to the built-in generator.
+//!
+//! The request is `{"function": FunctionInfo, "module_path": "...",
+//! "test_name": "...", "config": Config}`, with the same field names as the
+//! JSON report. Plugins get no imports, and each call runs in a fresh instance
+//! with a bounded amount of fuel.
+
+use crate::config::Config;
+use crate::core::models::FunctionInfo;
+use crate::error::{AutoTestError, Result};
+use std::path::{Path, PathBuf};
+
+/// Fuel (roughly, WASM instructions) a plugin may spend on one function.
+pub const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// The plugins of a project, compiled once per run.
+#[derive(Default)]
+pub struct PluginHost {
+    #[cfg(feature = "plugins")]
+    engine: wasmtime::Engine,
+    #[cfg(feature = "plugins")]
+    plugins: Vec<(PathBuf, wasmtime::Module)>,
+}
+
+#[derive(serde::Serialize)]
+struct Request<'a> {
+    function: &'a FunctionInfo,
+    module_path: &'a str,
+    test_name: &'a str,
+    config: &'a Config,
+}
+
+impl PluginHost {
+    /// Plugin files of a project: `plugins.dir` sorted by name, then `plugins.paths`.
+    pub fn discover(project_path: &Path, config: &Config) -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = std::fs::read_dir(project_path.join(&config.plugins.dir))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(|e| e.to_str()),
+                        Some("wasm" | "wat")
+                    )
+            })
+            .collect();
+        found.sort();
+        found.extend(
+            config
+                .plugins
+                .paths
+                .iter()
+                .map(|path| project_path.join(path)),
+        );
+        found
+    }
+
+    /// Compile the plugins of a project.
+    ///
+    /// # Returns
+    ///
+    /// The loaded plugins, or an error if one can't be compiled or `plugins.paths`
+    /// is set in a build without the `plugins` feature. Without the feature,
+    /// modules found in `plugins.dir` are skipped with a warning.
+    pub fn load(project_path: &Path, config: &Config) -> Result<Self> {
+        let paths = Self::discover(project_path, config);
+        #[cfg(feature = "plugins")]
+        {
+            let mut engine_config = wasmtime::Config::new();
+            engine_config.consume_fuel(true);
+            let engine =
+                wasmtime::Engine::new(&engine_config).map_err(|e| AutoTestError::Plugin {
+                    path: PathBuf::new(),
+                    message: e.to_string(),
+                })?;
+            let mut plugins = Vec::new();
+            for path in paths {
+                if !path.is_file() {
+                    return Err(AutoTestError::Plugin {
+                        path,
+                        message: "file not found".to_string(),
+                    });
+                }
+                let module = wasmtime::Module::from_file(&engine, &path).map_err(|e| {
+                    AutoTestError::Plugin {
+                        path: path.clone(),
+                        message: e.to_string(),
+                    }
+                })?;
+                plugins.push((path, module));
+            }
+            Ok(Self { engine, plugins })
+        }
+        #[cfg(not(feature = "plugins"))]
+        {
+            if let Some(path) = config.plugins.paths.first() {
+                return Err(AutoTestError::Plugin {
+                    path: project_path.join(path),
+                    message: "autotest was built without the `plugins` feature".to_string(),
+                });
+            }
+            if !paths.is_empty() {
+                crate::utils::log::status!(
+                    "Warning: skipping {} plugin(s) in {}: autotest was built without the `plugins` feature",
+                    paths.len(),
+                    config.plugins.dir
+                );
+            }
+            Ok(Self::default())
+        }
+    }
+
+    /// Whether no plugins are loaded.
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "plugins")]
+        return self.plugins.is_empty();
+        #[cfg(not(feature = "plugins"))]
+        true
+    }
+
+    /// Test for `func` from the first plugin that handles it.
+    ///
+    /// # Returns
+    ///
+    /// The plugin's test, or `None` when every plugin declined or failed; failures
+    /// are reported as warnings
+    pub fn render(
+        &self,
+        func: &FunctionInfo,
+        module_path: &str,
+        test_name: &str,
+        config: &Config,
+    ) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let request = serde_json::to_vec(&Request {
+            function: func,
+            module_path,
+            test_name,
+            config,
+        })
+        .ok()?;
+
+        #[cfg(feature = "plugins")]
+        for (path, module) in &self.plugins {
+            match self.call(module, &request) {
+                Ok(test) if !test.trim().is_empty() => return Some(test),
+                Ok(_) => {}
+                Err(e) => crate::utils::log::status!(
+                    "Warning: plugin {} failed on {}: {}",
+                    path.display(),
+                    func.name,
+                    e
+                ),
+            }
+        }
+        #[cfg(not(feature = "plugins"))]
+        let _ = request;
+        None
+    }
+
+    /// Run `generate` of a plugin on one request in a fresh instance.
+    #[cfg(feature = "plugins")]
+    fn call(&self, module: &wasmtime::Module, request: &[u8]) -> wasmtime::Result<String> {
+        let mut store = wasmtime::Store::new(&self.engine, ());
+        store.set_fuel(FUEL_PER_CALL)?;
+        let instance = wasmtime::Instance::new(&mut store, module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("missing `memory` export"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let generate = instance.get_typed_func::<(i32, i32), i64>(&mut store, "generate")?;
+
+        let len = i32::try_from(request.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, request)?;
+        let packed = generate.call(&mut store, (ptr, len))? as u64;
+
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        // Check the range before allocating, as `out_len` comes from the plugin
+        let out = memory
+            .data(&store)
+            .get(out_ptr..out_ptr.saturating_add(out_len))
+            .ok_or_else(|| wasmtime::Error::msg("result out of memory bounds"))?;
+        Ok(String::from_utf8(out.to_vec())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_plugin_dir_then_config_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("plugins")).unwrap();
+        std::fs::write(dir.path().join("plugins/b.wasm"), "").unwrap();
+        std::fs::write(dir.path().join("plugins/a.wat"), "").unwrap();
+        std::fs::write(dir.path().join("plugins/README.md"), "").unwrap();
+        let mut config = Config::default();
+        config.plugins.paths = vec!["vendor/org.wasm".to_string()];
+
+        assert_eq!(
+            PluginHost::discover(dir.path(), &config),
+            vec![
+                dir.path().join("plugins/a.wat"),
+                dir.path().join("plugins/b.wasm"),
+                dir.path().join("vendor/org.wasm"),
+            ]
+        );
+        // Only invalid modules fail with the feature; without it they're skipped
+        assert_eq!(
+            PluginHost::load(dir.path(), &Config::default()).is_err(),
+            cfg!(feature = "plugins")
+        );
+        assert!(PluginHost::load(dir.path(), &config).is_err());
+        assert!(
+            PluginHost::load(&dir.path().join("src"), &Config::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    /// Plugin answering every request with a fixed test, or declining functions
+    /// named `s...`: byte 21 of the request is the first letter of the name.
+    #[cfg(feature = "plugins")]
+    const FIXED_PLUGIN: &str = r##"(module
+      (memory (export "memory") 1)
+      (data (i32.const 16) "#[test] fn custom() {}")
+      (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+      (func (export "generate") (param i32 i32) (result i64)
+        (if (result i64) (i32.eq (i32.load8_u (i32.add (local.get 0) (i32.const 21))) (i32.const 115))
+          (then (i64.const 0))
+          (else (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 22))))))"##;
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_plugin_renders_or_declines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("plugins")).unwrap();
+        std::fs::write(dir.path().join("plugins/fixed.wat"), FIXED_PLUGIN).unwrap();
+        let config = Config::default();
+        let host = PluginHost::load(dir.path(), &config).unwrap();
+
+        let func = FunctionInfo {
+            name: "add".to_string(),
+            ..FunctionInfo::default()
+        };
+        assert_eq!(
+            host.render(&func, "crate", "test_add", &config).as_deref(),
+            Some("#[test] fn custom() {}")
+        );
+        let skip = FunctionInfo {
+            name: "skip".to_string(),
+            ..FunctionInfo::default()
+        };
+        assert_eq!(host.render(&skip, "crate", "test_skip", &config), None);
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_plugin_result_out_of_bounds_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("plugins")).unwrap();
+        std::fs::write(
+            dir.path().join("plugins/huge.wat"),
+            r#"(module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "generate") (param i32 i32) (result i64) (i64.const 0xffffffff)))"#,
+        )
+        .unwrap();
+        let config = Config::default();
+        let host = PluginHost::load(dir.path(), &config).unwrap();
+
+        assert_eq!(
+            host.render(&FunctionInfo::default(), "crate", "test_f", &config),
+            None
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! # Property Test Generation
+//!
+//! With `generation.strategy = "property"`, pure functions get
+//! [proptest](https://docs.rs/proptest) tests instead of single-example
+//! integration tests. Only functions marked pure by the analyzer qualify, since
+//! properties like determinism don't hold for code with side effects.
+//!
+//! Normalizer-style functions, taking and returning the same type
+//! (`fn normalize(s: &str) -> String`), also get an idempotence property
+//! (`f(f(x)) == f(x)`). Whether it should hold is a guess from the signature,
+//! so those functions are flagged in the generation report. Binary operations
+//! (`fn add(a: T, b: T) -> T`) get commented-out commutativity and
+//! associativity templates to uncomment where the algebra holds.
+
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::core::models::FunctionInfo;
+
+/// Generator for proptest-based tests of pure functions.
+pub struct PropertyGenerator;
+
+impl PropertyGenerator {
+    /// Whether `func` can get a property test: it's pure, synchronous, returns a
+    /// value, and every parameter type has an input strategy.
+    pub fn supports(func: &FunctionInfo) -> bool {
+        func.is_pure
+            && !func.is_async
+            && func.returns.as_str() != "()"
+            && func
+                .params
+                .iter()
+                .all(|p| Self::strategy_for(p.typ.as_str()).is_some())
+    }
+
+    /// Whether `func` gets an idempotence property: its single parameter has the
+    /// return type, or is a reference to it (`&str` counting as `String`).
+    pub fn idempotent(func: &FunctionInfo) -> bool {
+        let [param] = func.params.as_slice() else {
+            return false;
+        };
+        owned_type(param.typ.as_str())
+            .is_some_and(|t| t == RustGenerator::display_type(func.returns.as_str()))
+    }
+
+    /// Whether `func` is a binary operation: two parameters of the same type,
+    /// which is the return type or a reference to it.
+    pub fn binary_operation(func: &FunctionInfo) -> bool {
+        let [a, b] = func.params.as_slice() else {
+            return false;
+        };
+        RustGenerator::display_type(a.typ.as_str()) == RustGenerator::display_type(b.typ.as_str())
+            && owned_type(a.typ.as_str())
+                .is_some_and(|t| t == RustGenerator::display_type(func.returns.as_str()))
+    }
+
+    /// Render a `proptest!` block checking that `func` is deterministic, and
+    /// idempotent if [`PropertyGenerator::idempotent`] holds. Binary operations
+    /// also get commented-out algebraic property templates.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - A function for which [`PropertyGenerator::supports`] holds
+    /// * `test_stem` - Module-qualified name used in the test name
+    /// * `call_path` - Path used to call the function from the test
+    pub fn render(func: &FunctionInfo, test_stem: &str, call_path: &str) -> String {
+        let mut inputs = Vec::new();
+        let mut first_args = Vec::new();
+        let mut second_args = Vec::new();
+
+        for (param, name) in func.params.iter().zip(RustGenerator::param_bindings(func)) {
+            let typ = param.typ.as_str();
+            let strategy = Self::strategy_for(typ).unwrap_or_else(|| "any::<()>()".to_string());
+            inputs.push(format!("{} in {}", name, strategy));
+
+            if typ.trim_start().starts_with('&') {
+                first_args.push(format!("&{}", name));
+                second_args.push(format!("&{}", name));
+            } else {
+                first_args.push(format!("{}.clone()", name));
+                second_args.push(name);
+            }
+        }
+
+        let mut extra = String::new();
+        if Self::idempotent(func) {
+            let again = if func.params[0].typ.as_str().trim_start().starts_with('&') {
+                "&once"
+            } else {
+                "once.clone()"
+            };
+            extra = format!(
+                "
+
+        // Assumed from the signature; remove if `{function}` isn't meant to be idempotent
+        #[test]
+        fn prop_{name}_idempotent({inputs}) {{
+            let once = {call}({first});
+            let twice = {call}({again});
+            prop_assert_eq!(twice, once);
+        }}",
+                function = func.name,
+                name = test_stem,
+                inputs = inputs.join(", "),
+                call = call_path,
+                first = first_args.join(", "),
+                again = again,
+            );
+        }
+
+        if Self::binary_operation(func) {
+            let strategy = Self::strategy_for(func.params[0].typ.as_str()).unwrap_or_default();
+            extra.push_str(&Self::algebra_templates(
+                func, test_stem, call_path, &strategy,
+            ));
+        }
+
+        format!(
+            "    proptest! {{
+        #[test]
+        fn prop_{name}_deterministic({inputs}) {{
+            let first = {call}({first});
+            let second = {call}({second});
+            prop_assert_eq!(first, second);
+        }}{extra}
+    }}",
+            name = test_stem,
+            inputs = inputs.join(", "),
+            call = call_path,
+            first = first_args.join(", "),
+            second = second_args.join(", "),
+            extra = extra,
+        )
+    }
+
+    /// Commented-out commutativity and associativity tests of a binary operation.
+    fn algebra_templates(
+        func: &FunctionInfo,
+        test_stem: &str,
+        call_path: &str,
+        strategy: &str,
+    ) -> String {
+        let by_ref = func.params[0].typ.as_str().trim_start().starts_with('&');
+        // Operands used again later are cloned; references are borrowed
+        let arg = |value: &str, reused: bool| match (by_ref, reused) {
+            (true, _) => format!("&{}", value),
+            (false, true) => format!("{}.clone()", value),
+            (false, false) => value.to_string(),
+        };
+        let call = |a: String, b: String| format!("{}({}, {})", call_path, a, b);
+        let commutative = format!(
+            "prop_assert_eq!({}, {});",
+            call(arg("a", true), arg("b", true)),
+            call(arg("b", false), arg("a", false))
+        );
+        let associative = format!(
+            "prop_assert_eq!({}, {});",
+            call(
+                arg(&call(arg("a", true), arg("b", true)), false),
+                arg("c", true)
+            ),
+            call(
+                arg("a", false),
+                arg(&call(arg("b", false), arg("c", false)), false)
+            )
+        );
+        let float = matches!(
+            RustGenerator::display_type(func.returns.as_str()).as_str(),
+            "f32" | "f64"
+        );
+        let note = if float {
+            "\n        // Floating-point rounding may need a tolerance instead of exact equality"
+        } else {
+            ""
+        };
+
+        format!(
+            "
+
+        // Algebraic properties of `{function}`; uncomment those it should satisfy{note}
+        // #[test]
+        // fn prop_{name}_commutative(a in {strategy}, b in {strategy}) {{
+        //     {commutative}
+        // }}
+        //
+        // #[test]
+        // fn prop_{name}_associative(a in {strategy}, b in {strategy}, c in {strategy}) {{
+        //     {associative}
+        // }}",
+            function = func.name,
+            note = note,
+            name = test_stem,
+            strategy = strategy,
+            commutative = commutative,
+            associative = associative,
+        )
+    }
+
+    /// Input strategy for a parameter type; references use the referent's strategy.
+    pub fn strategy_for(type_str: &str) -> Option<String> {
+        let display = RustGenerator::display_type(type_str);
+        let t = display.trim_start_matches('&');
+
+        let strategy = match t {
+            "bool" | "char" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+            | "u32" | "u64" | "u128" | "usize" => format!("any::<{}>()", t),
+            // Bounded and finite, so comparisons aren't thrown off by NaN
+            "f32" | "f64" => format!("-1.0e6{t}..1.0e6{t}", t = t),
+            "String" | "str" => "\".{0,32}\"".to_string(),
+            _ => {
+                if let Some(inner) = generic_arg(t, "Vec") {
+                    format!(
+                        "proptest::collection::vec({}, 0..16)",
+                        Self::strategy_for(inner)?
+                    )
+                } else if let Some(inner) = generic_arg(t, "Option") {
+                    format!("proptest::option::of({})", Self::strategy_for(inner)?)
+                } else {
+                    return None;
+                }
+            }
+        };
+
+        Some(strategy)
+    }
+}
+
+/// Owned type of a parameter, normalized: the referent for `&T`, `String` for
+/// `&str`; `None` for `&mut T`.
+fn owned_type(type_str: &str) -> Option<String> {
+    let param = RustGenerator::display_type(type_str);
+    let owned = match param.strip_prefix('&') {
+        Some(_) if param.starts_with("&mut") => return None,
+        Some("str") => "String",
+        Some(referent) => referent,
+        None => param.as_str(),
+    };
+    Some(owned.to_string())
+}
+
+/// `T` from `Outer<T>`.
+fn generic_arg<'a>(t: &'a str, outer: &str) -> Option<&'a str> {
+    t.strip_prefix(outer)?.strip_prefix('<')?.strip_suffix('>')
+}
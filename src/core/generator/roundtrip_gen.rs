@@ -0,0 +1,319 @@
+//! # Round-Trip Tests
+//!
+//! Codecs and conversions usually come in pairs (`encode`/`decode`,
+//! `serialize`/`deserialize`, `to_hex`/`from_hex`, `format`/`parse`,
+//! `celsius_to_fahrenheit`/`fahrenheit_to_celsius`). When both functions of a
+//! pair are in the same module and their signatures fit together, a test pipes
+//! a value through one and back through the other and checks it comes back
+//! unchanged. Unlike the other generated assertions, these need no expected
+//! values filled in.
+
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::core::models::FunctionInfo;
+
+/// Name prefixes (or whole names) of functions and of their inverses.
+const INVERSE_PREFIXES: &[(&str, &str)] = &[
+    ("encode", "decode"),
+    ("serialize", "deserialize"),
+    ("compress", "decompress"),
+    ("encrypt", "decrypt"),
+    ("escape", "unescape"),
+    ("pack", "unpack"),
+    ("to", "from"),
+    ("format", "parse"),
+    ("to_string", "parse"),
+];
+
+/// A function and the function undoing it.
+#[derive(Debug, Clone, Copy)]
+pub struct InversePair<'a> {
+    /// Function applied first, to the generated input
+    pub forward: &'a FunctionInfo,
+    /// Function applied to the output of `forward`
+    pub inverse: &'a FunctionInfo,
+}
+
+/// Generator for round-trip tests of inverse function pairs.
+pub struct RoundtripGenerator;
+
+impl RoundtripGenerator {
+    /// Inverse pairs among `functions`, found by name and checked by signature:
+    /// both take a single argument, and the inverse takes what the forward
+    /// function returns and returns what it takes (`Result` and `Option` are
+    /// unwrapped, references compared with their owned types). The input must
+    /// support `assert_eq!`, which `comparable` decides for types other than
+    /// primitives, strings and vectors of them.
+    pub fn pairs<'a>(
+        functions: &[&'a FunctionInfo],
+        comparable: &dyn Fn(&str) -> bool,
+    ) -> Vec<InversePair<'a>> {
+        let mut pairs = Vec::new();
+        for forward in functions {
+            for name in inverse_names(&forward.name) {
+                let Some(inverse) = functions.iter().find(|f| f.name == name) else {
+                    continue;
+                };
+                if fits(forward, inverse) && comparable_type(&input_type(forward), comparable) {
+                    pairs.push(InversePair { forward, inverse });
+                    break;
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Render the round-trip test of a pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `pair` - A pair returned by [`RoundtripGenerator::pairs`]
+    /// * `test_stem` - Module-qualified name used in the test name
+    /// * `forward_path` - Path used to call the forward function from the test
+    /// * `inverse_path` - Path used to call the inverse function from the test
+    /// * `value_for` - Value of the input type
+    pub fn render(
+        pair: InversePair,
+        test_stem: &str,
+        forward_path: &str,
+        inverse_path: &str,
+        value_for: &dyn Fn(&str) -> String,
+    ) -> String {
+        let input_type = input_type(pair.forward);
+        let forward_param = RustGenerator::display_type(pair.forward.params[0].typ.as_str());
+        let forward_arg = if forward_param.starts_with('&') {
+            "&input"
+        } else if is_primitive(&input_type) {
+            "input"
+        } else {
+            "input.clone()"
+        };
+        let inverse_arg =
+            if RustGenerator::display_type(pair.inverse.params[0].typ.as_str()).starts_with('&') {
+                "&output"
+            } else {
+                "output"
+            };
+        let check = if input_type == "f32" || input_type == "f64" {
+            "assert!((restored - input).abs() < 1e-6);".to_string()
+        } else {
+            "assert_eq!(restored, input);".to_string()
+        };
+
+        format!(
+            "    #[test]
+    fn test_{stem}_roundtrip() {{
+        // `{inverse}` should undo `{forward}`
+        let input: {typ} = {value};
+        let output = {forward_path}({forward_arg}){forward_unwrap};
+        let restored = {inverse_path}({inverse_arg}){inverse_unwrap};
+        {check}
+    }}",
+            stem = test_stem,
+            forward = pair.forward.name,
+            inverse = pair.inverse.name,
+            typ = input_type,
+            value = value_for(&input_type),
+            forward_path = forward_path,
+            forward_arg = forward_arg,
+            forward_unwrap = unwrap_suffix(pair.forward.returns.as_str()),
+            inverse_path = inverse_path,
+            inverse_arg = inverse_arg,
+            inverse_unwrap = unwrap_suffix(pair.inverse.returns.as_str()),
+            check = check,
+        )
+    }
+}
+
+/// Names the inverse of `name` could have: `decode_json` for `encode_json`,
+/// `from_hex` for `to_hex`, `b_to_a` for `a_to_b`.
+fn inverse_names(name: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for (prefix, inverse) in INVERSE_PREFIXES {
+        if name == *prefix {
+            names.push(inverse.to_string());
+        } else if let Some(rest) = name.strip_prefix(&format!("{}_", prefix)) {
+            names.push(format!("{}_{}", inverse, rest));
+        }
+    }
+    // `a_to_b`/`b_to_a` are symmetric; only the alphabetically first is the forward one
+    if let Some((from, to)) = name.split_once("_to_") {
+        if !from.is_empty() && !to.is_empty() && from < to {
+            names.push(format!("{}_to_{}", to, from));
+        }
+    }
+    names
+}
+
+/// Whether the signatures of `forward` and `inverse` undo each other.
+fn fits(forward: &FunctionInfo, inverse: &FunctionInfo) -> bool {
+    let plain = |f: &FunctionInfo| {
+        f.params.len() == 1
+            && !f.is_async
+            && f.self_type.is_none()
+            && !RustGenerator::display_type(f.params[0].typ.as_str()).starts_with("&mut")
+    };
+    plain(forward)
+        && plain(inverse)
+        && owned(&unwrapped(forward.returns.as_str()))
+            == owned(&RustGenerator::display_type(inverse.params[0].typ.as_str()))
+        && owned(&unwrapped(inverse.returns.as_str())) == input_type(forward)
+}
+
+/// The owned type of the forward function's parameter, bound as the test input.
+fn input_type(forward: &FunctionInfo) -> String {
+    owned(&RustGenerator::display_type(forward.params[0].typ.as_str()))
+}
+
+/// The value type inside `Result` or `Option`, normalized.
+fn unwrapped(type_str: &str) -> String {
+    let t = RustGenerator::display_type(type_str);
+    for wrapper in ["Result<", "Option<"] {
+        if let Some(inner) = t.strip_prefix(wrapper).and_then(|t| t.strip_suffix('>')) {
+            let mut depth = 0usize;
+            for (i, ch) in inner.char_indices() {
+                match ch {
+                    '<' | '(' | '[' => depth += 1,
+                    '>' | ')' | ']' => depth = depth.saturating_sub(1),
+                    ',' if depth == 0 => return inner[..i].to_string(),
+                    _ => {}
+                }
+            }
+            return inner.to_string();
+        }
+    }
+    t
+}
+
+/// `.unwrap()` for `Result` and `Option` returns.
+fn unwrap_suffix(type_str: &str) -> &'static str {
+    let t = RustGenerator::display_type(type_str);
+    if t.starts_with("Result<") || t.starts_with("Option<") {
+        ".unwrap()"
+    } else {
+        ""
+    }
+}
+
+/// Owned counterpart of a normalized type: `String` for `&str`, `Vec<T>` for `&[T]`,
+/// `T` for `&T`.
+fn owned(type_str: &str) -> String {
+    let Some(referenced) = type_str.strip_prefix('&') else {
+        return type_str.to_string();
+    };
+    let referenced = match referenced.strip_prefix('\'') {
+        Some(lifetime) => lifetime
+            .trim_start_matches(|c: char| c.is_alphanumeric() || c == '_')
+            .trim_start(),
+        None => referenced,
+    };
+    if referenced == "str" {
+        "String".to_string()
+    } else if let Some(item) = referenced
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+    {
+        format!("Vec<{}>", item)
+    } else {
+        referenced.to_string()
+    }
+}
+
+fn is_primitive(type_str: &str) -> bool {
+    [
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64", "bool", "char",
+    ]
+    .contains(&type_str)
+}
+
+/// Whether values of an owned type can be compared with `assert_eq!`.
+fn comparable_type(type_str: &str, comparable: &dyn Fn(&str) -> bool) -> bool {
+    if is_primitive(type_str) || type_str == "String" {
+        return true;
+    }
+    if let Some(item) = type_str
+        .strip_prefix("Vec<")
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        return comparable_type(item, comparable);
+    }
+    let name = type_str.rsplit("::").next().unwrap_or(type_str);
+    !name.contains('<') && comparable(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::ParamInfo;
+
+    fn func(name: &str, param: &str, returns: &str) -> FunctionInfo {
+        FunctionInfo {
+            name: name.to_string(),
+            params: vec![ParamInfo {
+                name: "x".to_string(),
+                typ: param.into(),
+            }],
+            returns: returns.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inverse_pairs_are_matched_by_name_and_signature() {
+        let encode = func("encode", "& [u8]", "String");
+        let decode = func("decode", "& str", "Result < Vec < u8 > , DecodeError >");
+        let to_hex = func("to_hex", "u32", "String");
+        let from_hex = func("from_hex", "&str", "Option < u64 >");
+        let c_to_f = func("celsius_to_fahrenheit", "f64", "f64");
+        let f_to_c = func("fahrenheit_to_celsius", "f64", "f64");
+        let format = func("format_config", "&Config", "String");
+        let parse = func("parse_config", "&str", "Config");
+        let functions = [
+            &decode, &encode, &to_hex, &from_hex, &c_to_f, &f_to_c, &format, &parse,
+        ];
+
+        let names = |comparable: &dyn Fn(&str) -> bool| -> Vec<(String, String)> {
+            RoundtripGenerator::pairs(&functions, comparable)
+                .iter()
+                .map(|p| (p.forward.name.clone(), p.inverse.name.clone()))
+                .collect()
+        };
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        // `from_hex` returns u64 for a u32 input, so it isn't an inverse
+        assert_eq!(
+            names(&|_| false),
+            [
+                pair("encode", "decode"),
+                pair("celsius_to_fahrenheit", "fahrenheit_to_celsius")
+            ]
+        );
+        assert!(names(&|t| t == "Config").contains(&pair("format_config", "parse_config")));
+    }
+
+    #[test]
+    fn test_roundtrip_pipes_output_back_through_the_inverse() {
+        let encode = func("encode", "& [u8]", "String");
+        let decode = func("decode", "& str", "Result < Vec < u8 > , DecodeError >");
+        let pair = RoundtripGenerator::pairs(&[&encode, &decode], &|_| false)[0];
+        let rendered = RoundtripGenerator::render(
+            pair,
+            "codec_encode_decode",
+            "codec::encode",
+            "codec::decode",
+            &|_| "vec![1, 2, 3]".to_string(),
+        );
+        assert!(rendered.contains("fn test_codec_encode_decode_roundtrip()"));
+        assert!(rendered.contains("let input: Vec<u8> = vec![1, 2, 3];"));
+        assert!(rendered.contains("let output = codec::encode(&input);"));
+        assert!(rendered.contains("let restored = codec::decode(&output).unwrap();"));
+        assert!(rendered.contains("assert_eq!(restored, input);"));
+
+        let c_to_f = func("celsius_to_fahrenheit", "f64", "f64");
+        let f_to_c = func("fahrenheit_to_celsius", "f64", "f64");
+        let pair = RoundtripGenerator::pairs(&[&f_to_c, &c_to_f], &|_| false)[0];
+        let rendered = RoundtripGenerator::render(pair, "t", "c2f", "f2c", &|_| "0.0".into());
+        assert!(rendered.contains("let output = c2f(input);"));
+        assert!(rendered.contains("assert!((restored - input).abs() < 1e-6);"));
+    }
+}
@@ -0,0 +1,189 @@
+//! # Type Mapping Suggestions
+//!
+//! Turns types the generator can't synthesize into a config snippet that can be
+//! pasted into `auto_test.toml`, using constructors found in the project.
+
+use crate::core::analyzer::ConstructorIndex;
+
+/// Where an unsupported type appears in a function signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TypeUse {
+    /// A parameter that needs a value (`[types.mappings]`).
+    Param,
+    /// A return type that needs an assertion (`[generation.custom_assertions]`).
+    Return,
+}
+
+/// Render `[types.mappings]` and `[generation.custom_assertions]` entries for
+/// the given unsupported types.
+///
+/// # Arguments
+///
+/// * `types` - Unsupported types by use, without references
+/// * `index` - Constructors defined in the project
+/// * `value_for` - Value expression for a constructor parameter type
+pub fn config_snippet(
+    types: &[(TypeUse, String)],
+    index: &ConstructorIndex,
+    value_for: &dyn Fn(&str) -> String,
+) -> String {
+    let mut sections = Vec::new();
+
+    let params: Vec<String> = types
+        .iter()
+        .filter(|(use_, name)| *use_ == TypeUse::Param && suggestible(name))
+        .map(|(_, name)| {
+            let (expr, note) = construct(name, index, value_for);
+            format!("{} = {}  # {}", quote(name), quote(&expr), note)
+        })
+        .collect();
+    if !params.is_empty() {
+        sections.push(format!("[types.mappings]\n{}", params.join("\n")));
+    }
+
+    let returns: Vec<String> = types
+        .iter()
+        .filter(|(use_, name)| *use_ == TypeUse::Return && suggestible(name))
+        .map(|(_, name)| {
+            let (expr, note) = construct(name, index, value_for);
+            let assertion = format!("assert_eq!(result, {})", expr);
+            format!("{} = {}  # {}", quote(name), quote(&assertion), note)
+        })
+        .collect();
+    if !returns.is_empty() {
+        sections.push(format!(
+            "[generation.custom_assertions]\n{}",
+            returns.join("\n")
+        ));
+    }
+
+    sections.join("\n\n")
+}
+
+/// Standard library generics, which have no constructor worth suggesting.
+const STD_GENERICS: &[&str] = &[
+    "Option", "Result", "Vec", "VecDeque", "HashMap", "HashSet", "BTreeMap", "BTreeSet", "Box",
+    "Rc", "Arc", "Cow", "Cell", "RefCell", "Mutex", "RwLock",
+];
+
+/// Whether `type_name` can get a `Type::constructor()` suggestion.
+///
+/// Slices, tuples, `impl`/`dyn` types and std generics such as `Option<T>`
+/// can't be built that way, so they are left out of the snippet.
+fn suggestible(type_name: &str) -> bool {
+    let Ok(syn::Type::Path(path)) = syn::parse_str::<syn::Type>(type_name) else {
+        return false;
+    };
+    match path.path.segments.last() {
+        Some(last) if last.arguments.is_empty() => true,
+        Some(last) => !STD_GENERICS.contains(&last.ident.to_string().as_str()),
+        None => false,
+    }
+}
+
+/// An expression building `type_name`, and where it came from.
+fn construct(
+    type_name: &str,
+    index: &ConstructorIndex,
+    value_for: &dyn Fn(&str) -> String,
+) -> (String, String) {
+    // `Html<'static>` is built as `Html::new()`
+    let type_name = type_name.split('<').next().unwrap_or(type_name);
+    if let Some(ctor) = index.best_for(type_name) {
+        let args: Vec<String> = ctor
+            .params
+            .iter()
+            .map(|p| value_for(p.typ.as_str()))
+            .collect();
+        (
+            format!("{}::{}({})", type_name, ctor.name, args.join(", ")),
+            format!("constructor found in `impl {}`", type_name),
+        )
+    } else if index.implements_default(type_name) {
+        (
+            format!("{}::default()", type_name),
+            "implements Default".to_string(),
+        )
+    } else if index.defines(type_name) {
+        (
+            format!("{}::new()", type_name),
+            "no public constructor found; adjust".to_string(),
+        )
+    } else {
+        (
+            format!("{}::new()", type_name),
+            "not defined in this project; adjust".to_string(),
+        )
+    }
+}
+
+/// A quoted TOML string.
+fn quote(s: &str) -> String {
+    toml_edit::Value::from(s).to_string().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_uses_project_constructors() {
+        let ast: syn::File = syn::parse_str(
+            r#"
+            pub struct Client;
+            impl Client { pub fn new(url: &str, retries: u32) -> Self { Client } }
+            #[derive(Default)]
+            pub struct Options;
+            "#,
+        )
+        .unwrap();
+        let mut index = ConstructorIndex::default();
+        index.add_items(&ast.items);
+
+        let value_for = |t: &str| match t {
+            "&str" => "\"test\"".to_string(),
+            _ => "0".to_string(),
+        };
+        let snippet = config_snippet(
+            &[
+                (TypeUse::Param, "Client".to_string()),
+                (TypeUse::Param, "Socket".to_string()),
+                (TypeUse::Return, "Options".to_string()),
+            ],
+            &index,
+            &value_for,
+        );
+
+        assert_eq!(
+            snippet,
+            r#"[types.mappings]
+"Client" = 'Client::new("test", 0)'  # constructor found in `impl Client`
+"Socket" = "Socket::new()"  # not defined in this project; adjust
+
+[generation.custom_assertions]
+"Options" = "assert_eq!(result, Options::default())"  # implements Default"#
+        );
+    }
+
+    #[test]
+    fn test_snippet_skips_types_without_constructors() {
+        let value_for = |_: &str| "0".to_string();
+        let snippet = config_snippet(
+            &[
+                (TypeUse::Param, "[i32]".to_string()),
+                (TypeUse::Param, "(i32, i32)".to_string()),
+                (TypeUse::Param, "impl Fn()".to_string()),
+                (TypeUse::Return, "Option<i32>".to_string()),
+                (TypeUse::Return, "Html<'static>".to_string()),
+            ],
+            &ConstructorIndex::default(),
+            &value_for,
+        );
+
+        assert_eq!(
+            snippet,
+            r#"[generation.custom_assertions]
+"Html<'static>" = "assert_eq!(result, Html::new())"  # not defined in this project; adjust"#
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! # Case Table Tests
+//!
+//! With `generation.strategy = "table"`, each function gets a single test
+//! looping over a `cases` table of `(inputs, expected)` rows instead of one test
+//! per example. Inputs are synthesized from edge values of each parameter type
+//! (`0`, `1` and `MAX` for unsigned integers, `""` and `"test"` for strings, ...);
+//! the expected values are left as `todo!()` for the author to fill in.
+//!
+//! With `generation.case_style = "test-case"` the same cases become
+//! [test-case](https://docs.rs/test-case) attributes on a single function.
+
+use crate::core::generator::rust_gen::{AsyncTest, RustGenerator};
+use crate::core::models::FunctionInfo;
+
+/// Generator for case-table tests.
+pub struct TableGenerator;
+
+impl TableGenerator {
+    /// Whether `func` can get a case table: it takes parameters, returns a
+    /// value, and its types can be written in the table's type annotation.
+    pub fn supports(func: &FunctionInfo) -> bool {
+        !func.params.is_empty()
+            && func.returns.as_str() != "()"
+            && nameable(func.returns.as_str())
+            && func.params.iter().all(|p| nameable(p.typ.as_str()))
+    }
+
+    /// Render the case-table test of `func`.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - A function for which [`TableGenerator::supports`] holds
+    /// * `test_name` - Name of the test function
+    /// * `call_path` - Path used to call the function from the test
+    /// * `async_test` - How the test drives the call if `func` is async
+    /// * `value_for` - Value of a type the table has no edge values for
+    pub fn render(
+        func: &FunctionInfo,
+        test_name: &str,
+        call_path: &str,
+        async_test: AsyncTest,
+        value_for: &dyn Fn(&str) -> String,
+    ) -> String {
+        let columns = Self::columns(func, value_for, |i| format!("input.{}", i));
+        let tuple = |items: Vec<String>| match items.len() {
+            1 => format!("({},)", items[0]),
+            _ => format!("({})", items.join(", ")),
+        };
+
+        let types = tuple(columns.iter().map(|c| c.typ.clone()).collect());
+        let cases: String = Self::rows(&columns)
+            .into_iter()
+            .map(|row| format!("            ({}, todo!()),\n", tuple(row)))
+            .collect();
+        let args: Vec<&str> = columns.iter().map(|c| c.arg.as_str()).collect();
+        let binding = if columns.iter().any(|c| c.arg.starts_with("&mut ")) {
+            "mut input"
+        } else {
+            "input"
+        };
+        let (attr, async_kw, call) = async_call(
+            func,
+            async_test,
+            format!("{}({})", call_path, args.join(", ")),
+        );
+
+        format!(
+            "    {attr}
+    #[allow(unreachable_code, unused_variables)]
+    {async_kw}fn {name}() {{
+        // Fill in the expected result of each case
+        let cases: Vec<({types}, {returns})> = vec![
+{cases}        ];
+        for ({binding}, expected) in cases {{
+            let result = {call};
+            assert_eq!(result, expected);
+        }}
+    }}",
+            attr = attr,
+            async_kw = async_kw,
+            name = test_name,
+            types = types,
+            returns = RustGenerator::display_type(func.returns.as_str()),
+            cases = cases,
+            binding = binding,
+            call = call,
+        )
+    }
+
+    /// Render the cases of `func` as `#[test_case(inputs, todo!() ; "case N")]`
+    /// attributes on one test function; the last argument is the expected result.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - A function for which [`TableGenerator::supports`] holds
+    /// * `test_name` - Name of the test function
+    /// * `call_path` - Path used to call the function from the test
+    /// * `async_test` - How the test drives the call if `func` is async
+    /// * `value_for` - Value of a type the table has no edge values for
+    pub fn render_test_case(
+        func: &FunctionInfo,
+        test_name: &str,
+        call_path: &str,
+        async_test: AsyncTest,
+        value_for: &dyn Fn(&str) -> String,
+    ) -> String {
+        let bindings = RustGenerator::param_bindings(func);
+        let columns = Self::columns(func, value_for, |i| bindings[i].clone());
+        let cases: String = Self::rows(&columns)
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                format!(
+                    "    #[test_case({}, todo!() ; \"case {}\")]\n",
+                    row.join(", "),
+                    i + 1
+                )
+            })
+            .collect();
+        let mut params: Vec<String> = columns
+            .iter()
+            .zip(&bindings)
+            .map(|(c, name)| {
+                let binding = if c.arg.starts_with("&mut ") {
+                    "mut "
+                } else {
+                    ""
+                };
+                format!("{}{}: {}", binding, name, c.typ)
+            })
+            .collect();
+        params.push(format!(
+            "expected: {}",
+            RustGenerator::display_type(func.returns.as_str())
+        ));
+        let args: Vec<&str> = columns.iter().map(|c| c.arg.as_str()).collect();
+        let (attr, async_kw, call) = async_call(
+            func,
+            async_test,
+            format!("{}({})", call_path, args.join(", ")),
+        );
+        // `#[test_case]` adds `#[test]` itself; async tests still need their runtime's
+        let attr = if async_kw.is_empty() {
+            String::new()
+        } else {
+            format!("    {}\n", attr)
+        };
+
+        format!(
+            "{cases}{attr}    #[allow(unreachable_code)]
+    {async_kw}fn {name}({params}) {{
+        // Fill in the expected result of each case
+        let result = {call};
+        assert_eq!(result, expected);
+    }}",
+            cases = cases,
+            attr = attr,
+            async_kw = async_kw,
+            name = test_name,
+            params = params.join(", "),
+            call = call,
+        )
+    }
+
+    /// One column per parameter of `func`; `access` names the value of the i-th.
+    fn columns(
+        func: &FunctionInfo,
+        value_for: &dyn Fn(&str) -> String,
+        access: impl Fn(usize) -> String,
+    ) -> Vec<Column> {
+        func.params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| Column::new(access(i), p.typ.as_str(), value_for))
+            .collect()
+    }
+
+    /// Input rows: row N takes each column's Nth edge value, wrapping around, until
+    /// every value of the longest column is used.
+    fn rows(columns: &[Column]) -> Vec<Vec<String>> {
+        let rows = columns.iter().map(|c| c.values.len()).max().unwrap_or(1);
+        (0..rows)
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|c| c.values[row % c.values.len()].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Edge values of a type, most ordinary first.
+    fn edge_values(typ: &str, value_for: &dyn Fn(&str) -> String) -> Vec<String> {
+        let compact: String = typ.chars().filter(|c| !c.is_whitespace()).collect();
+        match compact.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
+                vec!["0".into(), "1".into(), format!("{}::MAX", compact)]
+            }
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => vec![
+                "0".into(),
+                "-1".into(),
+                format!("{}::MIN", compact),
+                format!("{}::MAX", compact),
+            ],
+            "f32" | "f64" => vec!["0.0".into(), "-1.5".into(), format!("{}::MAX", compact)],
+            "bool" => vec!["false".into(), "true".into()],
+            "char" => vec!["'a'".into(), "' '".into()],
+            "&str" | "&'staticstr" => vec!["\"\"".into(), "\"test\"".into()],
+            "String" => vec!["String::new()".into(), "\"test\".to_string()".into()],
+            _ => {
+                if let Some(inner) = generic_arg(&compact, "Vec") {
+                    let item = Self::edge_values(inner, value_for).swap_remove(0);
+                    vec!["Vec::new()".into(), format!("vec![{}]", item)]
+                } else if let Some(inner) = generic_arg(&compact, "Option") {
+                    let value = Self::edge_values(inner, value_for).swap_remove(0);
+                    vec!["None".into(), format!("Some({})", value)]
+                } else {
+                    vec![value_for(typ)]
+                }
+            }
+        }
+    }
+}
+
+/// Test attribute, `async` keyword and call expression for calling `func`:
+/// awaited in an `async fn` test, or passed to a `block_on` executor.
+fn async_call(
+    func: &FunctionInfo,
+    async_test: AsyncTest,
+    call: String,
+) -> (String, &'static str, String) {
+    match async_test {
+        _ if !func.is_async => ("#[test]".to_string(), "", call),
+        AsyncTest::BlockOn(executor) => {
+            ("#[test]".to_string(), "", format!("{}({})", executor, call))
+        }
+        _ => (async_test.attribute(), "async ", format!("{}.await", call)),
+    }
+}
+
+/// How one parameter is stored in the table and passed to the function.
+struct Column {
+    /// Type of the table column; references are stored as owned values.
+    typ: String,
+    /// Argument expression passing the stored value to the function.
+    arg: String,
+    values: Vec<String>,
+}
+
+impl Column {
+    fn new(field: String, param_type: &str, value_for: &dyn Fn(&str) -> String) -> Self {
+        let display = RustGenerator::display_type(param_type);
+        let compact: String = display.chars().filter(|c| !c.is_whitespace()).collect();
+        let (typ, arg) = if compact == "&str" || compact == "&'staticstr" {
+            ("&str".to_string(), field)
+        } else if let Some(inner) = display.strip_prefix("&mut ") {
+            (owned_type(inner.trim()), format!("&mut {}", field))
+        } else if let Some(inner) = display.strip_prefix('&') {
+            (owned_type(inner.trim()), format!("&{}", field))
+        } else {
+            (display.clone(), field)
+        };
+        let values = TableGenerator::edge_values(&typ, value_for);
+        Self { typ, arg, values }
+    }
+}
+
+/// Owned type holding a borrowed one: `Vec<T>` for a `[T]` slice.
+fn owned_type(borrowed: &str) -> String {
+    match borrowed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        Some(item) => format!("Vec<{}>", item.trim()),
+        None => borrowed.to_string(),
+    }
+}
+
+/// Whether a type can be named in a `let` type annotation: no `impl Trait`,
+/// `Self` or named lifetimes.
+fn nameable(typ: &str) -> bool {
+    let compact: String = typ.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut words = typ.split(|c: char| !c.is_alphanumeric() && c != '_');
+    !words.any(|word| word == "impl" || word == "Self")
+        && compact
+            .match_indices('\'')
+            .all(|(i, _)| compact[i..].starts_with("'static") || compact[i..].starts_with("'_"))
+}
+
+/// `T` from `Outer<T>`.
+fn generic_arg<'a>(t: &'a str, outer: &str) -> Option<&'a str> {
+    t.strip_prefix(outer)?.strip_prefix('<')?.strip_suffix('>')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::ParamInfo;
+
+    fn func(params: &[&str], returns: &str) -> FunctionInfo {
+        FunctionInfo {
+            name: "clamp".to_string(),
+            params: params
+                .iter()
+                .map(|typ| ParamInfo {
+                    name: "x".to_string(),
+                    typ: (*typ).into(),
+                })
+                .collect(),
+            returns: returns.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_case_table_cycles_edge_values() {
+        let clamp = func(&["u32", "& str", "& [u8]"], "Option < u32 >");
+        assert!(TableGenerator::supports(&clamp));
+        let rendered = TableGenerator::render(
+            &clamp,
+            "test_clamp_table",
+            "math::clamp",
+            AsyncTest::Tokio(""),
+            &|t| format!("{}::default()", t),
+        );
+        assert!(rendered.contains("let cases: Vec<((u32, &str, Vec<u8>), Option<u32>)> = vec!["));
+        assert!(rendered.contains("((0, \"\", Vec::new()), todo!()),"));
+        assert!(rendered.contains("((1, \"test\", vec![0]), todo!()),"));
+        assert!(rendered.contains("((u32::MAX, \"\", Vec::new()), todo!()),"));
+        assert!(rendered.contains("for (input, expected) in cases {"));
+        assert!(rendered.contains("let result = math::clamp(input.0, input.1, &input.2);"));
+
+        let single = func(&["&mut Config"], "bool");
+        let rendered = TableGenerator::render(&single, "t", "apply", AsyncTest::Tokio(""), &|t| {
+            format!("{}::default()", t)
+        });
+        assert!(rendered.contains("Vec<((Config,), bool)>"));
+        assert!(rendered.contains("((Config::default(),), todo!()),"));
+        assert!(rendered.contains("for (mut input, expected) in cases {"));
+
+        let rendered = TableGenerator::render_test_case(
+            &clamp,
+            "test_clamp_table",
+            "clamp",
+            AsyncTest::Tokio(""),
+            &|t| format!("{}::default()", t),
+        );
+        assert!(
+            rendered.starts_with("    #[test_case(0, \"\", Vec::new(), todo!() ; \"case 1\")]\n")
+        );
+        assert!(rendered.contains("#[test_case(u32::MAX, \"\", Vec::new(), todo!() ; \"case 3\")]"));
+        assert!(rendered.contains(
+            "fn test_clamp_table(x: u32, param_1: &str, param_2: Vec<u8>, expected: Option<u32>)"
+        ));
+        assert!(rendered.contains("let result = clamp(x, param_1, &param_2);"));
+
+        assert!(!TableGenerator::supports(&func(&[], "u32")));
+        assert!(!TableGenerator::supports(&func(&["u32"], "()")));
+        assert!(!TableGenerator::supports(&func(&["u32"], "& 'a str")));
+        assert!(!TableGenerator::supports(&func(
+            &["impl Into < String >"],
+            "u32"
+        )));
+    }
+}
@@ -0,0 +1,111 @@
+//! # Unit Tests for Private Functions
+//!
+//! Integration tests in `tests/` only see the crate's public API, so private
+//! and `pub(crate)` functions, included with `include_private`, are tested from
+//! a module appended to their own source file instead:
+//!
+//! ```text
+//! // Generated by autotest for the private functions above; regenerated on every run.
+//! #[cfg(test)]
+//! mod autotest_tests {
+//!     use super::*;
+//!
+//!     #[test] fn test_parse_header() { ... }
+//! }
+//! ```
+//!
+//! Each run replaces that module and leaves the rest of the file as it is.
+
+use crate::core::models::TestFile;
+
+/// Name of the generated test module.
+pub const MODULE_NAME: &str = "autotest_tests";
+
+/// Line above the generated module, marking where it starts.
+pub const MARKER: &str =
+    "// Generated by autotest for the private functions above; regenerated on every run.";
+
+/// Whether `file` is a source file carrying generated unit tests rather than a
+/// test file, so it must not get a file header or have its line endings changed.
+pub fn is_source_file(file: &TestFile) -> bool {
+    file.content.contains(MARKER)
+}
+
+/// `source` with its generated module holding `tests` (test functions indented
+/// for a module body), replacing the one from an earlier run or appended at the
+/// end. The module uses the file's line endings.
+pub fn splice(source: &str, tests: &str) -> String {
+    let newline = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let source = source.replace("\r\n", "\n");
+
+    // The module ends at the first unindented closing brace after the marker
+    let (before, after) = match source.find(MARKER) {
+        Some(start) => {
+            let end = source[start..]
+                .find("\n}\n")
+                .map(|end| start + end + 3)
+                .or_else(|| source[start..].ends_with("\n}").then_some(source.len()))
+                .unwrap_or(source.len());
+            (&source[..start], &source[end..])
+        }
+        None => (source.as_str(), ""),
+    };
+
+    let mut spliced = before.trim_end().to_string();
+    if !spliced.is_empty() {
+        spliced.push_str("\n\n");
+    }
+    spliced.push_str(&format!(
+        "{}\n#[cfg(test)]\nmod {} {{\n    use super::*;\n\n{}}}\n",
+        MARKER,
+        MODULE_NAME,
+        tests.trim_start_matches('\n')
+    ));
+    if !after.trim().is_empty() {
+        spliced.push('\n');
+        spliced.push_str(after.trim_start_matches('\n'));
+    }
+    spliced.replace('\n', newline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_is_appended_then_replaced() {
+        let source = "fn parse(s: &str) -> u8 {\n    s.len() as u8\n}\n";
+        let first = splice(source, "    #[test] fn test_parse() {}\n");
+        assert_eq!(
+            first,
+            format!(
+                "fn parse(s: &str) -> u8 {{\n    s.len() as u8\n}}\n\n{}\n#[cfg(test)]\nmod autotest_tests {{\n    use super::*;\n\n    #[test] fn test_parse() {{}}\n}}\n",
+                MARKER
+            )
+        );
+        assert!(is_source_file(&TestFile {
+            path: "src/lib.rs".to_string(),
+            content: first.clone(),
+        }));
+
+        // Only the generated module changes, also with code added after it
+        let edited = format!("{}\nfn later() {{}}\n", first);
+        let second = splice(&edited, "    #[test] fn test_parse_again() {}\n");
+        assert_eq!(second.matches(MARKER).count(), 1);
+        assert!(second.starts_with("fn parse(s: &str) -> u8 {"));
+        assert!(second.contains("    #[test] fn test_parse_again() {}\n}\n\nfn later() {}\n"));
+        assert!(!second.contains("test_parse()"));
+        assert_eq!(
+            splice(&second, "    #[test] fn test_parse_again() {}\n"),
+            second
+        );
+
+        let crlf = splice("fn a() {}\r\n", "    #[test] fn test_a() {}\n");
+        assert!(crlf.ends_with("fn test_a() {}\r\n}\r\n"));
+        assert!(!crlf.replace("\r\n", "").contains('\n'));
+    }
+}
@@ -0,0 +1,390 @@
+//! # Workspace Generation
+//!
+//! A workspace root is generated one crate at a time. With
+//! `generation.output_scope = "member"` each crate gets tests in its own
+//! `tests/` directory; with `"workspace"` every crate's tests are collected into
+//! a central test crate in the root's output directory, whose `Cargo.toml`
+//! depends on all members.
+
+use crate::config::Config;
+use crate::core::analyzer::CrateManifest;
+use crate::core::generator::layout::{NestedLayout, GENERATED_DIR};
+use crate::core::generator::plugin::PluginHost;
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::core::models::{ProjectInfo, TestFile};
+use crate::core::report::GenerationReport;
+use crate::error::Result;
+use crate::utils::log::status;
+use std::path::{Component, Path, PathBuf};
+
+/// Package name of the generated central test crate.
+pub const WORKSPACE_TESTS_PACKAGE: &str = "workspace-tests";
+
+/// A crate of the workspace being generated.
+struct Member {
+    dir: PathBuf,
+    manifest: CrateManifest,
+}
+
+/// Generator for workspace roots.
+pub struct WorkspaceGenerator;
+
+impl WorkspaceGenerator {
+    /// Generate tests for every crate of a workspace.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Path to the workspace root
+    /// * `config` - Configuration for generation behavior
+    /// * `manifest` - The root `Cargo.toml`
+    /// * `report` - Receives one entry per analyzed function
+    /// * `plugins` - Generator plugins of the workspace
+    /// * `analysis` - Saved analysis of the workspace, instead of analyzing the sources
+    ///
+    /// # Returns
+    ///
+    /// The generated test files of all crates, or the first error
+    pub fn generate(
+        root: &Path,
+        config: &Config,
+        manifest: &CrateManifest,
+        report: &mut GenerationReport,
+        plugins: &PluginHost,
+        analysis: Option<&ProjectInfo>,
+    ) -> Result<Vec<TestFile>> {
+        let member_dirs = manifest.member_dirs(root);
+        let central = config.generation.output_scope == "workspace";
+        status!(
+            "Workspace detected with {} members (output scope: {})",
+            member_dirs.len(),
+            config.generation.output_scope
+        );
+
+        let mut members = Vec::new();
+        for dir in &member_dirs {
+            match CrateManifest::load(dir)? {
+                Some(mut member) => {
+                    if member.inherits_edition && member.edition.is_none() {
+                        member.edition = manifest.workspace_edition.clone();
+                    }
+                    members.push(Member {
+                        dir: dir.clone(),
+                        manifest: member,
+                    })
+                }
+                None => status!("Warning: no Cargo.toml in member {}", dir.display()),
+            }
+        }
+
+        let mut files = Vec::new();
+        let mut config = config.clone();
+        if central {
+            // Tests are compiled by the central crate, whatever the members' editions
+            config
+                .generation
+                .edition
+                .get_or_insert_with(|| "2021".to_string());
+        }
+        let config = &config;
+
+        // A root package is analyzed without its members' sources
+        if manifest.name.is_some() {
+            let mut root_config = config.clone();
+            for dir in &member_dirs {
+                root_config
+                    .skip_patterns
+                    .push(format!("{}/*", dir.to_string_lossy()));
+            }
+            let root_files = RustGenerator::generate_crate(
+                root,
+                &root_config,
+                Some(manifest),
+                report,
+                plugins,
+                analysis,
+            )?;
+            files.extend(Self::place(
+                root_files, root, manifest, root, config, central,
+            ));
+        }
+
+        for member in &members {
+            status!("Generating tests for {}", member.dir.display());
+            let mut member_config = config.clone();
+            member_config.generation.crate_name = None;
+            if central {
+                // Each member's files become separate test targets of one crate,
+                // so a shared tests/common module can't be resolved
+                member_config.generation.shared_fixtures = false;
+            }
+            let member_files = RustGenerator::generate_crate(
+                &member.dir,
+                &member_config,
+                Some(&member.manifest),
+                report,
+                plugins,
+                analysis,
+            )?;
+            files.extend(Self::place(
+                member_files,
+                &member.dir,
+                &member.manifest,
+                root,
+                config,
+                central,
+            ));
+        }
+
+        if central {
+            let crate_dir = root.join(&config.output_dir);
+            if crate_dir.join("Cargo.toml").exists() {
+                status!(
+                    "Note: keeping existing {}; make sure it depends on every member",
+                    crate_dir.join("Cargo.toml").display()
+                );
+            } else {
+                let root_member = manifest.name.is_some().then(|| Member {
+                    dir: root.to_path_buf(),
+                    manifest: manifest.clone(),
+                });
+                let all: Vec<&Member> = root_member.iter().chain(&members).collect();
+                files.push(TestFile {
+                    path: crate_dir.join("Cargo.toml").to_string_lossy().to_string(),
+                    content: Self::render_manifest(&all, &config.output_dir, root),
+                });
+            }
+
+            let listed = manifest
+                .workspace_members
+                .iter()
+                .any(|m| Path::new(m) == Path::new(&config.output_dir));
+            if !listed {
+                status!(
+                    "Note: add \"{}\" to [workspace] members to build the generated test crate",
+                    config.output_dir
+                );
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Move a crate's generated files into the central test crate when the
+    /// output scope is `workspace`; member-scoped and proc-macro files stay put.
+    fn place(
+        files: Vec<TestFile>,
+        crate_dir: &Path,
+        manifest: &CrateManifest,
+        root: &Path,
+        config: &Config,
+        central: bool,
+    ) -> Vec<TestFile> {
+        // trybuild harnesses glob paths relative to the proc-macro crate
+        if !central || manifest.is_proc_macro {
+            return files;
+        }
+
+        files
+            .into_iter()
+            .map(|file| {
+                match Self::central_path(Path::new(&file.path), crate_dir, manifest, root, config) {
+                    Some(path) => TestFile {
+                        path: path.to_string_lossy().to_string(),
+                        content: file.content,
+                    },
+                    None => file,
+                }
+            })
+            .collect()
+    }
+
+    /// Where the workspace output scope moves `path`, a file generated into the
+    /// crate's output directory: into the central crate's `tests/`, named after
+    /// the crate. `None` for files outside the output directory.
+    pub(crate) fn central_path(
+        path: &Path,
+        crate_dir: &Path,
+        manifest: &CrateManifest,
+        root: &Path,
+        config: &Config,
+    ) -> Option<PathBuf> {
+        let rel = path.strip_prefix(crate_dir.join(&config.output_dir)).ok()?;
+        let prefix = manifest
+            .name
+            .as_deref()
+            .unwrap_or("crate")
+            .replace('-', "_");
+        Some(
+            root.join(&config.output_dir)
+                .join("tests")
+                .join(Self::prefixed(rel, &prefix)),
+        )
+    }
+
+    /// Prefix a path relative to the output directory with the crate name: the
+    /// nested layout's `generated` tree is renamed after the crate, flat files
+    /// get a `<crate>_` file name prefix.
+    fn prefixed(rel: &Path, prefix: &str) -> PathBuf {
+        let mut components = rel.components();
+        let Some(Component::Normal(first)) = components.next() else {
+            return rel.to_path_buf();
+        };
+        let rest = components.as_path();
+        let first = first.to_string_lossy();
+
+        let renamed = if first == GENERATED_DIR {
+            prefix.to_string()
+        } else if Path::new(first.as_ref()) == NestedLayout::root_file() {
+            format!("{}.rs", prefix)
+        } else {
+            format!("{}_{}", prefix, first)
+        };
+        // Joining an empty rest would leave a trailing separator on a file name
+        if rest.as_os_str().is_empty() {
+            PathBuf::from(renamed)
+        } else {
+            PathBuf::from(renamed).join(rest)
+        }
+    }
+
+    /// `Cargo.toml` of the central test crate, with a path dependency per crate.
+    fn render_manifest(members: &[&Member], output_dir: &str, root: &Path) -> String {
+        let up = "../".repeat(Path::new(output_dir).components().count());
+        let mut content = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.0.0\"\nedition = \"2021\"\npublish = false\n\n[dependencies]\n",
+            WORKSPACE_TESTS_PACKAGE
+        );
+
+        for member in members {
+            let Some(name) = &member.manifest.name else {
+                continue;
+            };
+            let rel = member
+                .dir
+                .strip_prefix(root)
+                .unwrap_or(&member.dir)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let path = if rel.is_empty() {
+                up.trim_end_matches('/').to_string()
+            } else {
+                format!("{}{}", up, rel)
+            };
+            content.push_str(&format!("{} = {{ path = \"{}\" }}\n", name, path));
+        }
+
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_workspace_scope_collects_members_into_central_crate() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        for name in ["alpha", "beta-core"] {
+            write(
+                &root.join("crates").join(name).join("Cargo.toml"),
+                &format!("[package]\nname = \"{}\"\n", name),
+            );
+            write(
+                &root.join("crates").join(name).join("src/lib.rs"),
+                "pub fn answer() -> u32 { 42 }\n",
+            );
+        }
+
+        let mut config = Config {
+            parallel: false,
+            ..Default::default()
+        };
+        config.generation.output_scope = "workspace".to_string();
+        let manifest = CrateManifest::load(root).unwrap().unwrap();
+        let files = WorkspaceGenerator::generate(
+            root,
+            &config,
+            &manifest,
+            &mut GenerationReport::new(root),
+            &PluginHost::default(),
+            None,
+        )
+        .unwrap();
+
+        let find = |rel: &str| {
+            files
+                .iter()
+                .find(|f| f.path == root.join(rel).to_string_lossy())
+                .unwrap_or_else(|| panic!("missing {}", rel))
+        };
+        assert!(find("tests/tests/alpha_integration_tests.rs")
+            .content
+            .starts_with("use alpha::*;"));
+        assert!(find("tests/tests/beta_core_integration_tests.rs")
+            .content
+            .starts_with("use beta_core::*;"));
+        let manifest = &find("tests/Cargo.toml").content;
+        assert!(manifest.contains("alpha = { path = \"../crates/alpha\" }"));
+        assert!(manifest.contains("beta-core = { path = \"../crates/beta-core\" }"));
+    }
+
+    #[test]
+    fn test_root_tests_in_the_central_crate_are_not_existing_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nedition = \"2021\"\n\n[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(&root.join("src/lib.rs"), "pub fn answer() -> u32 { 42 }\n");
+        write(
+            &root.join("crates/util/Cargo.toml"),
+            "[package]\nname = \"util\"\nedition = \"2021\"\n",
+        );
+        write(
+            &root.join("crates/util/src/lib.rs"),
+            "pub fn answer() -> u32 { 7 }\n",
+        );
+
+        let mut config = Config {
+            parallel: false,
+            ..Default::default()
+        };
+        config.generation.output_scope = "workspace".to_string();
+        let manifest = CrateManifest::load(root).unwrap().unwrap();
+        let generate = || {
+            let mut paths: Vec<String> = WorkspaceGenerator::generate(
+                root,
+                &config,
+                &manifest,
+                &mut GenerationReport::new(root),
+                &PluginHost::default(),
+                None,
+            )
+            .unwrap()
+            .into_iter()
+            .filter(|f| f.path.ends_with(".rs"))
+            .map(|f| {
+                write(Path::new(&f.path), &f.content);
+                f.path
+            })
+            .collect();
+            paths.sort();
+            paths
+        };
+
+        let first = generate();
+        assert_eq!(first.len(), 2, "{:?}", first);
+        assert_eq!(generate(), first);
+    }
+}
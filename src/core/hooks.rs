@@ -0,0 +1,198 @@
+//! # Post-Generation Hooks
+//!
+//! Shell commands run after a generation run has written its tests, for custom
+//! post-processing such as formatting or staging them: `generation.post_hooks`
+//! once per written file, then `generation.post_run_hooks` once for the run.
+//! Commands run from the project root with `sh -c` (`cmd /C` on Windows) and
+//! are killed after `generation.hook_timeout_secs`.
+
+use crate::config::Config;
+use crate::utils::log::status;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Outcome of one hook command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookRun {
+    /// The command after placeholder substitution.
+    pub command: String,
+    pub success: bool,
+    /// Exit status, timeout or spawn error, with the command's stderr.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Quote a value so the shell passes it as a single argument.
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Replace `{name}` placeholders of a hook command with shell-quoted values.
+///
+/// # Arguments
+///
+/// * `template` - The configured command, e.g. `rustfmt {file}`
+/// * `placeholders` - Placeholder names and their values
+pub fn substitute(template: &str, placeholders: &[(&str, &[String])]) -> String {
+    let mut command = template.to_string();
+    for (name, values) in placeholders {
+        let quoted: Vec<String> = values.iter().map(|v| shell_quote(v)).collect();
+        command = command.replace(&format!("{{{}}}", name), &quoted.join(" "));
+    }
+    command
+}
+
+/// Run one command in `dir`, killing it once `timeout` has passed.
+pub fn run_command(command: &str, dir: &Path, timeout: Duration) -> HookRun {
+    let failed = |error: String| HookRun {
+        command: command.to_string(),
+        success: false,
+        error: Some(error),
+    };
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let child = shell
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return failed(format!("failed to start: {}", e)),
+    };
+
+    // Drain stderr on another thread so a chatty command can't fill the pipe
+    let mut stderr = child.stderr.take();
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut output);
+        }
+        output
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break Err(format!("timed out after {:?}", timeout));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => break Err(e.to_string()),
+        }
+    };
+    // A killed command's children may still hold stderr open, so don't wait for them
+    let stderr = match status {
+        Ok(_) => reader.join().unwrap_or_default(),
+        Err(_) => String::new(),
+    };
+
+    match status {
+        Ok(status) if status.success() => HookRun {
+            command: command.to_string(),
+            success: true,
+            error: None,
+        },
+        Ok(status) if stderr.trim().is_empty() => failed(status.to_string()),
+        Ok(status) => failed(format!("{}: {}", status, stderr.trim())),
+        Err(error) => failed(error),
+    }
+}
+
+/// Run the configured hooks for the files written by a run.
+///
+/// # Arguments
+///
+/// * `project_path` - Project root, the working directory of the hooks
+/// * `files` - Written test files, relative to the project root
+/// * `config` - Configuration holding the hooks
+///
+/// # Returns
+///
+/// One entry per command run, in order; failures are also printed as warnings
+pub fn run_hooks(project_path: &Path, files: &[String], config: &Config) -> Vec<HookRun> {
+    let generation = &config.generation;
+    let timeout = Duration::from_secs(generation.hook_timeout_secs);
+    let project = std::path::absolute(project_path).unwrap_or_else(|_| project_path.to_path_buf());
+    let project = vec![project.to_string_lossy().to_string()];
+
+    let mut commands = Vec::new();
+    for file in files {
+        let file = std::slice::from_ref(file);
+        for hook in &generation.post_hooks {
+            commands.push(substitute(hook, &[("file", file), ("project", &project)]));
+        }
+    }
+    for hook in &generation.post_run_hooks {
+        commands.push(substitute(hook, &[("files", files), ("project", &project)]));
+    }
+
+    commands
+        .iter()
+        .map(|command| {
+            let run = run_command(command, project_path, timeout);
+            if let Some(error) = &run.error {
+                status!("Warning: hook `{}` failed: {}", command, error);
+            }
+            run
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_quotes_placeholders() {
+        let files = vec!["tests/a.rs".to_string(), "tests/it's.rs".to_string()];
+        assert_eq!(
+            substitute("git add {files}", &[("files", &files)]),
+            if cfg!(windows) {
+                "git add \"tests/a.rs\" \"tests/it's.rs\""
+            } else {
+                "git add 'tests/a.rs' 'tests/it'\\''s.rs'"
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks_report_failures_and_timeouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.generation.post_hooks = vec!["cp {file} {file}.bak".to_string()];
+        config.generation.post_run_hooks = vec!["echo broken >&2; exit 3".to_string()];
+        std::fs::write(dir.path().join("a.rs"), "").unwrap();
+
+        let runs = run_hooks(dir.path(), &["a.rs".to_string()], &config);
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].success);
+        assert!(dir.path().join("a.rs.bak").exists());
+        assert!(!runs[1].success);
+        assert_eq!(runs[1].error.as_deref(), Some("exit status: 3: broken"));
+
+        let run = run_command("sleep 5", dir.path(), Duration::from_millis(100));
+        assert_eq!(run.error.as_deref(), Some("timed out after 100ms"));
+    }
+}
@@ -2,14 +2,20 @@
 //!
 //! Core functionality for analyzing Rust code and generating tests.
 //!
-//! This module contains three main submodules:
+//! This module contains five main submodules:
 //!
 //! ## Modules
 //!
 //! - [`analyzer`]: Parses Rust source code and extracts function signatures
 //! - [`models`]: Data structures representing analyzed functions and projects
 //! - [`generator`]: Generates test code from analyzed data
+//! - [`hooks`]: Post-generation hook commands
+//! - [`report`]: Per-function outcome of a generation run
+//! - [`runner`]: Runs generated test targets and collects per-test outcomes
 
 pub mod analyzer;
 pub mod generator;
+pub mod hooks;
 pub mod models;
+pub mod report;
+pub mod runner;
@@ -7,13 +7,23 @@
 //! collections of functions.
 //!
 //! The models are designed for memory efficiency and thread safety, featuring
-//! string interning to reduce memory duplication for common type names.
+//! string interning so that every occurrence of a type name shares one allocation.
 
+use crate::error::AutoTestError;
 use serde::{Serialize, Deserialize};
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// Version of the serialized [`ProjectInfo`] format.
+///
+/// It only changes when a release can no longer read older data or vice versa.
+/// Fields added in between have defaults and unknown fields are ignored, so
+/// analysis results cached by one release load in its neighbours.
+pub const SCHEMA_VERSION: u32 = 1;
 
 /// Represents a function parameter with its name and type information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParamInfo {
     /// The parameter name as defined in the function signature.
     pub name: String,
@@ -24,17 +34,32 @@ pub struct ParamInfo {
 /// An interned string type optimized for memory efficiency in large codebases.
 ///
 /// This struct uses `Arc<str>` to provide shared ownership of type strings
-/// with automatic interning of common Rust types. This significantly reduces
-/// memory usage when analyzing large projects with repetitive type patterns.
+/// with automatic interning. This significantly reduces memory usage when
+/// analyzing large projects with repetitive type patterns.
 ///
 /// # Memory Optimization
 ///
-/// Common types like `"String"`, `"&str"`, `"i32"`, etc., are pre-interned
-/// in a static pool, ensuring they share the same memory allocation across
-/// all instances. This can reduce memory usage by 30-50% in large projects.
+/// Every type string is interned in a process-wide pool, pre-populated with
+/// common types like `"String"`, `"&str"` and `"i32"`, so that all occurrences
+/// of a type, the project's own `MyConfig` included, share one allocation.
+/// The pool is guarded by a read-write lock: lookups of known types, by far the
+/// most frequent, proceed in parallel. Entries live as long as the process.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct TypeIntern(Arc<str>);
 
+/// Types interned before any is analyzed.
+const COMMON_TYPES: [&str; 18] = [
+    "String", "&str", "i32", "u32", "i64", "u64", "usize",
+    "bool", "()", "Vec<T>", "Option<T>", "Result<T, E>",
+    "PathBuf", "Uuid", "Url", "DateTime", "Config", "Args"
+];
+
+/// The interning pool shared by all [`TypeIntern`]s.
+fn intern_pool() -> &'static RwLock<HashSet<Arc<str>>> {
+    static INTERN_POOL: OnceLock<RwLock<HashSet<Arc<str>>>> = OnceLock::new();
+    INTERN_POOL.get_or_init(|| RwLock::new(COMMON_TYPES.iter().map(|&typ| Arc::from(typ)).collect()))
+}
+
 impl serde::Serialize for TypeIntern {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -57,31 +82,24 @@ impl<'de> serde::Deserialize<'de> for TypeIntern {
 impl TypeIntern {
     /// Create a new interned type string, reusing existing allocations where possible
     pub fn new(s: &str) -> Self {
-        // Use static interning table for common type patterns
-        use std::collections::HashMap;
-        use std::sync::OnceLock;
-
-        static INTERN_POOL: OnceLock<HashMap<&'static str, TypeIntern>> = OnceLock::new();
-        let pool = INTERN_POOL.get_or_init(|| {
-            let mut map = HashMap::new();
-            // Pre-populate common types
-            let common_types = [
-                "String", "&str", "i32", "u32", "i64", "u64", "usize",
-                "bool", "()", "Vec<T>", "Option<T>", "Result<T, E>",
-                "PathBuf", "Uuid", "Url", "DateTime", "Config", "Args"
-            ];
-            for &typ in &common_types {
-                map.insert(typ, TypeIntern(typ.into()));
-            }
-            map
-        });
+        let pool = intern_pool();
+        if let Some(interned) = pool.read().unwrap_or_else(PoisonError::into_inner).get(s) {
+            return TypeIntern(interned.clone());
+        }
 
-        // Check if type exists in pool (exact match for common types)
+        let mut pool = pool.write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have added it between the two locks
         if let Some(interned) = pool.get(s) {
-            interned.clone()
-        } else {
-            TypeIntern(Arc::from(s))
+            return TypeIntern(interned.clone());
         }
+        let interned: Arc<str> = Arc::from(s);
+        pool.insert(interned.clone());
+        TypeIntern(interned)
+    }
+
+    /// Number of distinct type strings interned so far in this process.
+    pub fn interned_count() -> usize {
+        intern_pool().read().unwrap_or_else(PoisonError::into_inner).len()
     }
 
     /// Get the underlying string reference
@@ -90,6 +108,13 @@ impl TypeIntern {
     }
 }
 
+impl Default for TypeIntern {
+    /// The unit type, matching a function without an explicit return type.
+    fn default() -> Self {
+        Self::new("()")
+    }
+}
+
 impl std::fmt::Display for TypeIntern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -109,7 +134,7 @@ impl From<String> for TypeIntern {
 }
 
 /// Comprehensive information about a single analyzed function.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FunctionInfo {
     /// The function name as defined in the source code.
     pub name: String,
@@ -121,9 +146,119 @@ pub struct FunctionInfo {
     pub file: String,
     /// Whether this function is declared as async.
     pub is_async: bool,
+    /// Whether this function is declared as `const fn`.
+    #[serde(default)]
+    pub is_const: bool,
+    /// Cargo features required by `#[cfg(feature = "...")]` on the function.
+    #[serde(default)]
+    pub cfg_features: Vec<String>,
+    /// 1-based line of the `fn` keyword in `file`.
+    #[serde(default)]
+    pub line: usize,
+    /// 1-based line of the closing brace of the function body.
+    #[serde(default)]
+    pub end_line: usize,
+    /// Cyclomatic complexity of the body (1 for straight-line code).
+    #[serde(default)]
+    pub complexity: u32,
+    /// No obvious side effects (`&mut` args, IO, statics); see `analyzer::is_pure`.
+    #[serde(default)]
+    pub is_pure: bool,
+    /// Mutants cargo-mutants reported as surviving in this function.
+    #[serde(default)]
+    pub surviving_mutants: Vec<String>,
+    /// The `///` doc comment, without comment markers.
+    #[serde(default)]
+    pub docs: Option<String>,
+    /// Attributes other than doc comments as written, e.g. `#[must_use]`,
+    /// `#[cfg(unix)]` or `#[tracing::instrument(skip(db))]`.
+    #[serde(default)]
+    pub attrs: Vec<String>,
+    /// Module path within the crate, e.g. `net::tcp`, resolved from the `mod`
+    /// declarations; `None` when the file isn't reachable from a crate root.
+    #[serde(default)]
+    pub module_path: Option<String>,
+    /// Shortest public path from the crate root, e.g. `foo` for a function
+    /// re-exported with `pub use internal::foo;`; `None` when no path is public.
+    #[serde(default)]
+    pub public_path: Option<String>,
+    /// Declared without `pub` (private or `pub(crate)`), so it's tested from
+    /// its own source file; see `generator::unit`.
+    #[serde(default)]
+    pub is_private: bool,
+    /// Paths of the functions called in the body as written, e.g. `make_config`
+    /// or `config::load`; see `analyzer::CallGraph`.
+    #[serde(default)]
+    pub calls: Vec<String>,
+    /// For a method of a trait impl, the implementing type as written; the
+    /// receiver is recorded as the first parameter, e.g. `self: &Store`.
+    #[serde(default)]
+    pub self_type: Option<String>,
+    /// For a method of a trait impl, the trait as written, e.g. `Repository`.
+    #[serde(default)]
+    pub trait_path: Option<String>,
+    /// Generic parameters of the signature in declaration order, with the
+    /// bounds of `where T: ...` predicates merged into their parameter's.
+    #[serde(default)]
+    pub generics: Vec<GenericParam>,
+    /// `where` predicates that don't bound a single type parameter of the
+    /// function, as written, e.g. `Vec < T > : Debug`.
+    #[serde(default)]
+    pub where_predicates: Vec<String>,
+}
+
+/// Kind of a generic parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericKind {
+    /// A lifetime such as `'a`.
+    Lifetime,
+    /// A type parameter such as `T`.
+    Type,
+    /// A const parameter such as `const N: usize`.
+    Const,
+}
+
+/// A generic parameter of a function signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenericParam {
+    /// The name as written, e.g. `'a`, `T` or `N`.
+    pub name: String,
+    /// Lifetime, type or const parameter.
+    pub kind: GenericKind,
+    /// Bounds as written, e.g. `FromStr` or `Into < String >` for a type
+    /// parameter and `'b` for a lifetime; the type of a const parameter.
+    #[serde(default)]
+    pub bounds: Vec<String>,
 }
 
 impl FunctionInfo {
+    /// Whether the function has an attribute with this path, e.g. `deprecated`
+    /// or `tracing::instrument`.
+    pub fn has_attr(&self, path: &str) -> bool {
+        self.attrs.iter().any(|attr| {
+            attr.trim_start_matches("#[")
+                .split(['(', '=', ']', ' '])
+                .next()
+                == Some(path)
+        })
+    }
+
+    /// Names of the generic type parameters, e.g. `T`.
+    pub fn type_params(&self) -> impl Iterator<Item = &str> {
+        self.generics
+            .iter()
+            .filter(|g| g.kind == GenericKind::Type)
+            .map(|g| g.name.as_str())
+    }
+
+    /// `#[cfg(...)]` predicates of the function, e.g. `unix` or `feature = "x"`.
+    pub fn cfg_predicates(&self) -> impl Iterator<Item = &str> {
+        self.attrs.iter().filter_map(|attr| {
+            attr.strip_prefix("#[cfg(")?.strip_suffix(")]")
+        })
+    }
+
     /// Calculate estimated memory impact for profiling and diagnostics.
     ///
     /// This provides an approximate memory footprint including all string data
@@ -137,8 +272,15 @@ impl FunctionInfo {
 }
 
 /// Project-wide collection of analyzed functions and metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Serializable with a stable, versioned format (see [`SCHEMA_VERSION`]) so it
+/// can be cached, or analyzed on one machine and generated from on another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectInfo {
+    /// Version of the format the data was written with. Data from before
+    /// versioning has none and reads as version 1.
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
     /// Programming language of the project ("rust" or "typescript").
     pub language: String,
     /// Root directory path of the project.
@@ -147,7 +289,83 @@ pub struct ProjectInfo {
     pub functions: Vec<FunctionInfo>,
 }
 
+fn first_schema_version() -> u32 {
+    1
+}
+
 impl ProjectInfo {
+    /// Serialize to JSON in the current [`SCHEMA_VERSION`].
+    pub fn to_json(&self) -> crate::error::Result<String> {
+        let mut data = self.clone();
+        data.schema_version = SCHEMA_VERSION;
+        serde_json::to_string_pretty(&data).map_err(|e| AutoTestError::AnalysisData {
+            message: e.to_string(),
+        })
+    }
+
+    /// Read JSON written by [`Self::to_json`] of this or an earlier release.
+    ///
+    /// # Returns
+    ///
+    /// The project, or an error if the JSON is malformed or written by a release
+    /// with a newer schema version
+    pub fn from_json(json: &str) -> crate::error::Result<Self> {
+        let invalid = |e: serde_json::Error| AutoTestError::AnalysisData {
+            message: e.to_string(),
+        };
+        let value: serde_json::Value = serde_json::from_str(json).map_err(invalid)?;
+        // Checked first: a newer format may not deserialize into this one
+        let version = value
+            .get("schema_version")
+            .map_or(Some(first_schema_version() as u64), serde_json::Value::as_u64);
+        let message = match version {
+            Some(version) if version <= SCHEMA_VERSION as u64 => None,
+            Some(version) => Some(format!(
+                "schema version {} is newer than the supported version {}; upgrade autotest",
+                version, SCHEMA_VERSION
+            )),
+            None => Some("schema_version must be a non-negative integer".to_string()),
+        };
+        if let Some(message) = message {
+            return Err(AutoTestError::AnalysisData { message });
+        }
+        serde_json::from_value(value).map_err(invalid)
+    }
+
+    /// Read an analysis saved with [`Self::save`].
+    pub fn load(path: &Path) -> crate::error::Result<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| AutoTestError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Write the analysis as JSON, creating parent directories.
+    pub fn save(&self, path: &Path) -> crate::error::Result<()> {
+        let write_error = |e| AutoTestError::FileWrite {
+            path: path.to_path_buf(),
+            source: e,
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        std::fs::write(path, self.to_json()? + "\n").map_err(write_error)
+    }
+
+    /// Move the project to `root`, e.g. when it was analyzed on another machine:
+    /// function files under the old root are rewritten to the same place under
+    /// the new one.
+    pub fn rebase(&mut self, root: &Path) {
+        let old_root = PathBuf::from(&self.root);
+        for func in &mut self.functions {
+            if let Ok(relative) = Path::new(&func.file).strip_prefix(&old_root) {
+                func.file = root.join(relative).to_string_lossy().to_string();
+            }
+        }
+        self.root = root.to_string_lossy().to_string();
+    }
+
     /// Generate memory usage statistics for the analyzed project.
     ///
     /// This aggregates memory usage across all functions and provides
@@ -156,11 +374,20 @@ impl ProjectInfo {
         let total_functions = self.functions.len();
         let total_params = self.functions.iter().map(|f| f.params.len()).sum::<usize>();
         let total_memory = self.functions.iter().map(|f| f.memory_estimate()).sum::<usize>();
+        let types: Vec<&str> = self
+            .functions
+            .iter()
+            .flat_map(|f| f.params.iter().map(|p| p.typ.as_str()).chain([f.returns.as_str()]))
+            .collect();
+        let distinct_types = types.iter().collect::<HashSet<_>>().len();
 
         MemoryStats {
             total_functions,
             total_params,
             estimated_memory_mb: total_memory / 1_000_000,
+            type_refs: types.len(),
+            distinct_types,
+            interned_types: TypeIntern::interned_count(),
         }
     }
 }
@@ -174,13 +401,98 @@ pub struct MemoryStats {
     pub total_params: usize,
     /// Estimated memory usage in megabytes.
     pub estimated_memory_mb: usize,
+    /// Parameter and return types across all functions.
+    pub type_refs: usize,
+    /// Distinct types among them; each is allocated once, however often it occurs.
+    pub distinct_types: usize,
+    /// Types held by the process-wide interning pool.
+    pub interned_types: usize,
 }
 
 /// Generated test file with path and content.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TestFile {
     /// The file system path where the test should be written.
     pub path: String,
     /// The complete test file content as Rust source code.
     pub content: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> ProjectInfo {
+        ProjectInfo {
+            schema_version: SCHEMA_VERSION,
+            language: "rust".to_string(),
+            root: "/work/app".to_string(),
+            functions: vec![FunctionInfo {
+                name: "add".to_string(),
+                params: vec![ParamInfo {
+                    name: "a".to_string(),
+                    typ: TypeIntern::new("i32"),
+                }],
+                returns: TypeIntern::new("i32"),
+                file: "src/lib.rs".to_string(),
+                line: 3,
+                end_line: 5,
+                complexity: 1,
+                is_pure: true,
+                ..FunctionInfo::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_project_info_round_trips() {
+        let project = project();
+        assert_eq!(ProjectInfo::from_json(&project.to_json().unwrap()).unwrap(), project);
+
+        let file = TestFile {
+            path: "tests/lib.rs".to_string(),
+            content: "#[test]\nfn t() {}\n".to_string(),
+        };
+        let json = serde_json::to_string(&file).unwrap();
+        assert_eq!(serde_json::from_str::<TestFile>(&json).unwrap(), file);
+    }
+
+    #[test]
+    fn test_project_info_reads_older_and_rejects_newer_data() {
+        // Written before versioning and before most FunctionInfo fields existed
+        let old = r#"{"language": "rust", "root": "/work/app", "functions": [
+            {"name": "add", "params": [{"name": "a", "typ": "i32"}], "returns": "i32",
+             "file": "src/lib.rs", "is_async": false, "added_later": true}
+        ]}"#;
+        let project = ProjectInfo::from_json(old).unwrap();
+        assert_eq!(project.schema_version, 1);
+        assert_eq!(project.functions[0].params[0].typ.as_str(), "i32");
+
+        let newer = format!(r#"{{"schema_version": {}, "functions": 7}}"#, SCHEMA_VERSION + 1);
+        let error = ProjectInfo::from_json(&newer).unwrap_err();
+        assert_eq!(error.code(), "AT0005");
+        assert!(error.to_string().contains("upgrade autotest"));
+    }
+
+    #[test]
+    fn test_project_types_share_one_allocation() {
+        let types: Vec<TypeIntern> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| TypeIntern::new("ProjectTypeUsedOnlyHere")))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(types.windows(2).all(|pair| Arc::ptr_eq(&pair[0].0, &pair[1].0)));
+        let deserialized: TypeIntern = serde_json::from_str("\"ProjectTypeUsedOnlyHere\"").unwrap();
+        assert!(Arc::ptr_eq(&deserialized.0, &types[0].0));
+
+        let mut project = project();
+        project.functions[0].params.push(ParamInfo {
+            name: "config".to_string(),
+            typ: TypeIntern::new("ProjectTypeUsedOnlyHere"),
+        });
+        let stats = project.memory_stats();
+        assert_eq!((stats.type_refs, stats.distinct_types), (3, 2));
+        assert!(stats.interned_types > COMMON_TYPES.len());
+    }
+}
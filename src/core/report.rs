@@ -0,0 +1,705 @@
+//! # Generation Report
+//!
+//! Per-function outcome of a generation run, written as JSON (by default to
+//! `target/autotest-report.json`) so CI can post-process failures and track
+//! them over time.
+
+use crate::core::hooks::HookRun;
+use crate::core::models::FunctionInfo;
+use crate::error::{AutoTestError, Result};
+use console::{style, Color};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+mod html;
+mod manifest;
+mod metrics;
+
+pub use manifest::{FileAction, ManifestEntry, RunManifest};
+pub use metrics::{millis, RunMetrics};
+
+/// What happened to a function during generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionStatus {
+    /// A test was generated.
+    Generated,
+    /// Filtered out on purpose (skip list, existing tests, coverage, budget).
+    Skipped,
+    /// Uses types that can't be synthesized and strict mode is on.
+    Unsupported,
+    /// Generation failed with an error.
+    Failed,
+}
+
+/// Outcome for a single function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionReport {
+    pub name: String,
+    /// Module path relative to the crate root (empty for the crate root).
+    pub module: String,
+    /// Source file, relative to the project root when possible.
+    pub file: String,
+    pub line: usize,
+    pub status: FunctionStatus,
+    /// Why the function was skipped, or the error message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Diagnostic code of the error (`AT0102`), see `autotest explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// Test file the function's test was written to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_file: Option<String>,
+    /// Non-fatal problems, e.g. placeholder values for unsupported types.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Types the generator couldn't synthesize (parameters) or assert on (return types).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unsupported_types: Vec<String>,
+}
+
+/// Prefix of the warning recorded for each type filled with a placeholder value.
+pub const PLACEHOLDER_WARNING: &str = "placeholder value for unsupported type";
+
+/// Prefix of the warning recorded for functions given an idempotence property
+/// from their signature alone.
+pub const IDEMPOTENCE_WARNING: &str = "idempotence assumed from the signature";
+
+impl FunctionReport {
+    /// Whether the function uses types that can't be synthesized, either
+    /// rejected by strict mode or filled with placeholder values.
+    pub fn uses_unsupported_types(&self) -> bool {
+        self.status == FunctionStatus::Unsupported
+            || self
+                .warnings
+                .iter()
+                .any(|w| w.starts_with(PLACEHOLDER_WARNING))
+    }
+}
+
+/// A problem that didn't stop the run: a function that got no test because its
+/// generation failed, or a note on a generated test, see
+/// [`GenerationReport::warnings`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationWarning {
+    /// The function as `module::name` (just the name at the crate root).
+    pub function: String,
+    /// Source file, relative to the project root when possible.
+    pub file: String,
+    pub line: usize,
+    /// Whether the function got no test at all, rather than a test to review.
+    pub failed: bool,
+    pub message: String,
+    /// Diagnostic code of the error (`AT0102`), see `autotest explain`.
+    pub error_code: Option<String>,
+}
+
+/// Counts of functions per status, and of test files left unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub generated: usize,
+    pub skipped: usize,
+    pub unsupported: usize,
+    pub failed: usize,
+    /// Functions with at least one warning.
+    pub warnings: usize,
+    /// Test files that already had the generated content and weren't rewritten.
+    #[serde(default)]
+    pub unchanged: usize,
+}
+
+/// A type blocking functions from getting complete tests, with the functions
+/// it blocks; a type mapping or custom assertion for it fixes all of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockingType {
+    pub type_name: String,
+    /// Blocked functions as `module::name` (just the name at the crate root).
+    pub functions: Vec<String>,
+}
+
+/// Function counts of one module, see [`GenerationReport::module_stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleStats {
+    /// Module path relative to the crate root, empty for the root itself.
+    pub module: String,
+    /// Analyzed functions, whatever happened to them.
+    pub functions: usize,
+    pub generated: usize,
+    /// Filtered out by the skip list, existing tests, coverage or the budget.
+    pub skipped: usize,
+    /// Functions using types that can't be synthesized, with or without strict mode.
+    pub unsupported_types: usize,
+    pub failed: usize,
+}
+
+/// Outcome of a generation run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationReport {
+    /// RFC 3339 timestamp of the run.
+    pub generated_at: String,
+    pub project: String,
+    pub summary: ReportSummary,
+    pub functions: Vec<FunctionReport>,
+    /// Unsupported types by the number of functions they block, most first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocking_types: Vec<BlockingType>,
+    /// Test files generated by the run, relative to the project root.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// The subset of `files` already up to date on disk, so not rewritten.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unchanged_files: Vec<String>,
+    /// Post-generation hook commands that ran, see `generation.post_hooks`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<HookRun>,
+    /// Size of the analysis and duration of each phase.
+    #[serde(default)]
+    pub metrics: RunMetrics,
+}
+
+impl GenerationReport {
+    /// Start an empty report for a project.
+    pub fn new(project_path: &Path) -> Self {
+        Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            project: project_path.to_string_lossy().to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Record the outcome for a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The analyzed function
+    /// * `module` - Its module path relative to the crate root
+    /// * `status` - What happened to it
+    /// * `reason` - Skip reason or error message
+    pub fn record(
+        &mut self,
+        func: &FunctionInfo,
+        module: &str,
+        status: FunctionStatus,
+        reason: Option<String>,
+    ) -> &mut FunctionReport {
+        let file = Path::new(&func.file)
+            .strip_prefix(&self.project)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| func.file.clone());
+
+        self.functions.push(FunctionReport {
+            name: func.name.clone(),
+            module: module.to_string(),
+            file,
+            line: func.line,
+            status,
+            reason,
+            error_code: None,
+            test_file: None,
+            warnings: Vec::new(),
+            unsupported_types: Vec::new(),
+        });
+        self.functions.last_mut().expect("just pushed")
+    }
+
+    /// Order the recorded functions by module, name, then location, so reports of
+    /// the same project compare equal whatever order functions were processed in.
+    pub fn sort_functions(&mut self) {
+        self.functions.sort_by(|a, b| {
+            (&a.module, &a.name, &a.file, a.line).cmp(&(&b.module, &b.name, &b.file, b.line))
+        });
+    }
+
+    /// Record a failed function together with the error's diagnostic code.
+    pub fn record_error(
+        &mut self,
+        func: &FunctionInfo,
+        module: &str,
+        status: FunctionStatus,
+        error: &AutoTestError,
+    ) -> &mut FunctionReport {
+        let entry = self.record(func, module, status, Some(error.to_string()));
+        entry.error_code = Some(error.code().to_string());
+        entry
+    }
+
+    /// Failed functions and the warnings on generated tests, in the order the
+    /// functions are listed.
+    pub fn warnings(&self) -> Vec<GenerationWarning> {
+        let mut warnings = Vec::new();
+        for func in &self.functions {
+            let warning = |failed: bool, message: &str| GenerationWarning {
+                function: if func.module.is_empty() {
+                    func.name.clone()
+                } else {
+                    format!("{}::{}", func.module, func.name)
+                },
+                file: func.file.clone(),
+                line: func.line,
+                failed,
+                message: message.to_string(),
+                error_code: func.error_code.clone().filter(|_| failed),
+            };
+            if matches!(
+                func.status,
+                FunctionStatus::Failed | FunctionStatus::Unsupported
+            ) {
+                warnings.push(warning(true, func.reason.as_deref().unwrap_or_default()));
+            }
+            warnings.extend(func.warnings.iter().map(|message| warning(false, message)));
+        }
+        warnings
+    }
+
+    /// Recompute [`ReportSummary`] and [`GenerationReport::blocking_types`] from
+    /// the recorded functions.
+    pub fn summarize(&mut self) -> &ReportSummary {
+        let mut blocked: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for func in &self.functions {
+            for type_name in &func.unsupported_types {
+                let name = if func.module.is_empty() {
+                    func.name.clone()
+                } else {
+                    format!("{}::{}", func.module, func.name)
+                };
+                blocked.entry(type_name).or_default().push(name);
+            }
+        }
+        self.blocking_types = blocked
+            .into_iter()
+            .map(|(type_name, functions)| BlockingType {
+                type_name: type_name.to_string(),
+                functions,
+            })
+            .collect();
+        // Stable, so types blocking as many functions stay sorted by name
+        self.blocking_types
+            .sort_by_key(|t| std::cmp::Reverse(t.functions.len()));
+
+        let mut summary = ReportSummary::default();
+        for func in &self.functions {
+            match func.status {
+                FunctionStatus::Generated => summary.generated += 1,
+                FunctionStatus::Skipped => summary.skipped += 1,
+                FunctionStatus::Unsupported => summary.unsupported += 1,
+                FunctionStatus::Failed => summary.failed += 1,
+            }
+            if !func.warnings.is_empty() {
+                summary.warnings += 1;
+            }
+        }
+        summary.unchanged = self.unchanged_files.len();
+        self.summary = summary;
+        &self.summary
+    }
+
+    /// Render the end-of-run summary table: functions per status, then test
+    /// files written and left unchanged. Counts are colored when stderr
+    /// supports it (not with `--no-color` or `NO_COLOR`); zeros are dimmed.
+    pub fn summary_table(&self) -> String {
+        let summary = &self.summary;
+        let written = self.files.len().saturating_sub(summary.unchanged);
+        let rows = [
+            ("Generated", summary.generated, "function(s)", Color::Green),
+            ("Skipped", summary.skipped, "function(s)", Color::Yellow),
+            (
+                "Unsupported",
+                summary.unsupported,
+                "function(s)",
+                Color::Magenta,
+            ),
+            ("Failed", summary.failed, "function(s)", Color::Red),
+            ("Written", written, "file(s)", Color::Cyan),
+            ("Unchanged", summary.unchanged, "file(s)", Color::Blue),
+        ];
+
+        let mut table = String::from("Summary\n");
+        for (label, count, unit, color) in rows {
+            // Padded before styling, so escape codes don't throw off the columns
+            let number = style(format!("{:>6}", count)).for_stderr();
+            let number = if count == 0 {
+                number.dim()
+            } else {
+                number.fg(color).bold()
+            };
+            table.push_str(&format!("  {:<12}{} {}\n", label, number, unit));
+        }
+        table
+    }
+
+    /// Function counts per module, sorted by module path.
+    pub fn module_stats(&self) -> Vec<ModuleStats> {
+        let mut modules: BTreeMap<&str, ModuleStats> = BTreeMap::new();
+        for func in &self.functions {
+            let stats = modules
+                .entry(func.module.as_str())
+                .or_insert_with(|| ModuleStats {
+                    module: func.module.clone(),
+                    ..ModuleStats::default()
+                });
+            stats.functions += 1;
+            match func.status {
+                FunctionStatus::Generated => stats.generated += 1,
+                FunctionStatus::Skipped => stats.skipped += 1,
+                FunctionStatus::Unsupported => {}
+                FunctionStatus::Failed => stats.failed += 1,
+            }
+            if func.uses_unsupported_types() {
+                stats.unsupported_types += 1;
+            }
+        }
+        modules.into_values().collect()
+    }
+
+    /// Write the report as pretty-printed JSON, creating parent directories.
+    pub fn write(&mut self, path: &Path) -> Result<()> {
+        self.summarize();
+        let json = serde_json::to_string_pretty(self).map_err(|e| AutoTestError::FileWrite {
+            path: path.to_path_buf(),
+            source: std::io::Error::other(e),
+        })?;
+        write_file(path, &(json + "\n"))
+    }
+
+    /// Read a report written by [`GenerationReport::write`].
+    pub fn load(path: &Path) -> Result<Self> {
+        let read_error = |e| AutoTestError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        };
+        let json = std::fs::read_to_string(path).map_err(read_error)?;
+        serde_json::from_str(&json).map_err(|e| read_error(std::io::Error::other(e)))
+    }
+
+    /// Write the report as a single HTML page, creating parent directories; see
+    /// [`GenerationReport::to_html`].
+    pub fn write_html(&mut self, path: &Path, source_url: Option<&str>) -> Result<()> {
+        self.summarize();
+        write_file(path, &self.to_html(source_url))
+    }
+
+    /// Write the report as JUnit XML, creating parent directories.
+    pub fn write_junit(&mut self, path: &Path) -> Result<()> {
+        self.summarize();
+        write_file(path, &self.to_junit_xml())
+    }
+
+    /// Render the report as JUnit XML: one `<testsuite>` per module and one
+    /// `<testcase>` per function, which passed (test generated), was skipped
+    /// (filtered out), or failed (unsupported types or an error).
+    pub fn to_junit_xml(&self) -> String {
+        let mut modules: BTreeMap<&str, Vec<&FunctionReport>> = BTreeMap::new();
+        for func in &self.functions {
+            modules.entry(func.module.as_str()).or_default().push(func);
+        }
+
+        let failures = self.summary.failed + self.summary.unsupported;
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"autotest\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" timestamp=\"{}\">\n",
+            self.functions.len(),
+            failures,
+            self.summary.skipped,
+            escape_xml(&self.generated_at)
+        ));
+
+        for (module, functions) in modules {
+            let suite = if module.is_empty() { "crate" } else { module };
+            let count = |status| functions.iter().filter(|f| f.status == status).count();
+            xml.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+                escape_xml(suite),
+                functions.len(),
+                count(FunctionStatus::Failed) + count(FunctionStatus::Unsupported),
+                count(FunctionStatus::Skipped)
+            ));
+
+            for func in functions {
+                xml.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{}\"",
+                    escape_xml(&func.name),
+                    escape_xml(suite),
+                    escape_xml(&func.file),
+                    func.line
+                ));
+                let reason = escape_xml(func.reason.as_deref().unwrap_or_default());
+                let body = match func.status {
+                    FunctionStatus::Generated => String::new(),
+                    FunctionStatus::Skipped => format!("      <skipped message=\"{}\"/>\n", reason),
+                    FunctionStatus::Unsupported | FunctionStatus::Failed => format!(
+                        "      <failure message=\"{}\" type=\"{}\"/>\n",
+                        reason,
+                        func.error_code.as_deref().unwrap_or("error")
+                    ),
+                };
+                let output = if func.warnings.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "      <system-out>{}</system-out>\n",
+                        escape_xml(&func.warnings.join("\n"))
+                    )
+                };
+
+                if body.is_empty() && output.is_empty() {
+                    xml.push_str("/>\n");
+                } else {
+                    xml.push_str(&format!(">\n{}{}    </testcase>\n", body, output));
+                }
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+/// Write a file, creating its parent directories.
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| AutoTestError::FileWrite {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+    std::fs::write(path, contents).map_err(|e| AutoTestError::FileWrite {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+/// Escape text for use in XML attributes and element content.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_records_statuses_and_writes_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut report = GenerationReport::new(dir.path());
+        let func = |name: &str| FunctionInfo {
+            name: name.to_string(),
+            file: dir.path().join("src/lib.rs").to_string_lossy().to_string(),
+            line: 3,
+            ..FunctionInfo::default()
+        };
+
+        report
+            .record(&func("ok"), "", FunctionStatus::Generated, None)
+            .test_file = Some("tests/integration_tests.rs".to_string());
+        report.record(
+            &func("helper"),
+            "",
+            FunctionStatus::Skipped,
+            Some("skip_functions".into()),
+        );
+        report.record_error(
+            &func("render"),
+            "",
+            FunctionStatus::Unsupported,
+            &AutoTestError::UnsupportedType {
+                type_name: "Html".into(),
+            },
+        );
+
+        let path = dir.path().join("target/autotest-report.json");
+        report.write(&path).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(json["summary"]["generated"], 1);
+        assert_eq!(json["summary"]["skipped"], 1);
+        assert_eq!(json["summary"]["unsupported"], 1);
+        assert_eq!(json["functions"][0]["file"], "src/lib.rs");
+        assert_eq!(
+            json["functions"][0]["test_file"],
+            "tests/integration_tests.rs"
+        );
+        assert_eq!(json["functions"][2]["status"], "unsupported");
+        assert_eq!(json["functions"][2]["error_code"], "AT0102");
+        assert_eq!(json["functions"][2]["line"], 3);
+        assert!(json["functions"][1].get("error_code").is_none());
+        assert!(json.get("blocking_types").is_none());
+    }
+
+    #[test]
+    fn test_warnings_list_failures_and_notes_on_generated_tests() {
+        let mut report = GenerationReport::new(Path::new("/p"));
+        let func = |name: &str| FunctionInfo {
+            name: name.to_string(),
+            file: "/p/src/db.rs".to_string(),
+            line: 7,
+            ..FunctionInfo::default()
+        };
+        report
+            .record(&func("query"), "db", FunctionStatus::Generated, None)
+            .warnings = vec![format!("{} Row", PLACEHOLDER_WARNING)];
+        report.record(&func("open"), "db", FunctionStatus::Generated, None);
+        report.record_error(
+            &func("close"),
+            "",
+            FunctionStatus::Failed,
+            &AutoTestError::VerificationFailed {
+                message: "boom".into(),
+            },
+        );
+
+        let warnings = report.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].function, "db::query");
+        assert_eq!(warnings[0].file, "src/db.rs");
+        assert!(!warnings[0].failed);
+        assert_eq!(warnings[0].error_code, None);
+        assert_eq!(warnings[1].function, "close");
+        assert!(warnings[1].failed);
+        assert_eq!(warnings[1].message, "Generated tests failed: boom");
+        assert_eq!(
+            warnings[1].error_code.as_deref(),
+            Some(
+                AutoTestError::VerificationFailed {
+                    message: String::new()
+                }
+                .code()
+            )
+        );
+    }
+
+    #[test]
+    fn test_blocking_types_are_ranked_by_functions_blocked() {
+        let mut report = GenerationReport::new(Path::new("/p"));
+        let func = |name: &str| FunctionInfo {
+            name: name.to_string(),
+            ..FunctionInfo::default()
+        };
+        for (name, module, types) in [
+            ("query", "db", vec!["DbConn", "Row"]),
+            ("insert", "db", vec!["DbConn"]),
+            ("render", "", vec!["Html"]),
+            ("migrate", "db", vec!["DbConn"]),
+            ("parse", "", vec![]),
+        ] {
+            report
+                .record(&func(name), module, FunctionStatus::Generated, None)
+                .unsupported_types = types.into_iter().map(String::from).collect();
+        }
+        report.summarize();
+
+        let ranked: Vec<(&str, usize)> = report
+            .blocking_types
+            .iter()
+            .map(|t| (t.type_name.as_str(), t.functions.len()))
+            .collect();
+        assert_eq!(ranked, [("DbConn", 3), ("Html", 1), ("Row", 1)]);
+        assert_eq!(
+            report.blocking_types[0].functions,
+            ["db::query", "db::insert", "db::migrate"]
+        );
+        assert_eq!(report.blocking_types[1].functions, ["render"]);
+    }
+
+    #[test]
+    fn test_module_stats_count_functions_per_module() {
+        let mut report = GenerationReport::new(Path::new("/p"));
+        let func = FunctionInfo::default();
+        report.record(&func, "db", FunctionStatus::Generated, None);
+        report
+            .record(&func, "db", FunctionStatus::Generated, None)
+            .warnings = vec![format!("{} DbConn", PLACEHOLDER_WARNING)];
+        report.record(&func, "db", FunctionStatus::Unsupported, None);
+        report.record(&func, "", FunctionStatus::Skipped, None);
+
+        let stats = report.module_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].module, "");
+        assert_eq!(stats[0].skipped, 1);
+        assert_eq!(
+            stats[1],
+            ModuleStats {
+                module: "db".to_string(),
+                functions: 3,
+                generated: 2,
+                skipped: 0,
+                unsupported_types: 2,
+                failed: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_summary_table_counts_functions_and_files() {
+        let mut report = GenerationReport::new(Path::new("/p"));
+        let func = FunctionInfo::default();
+        report.record(&func, "", FunctionStatus::Generated, None);
+        report.record(&func, "", FunctionStatus::Generated, None);
+        report.record(&func, "", FunctionStatus::Failed, Some("boom".into()));
+        report.files = vec!["tests/a_tests.rs".into(), "tests/b_tests.rs".into()];
+        report.unchanged_files = vec!["tests/b_tests.rs".into()];
+        report.summarize();
+        assert_eq!(report.summary.unchanged, 1);
+
+        let table = console::strip_ansi_codes(&report.summary_table()).to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Summary");
+        assert_eq!(lines[1], "  Generated        2 function(s)");
+        assert_eq!(lines[2], "  Skipped          0 function(s)");
+        assert_eq!(lines[4], "  Failed           1 function(s)");
+        assert_eq!(lines[5], "  Written          1 file(s)");
+        assert_eq!(lines[6], "  Unchanged        1 file(s)");
+    }
+
+    #[test]
+    fn test_junit_xml_maps_statuses_to_testcases() {
+        let mut report = GenerationReport::new(Path::new("/p"));
+        let func = |name: &str| FunctionInfo {
+            name: name.to_string(),
+            file: "/p/src/parser.rs".to_string(),
+            line: 7,
+            ..FunctionInfo::default()
+        };
+        report.record(&func("parse"), "parser", FunctionStatus::Generated, None);
+        report.record(
+            &func("helper"),
+            "parser",
+            FunctionStatus::Skipped,
+            Some("skip_functions".into()),
+        );
+        report.record_error(
+            &func("render"),
+            "parser",
+            FunctionStatus::Unsupported,
+            &AutoTestError::UnsupportedType {
+                type_name: "Html<'a>".into(),
+            },
+        );
+        report.summarize();
+
+        assert_eq!(
+            report.to_junit_xml(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="autotest" tests="3" failures="1" skipped="1" timestamp="TS">
+  <testsuite name="parser" tests="3" failures="1" skipped="1">
+    <testcase name="parse" classname="parser" file="src/parser.rs" line="7"/>
+    <testcase name="helper" classname="parser" file="src/parser.rs" line="7">
+      <skipped message="skip_functions"/>
+    </testcase>
+    <testcase name="render" classname="parser" file="src/parser.rs" line="7">
+      <failure message="Unsupported type &apos;Html&lt;&apos;a&gt;&apos; - consider adding custom mapping" type="AT0102"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+            .replace("TS", &report.generated_at)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Single-file HTML rendering of a [`GenerationReport`], for CI artifacts:
+//! the summary, the unsupported types ranked by the functions they block, and
+//! the module tree with every function's outcome linked to its source line.
+
+use super::{escape_xml, FunctionReport, FunctionStatus, GenerationReport};
+use std::collections::BTreeMap;
+
+const STYLE: &str = "body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0; }
+.meta { color: #666; }
+.summary { display: flex; gap: 1em; list-style: none; padding: 0; }
+.summary li { padding: 0.5em 1em; border-radius: 4px; background: #f2f2f2; }
+table { border-collapse: collapse; margin: 0.5em 0 1em; }
+th, td { text-align: left; padding: 0.25em 0.75em; border-bottom: 1px solid #ddd; vertical-align: top; }
+details { margin-left: 1.25em; }
+summary { cursor: pointer; font-weight: 600; }
+.counts { font-weight: normal; color: #666; }
+.status { padding: 0.1em 0.5em; border-radius: 3px; color: #fff; font-size: 0.9em; }
+.generated { background: #2e7d32; }
+.skipped { background: #9e9e9e; }
+.unsupported { background: #8e24aa; }
+.failed { background: #c62828; }
+.summary .status { background: none; color: inherit; }
+.notes { color: #555; font-size: 0.9em; }";
+
+/// Functions of a module and its submodules, keyed by path segment.
+#[derive(Default)]
+struct ModuleNode<'a> {
+    functions: Vec<&'a FunctionReport>,
+    children: BTreeMap<&'a str, ModuleNode<'a>>,
+}
+
+impl ModuleNode<'_> {
+    /// Functions with generated tests and all functions, submodules included.
+    fn counts(&self) -> (usize, usize) {
+        let generated = self
+            .functions
+            .iter()
+            .filter(|f| f.status == FunctionStatus::Generated)
+            .count();
+        self.children
+            .values()
+            .map(ModuleNode::counts)
+            .fold((generated, self.functions.len()), |(g, t), (cg, ct)| {
+                (g + cg, t + ct)
+            })
+    }
+}
+
+impl GenerationReport {
+    /// Render the report as a self-contained HTML page.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_url` - Base URL of the project's sources, e.g.
+    ///   `https://github.com/org/repo/blob/main/`; function locations link to
+    ///   `<source_url><file>#L<line>`. Locations are plain text without it.
+    pub fn to_html(&self, source_url: Option<&str>) -> String {
+        let mut root = ModuleNode::default();
+        for func in &self.functions {
+            let mut node = &mut root;
+            for segment in func.module.split("::").filter(|s| !s.is_empty()) {
+                node = node.children.entry(segment).or_default();
+            }
+            node.functions.push(func);
+        }
+
+        let summary = &self.summary;
+        let mut html = format!(
+            "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>autotest report: {project}</title>
+<style>
+{style}
+</style>
+</head>
+<body>
+<h1>autotest report</h1>
+<p class=\"meta\">{project} &middot; {generated_at}</p>
+<ul class=\"summary\">
+<li class=\"status generated\">{generated} generated</li>
+<li>{skipped} skipped</li>
+<li>{unsupported} unsupported</li>
+<li>{failed} failed</li>
+<li>{warnings} with warnings</li>
+</ul>
+",
+            project = escape_xml(&self.project),
+            style = STYLE,
+            generated_at = escape_xml(&self.generated_at),
+            generated = summary.generated,
+            skipped = summary.skipped,
+            unsupported = summary.unsupported,
+            failed = summary.failed,
+            warnings = summary.warnings,
+        );
+
+        if !self.blocking_types.is_empty() {
+            html.push_str(
+                "<h2>Unsupported types</h2>
+<table>
+<tr><th>Type</th><th>Functions blocked</th><th>Blocked functions</th></tr>
+",
+            );
+            for blocking in &self.blocking_types {
+                html.push_str(&format!(
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                    escape_xml(&blocking.type_name),
+                    blocking.functions.len(),
+                    escape_xml(&blocking.functions.join(", "))
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Modules</h2>\n");
+        render_module(&mut html, "crate", &root, source_url);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// A module as a `<details>` element holding its functions and submodules.
+fn render_module(html: &mut String, name: &str, node: &ModuleNode, source_url: Option<&str>) {
+    let (generated, total) = node.counts();
+    html.push_str(&format!(
+        "<details open>\n<summary><code>{}</code> <span class=\"counts\">{}/{} generated</span></summary>\n",
+        escape_xml(name),
+        generated,
+        total
+    ));
+
+    if !node.functions.is_empty() {
+        html.push_str(
+            "<table>\n<tr><th>Function</th><th>Status</th><th>Location</th><th>Test file</th><th>Notes</th></tr>\n",
+        );
+        for func in &node.functions {
+            let location = format!("{}:{}", func.file, func.line);
+            let location = match source_url {
+                Some(base) => format!(
+                    "<a href=\"{}{}#L{}\">{}</a>",
+                    escape_xml(base),
+                    escape_xml(&func.file),
+                    func.line,
+                    escape_xml(&location)
+                ),
+                None => escape_xml(&location),
+            };
+            let notes: Vec<String> = func
+                .reason
+                .iter()
+                .chain(&func.warnings)
+                .map(|note| escape_xml(note))
+                .collect();
+            let status = status_name(func.status);
+            html.push_str(&format!(
+                "<tr><td><code>{}</code></td><td><span class=\"status {}\">{}</span></td><td>{}</td><td>{}</td><td class=\"notes\">{}</td></tr>\n",
+                escape_xml(&func.name),
+                status,
+                status,
+                location,
+                escape_xml(func.test_file.as_deref().unwrap_or_default()),
+                notes.join("<br>")
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    for (child, node) in &node.children {
+        render_module(html, child, node, source_url);
+    }
+    html.push_str("</details>\n");
+}
+
+/// The status as written in the JSON report.
+fn status_name(status: FunctionStatus) -> &'static str {
+    match status {
+        FunctionStatus::Generated => "generated",
+        FunctionStatus::Skipped => "skipped",
+        FunctionStatus::Unsupported => "unsupported",
+        FunctionStatus::Failed => "failed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::models::FunctionInfo;
+    use std::path::Path;
+
+    #[test]
+    fn test_html_report_nests_modules_and_links_sources() {
+        let mut report = GenerationReport::new(Path::new("/p"));
+        let func = |name: &str| FunctionInfo {
+            name: name.to_string(),
+            file: "/p/src/storage/db.rs".to_string(),
+            line: 12,
+            ..FunctionInfo::default()
+        };
+        report.record(
+            &func("open"),
+            "storage::db",
+            FunctionStatus::Generated,
+            None,
+        );
+        report
+            .record(
+                &func("query"),
+                "storage::db",
+                FunctionStatus::Generated,
+                None,
+            )
+            .unsupported_types = vec!["Row<'a>".to_string()];
+        report.record(
+            &func("helper"),
+            "",
+            FunctionStatus::Skipped,
+            Some("skip_functions".into()),
+        );
+        report.summarize();
+
+        let html = report.to_html(Some("https://example.com/blob/main/"));
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html
+            .contains("<summary><code>crate</code> <span class=\"counts\">2/3 generated</span>"));
+        assert!(html
+            .contains("<summary><code>storage</code> <span class=\"counts\">2/2 generated</span>"));
+        assert!(html.contains("<summary><code>db</code>"));
+        assert!(html.contains(
+            "<a href=\"https://example.com/blob/main/src/storage/db.rs#L12\">src/storage/db.rs:12</a>"
+        ));
+        assert!(html.contains("<tr><td><code>Row&lt;&apos;a&gt;</code></td><td>1</td><td>storage::db::query</td></tr>"));
+        assert!(html.contains("<td class=\"notes\">skip_functions</td>"));
+
+        assert!(report
+            .to_html(None)
+            .contains("<td>src/storage/db.rs:12</td>"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Machine-readable list of the files a run wrote, for build systems (Bazel or
+//! Buck wrappers, Nix) that declare a step's outputs and cache on their contents.
+
+use super::write_file;
+use crate::error::{AutoTestError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// What a run did with one of its test files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    /// The file didn't exist and was created.
+    Written,
+    /// The file existed with other contents and was rewritten.
+    Updated,
+    /// The file was already up to date, so it was left untouched.
+    Skipped,
+}
+
+/// A test file of the run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the project root, with `/` separators on every platform.
+    pub path: String,
+    pub action: FileAction,
+    /// Lowercase hex SHA-256 of the file's contents after the run.
+    pub sha256: String,
+    /// Size of the file's contents in bytes.
+    pub bytes: usize,
+}
+
+impl ManifestEntry {
+    /// Entry for a file with these contents.
+    pub fn new(path: impl Into<String>, action: FileAction, contents: &[u8]) -> Self {
+        Self {
+            path: path.into(),
+            action,
+            sha256: format!("{:x}", Sha256::digest(contents)),
+            bytes: contents.len(),
+        }
+    }
+}
+
+/// Every test file of a run, see `generate --emit-manifest`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// RFC 3339 timestamp of the run.
+    pub generated_at: String,
+    pub project: String,
+    /// The files in the order they were written, i.e. sorted by path.
+    pub files: Vec<ManifestEntry>,
+}
+
+impl RunManifest {
+    /// Write the manifest as pretty-printed JSON, creating parent directories.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| AutoTestError::FileWrite {
+            path: path.to_path_buf(),
+            source: std::io::Error::other(e),
+        })?;
+        write_file(path, &(json + "\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_carry_the_checksum_of_their_contents() {
+        let entry = ManifestEntry::new("tests/lib_tests.rs", FileAction::Written, b"abc");
+        assert_eq!(
+            entry.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(entry.bytes, 3);
+
+        let manifest = RunManifest {
+            generated_at: "2026-01-01T00:00:00+00:00".to_string(),
+            project: "/p".to_string(),
+            files: vec![entry],
+        };
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["files"][0]["action"], "written");
+        assert_eq!(json["files"][0]["path"], "tests/lib_tests.rs");
+    }
+}
@@ -0,0 +1,208 @@
+//! Size and timing of a run, kept in the report and optionally written in the
+//! Prometheus text format for node_exporter's textfile collector, so CI can
+//! track how analysis and generation times grow with the project.
+
+use super::{write_file, GenerationReport};
+use crate::core::models::MemoryStats;
+use crate::error::{AutoTestError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Analysis footprint and phase durations of a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunMetrics {
+    /// Functions analyzed, before any filtering.
+    pub functions: usize,
+    /// Parameters of those functions.
+    pub params: usize,
+    pub estimated_memory_mb: usize,
+    /// Parameter and return types of those functions.
+    pub type_refs: usize,
+    pub distinct_types: usize,
+    /// Types held by the interning pool at the end of analysis.
+    pub interned_types: usize,
+    /// Reading and parsing the sources, or loading a saved analysis.
+    pub analysis_ms: u64,
+    /// Generating the tests, after analysis.
+    pub generation_ms: u64,
+    /// Writing the test files.
+    pub write_ms: u64,
+    /// The whole run up to writing the reports, hooks included.
+    pub total_ms: u64,
+}
+
+impl RunMetrics {
+    /// Add an analyzed crate (a workspace member, or the whole project) and the
+    /// time its analysis took.
+    pub fn add_analysis(&mut self, stats: &MemoryStats, elapsed: Duration) {
+        self.functions += stats.total_functions;
+        self.params += stats.total_params;
+        self.estimated_memory_mb += stats.estimated_memory_mb;
+        self.type_refs += stats.type_refs;
+        self.distinct_types += stats.distinct_types;
+        self.interned_types = stats.interned_types;
+        self.analysis_ms += millis(elapsed);
+    }
+}
+
+/// A duration in whole milliseconds.
+pub fn millis(elapsed: Duration) -> u64 {
+    u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+}
+
+impl GenerationReport {
+    /// Render the metrics and the summary counts in the Prometheus text format,
+    /// one gauge per value, labelled with the project.
+    pub fn to_prometheus(&self) -> String {
+        let metrics = &self.metrics;
+        let summary = &self.summary;
+        let seconds = |ms: u64| format!("{:.3}", ms as f64 / 1000.0);
+        let gauges = [
+            (
+                "functions",
+                "Functions analyzed",
+                metrics.functions.to_string(),
+            ),
+            (
+                "params",
+                "Parameters of the analyzed functions",
+                metrics.params.to_string(),
+            ),
+            (
+                "estimated_memory_megabytes",
+                "Estimated memory of the analysis",
+                metrics.estimated_memory_mb.to_string(),
+            ),
+            (
+                "type_refs",
+                "Parameter and return types of the analyzed functions",
+                metrics.type_refs.to_string(),
+            ),
+            (
+                "distinct_types",
+                "Distinct types of the analyzed functions",
+                metrics.distinct_types.to_string(),
+            ),
+            (
+                "interned_types",
+                "Types held by the interning pool",
+                metrics.interned_types.to_string(),
+            ),
+            (
+                "generated_functions",
+                "Functions that got a test",
+                summary.generated.to_string(),
+            ),
+            (
+                "skipped_functions",
+                "Functions filtered out",
+                summary.skipped.to_string(),
+            ),
+            (
+                "unsupported_functions",
+                "Functions using types that can't be synthesized",
+                summary.unsupported.to_string(),
+            ),
+            (
+                "failed_functions",
+                "Functions whose generation failed",
+                summary.failed.to_string(),
+            ),
+            (
+                "files",
+                "Test files generated",
+                self.files.len().to_string(),
+            ),
+            (
+                "analysis_duration_seconds",
+                "Time spent analyzing the sources",
+                seconds(metrics.analysis_ms),
+            ),
+            (
+                "generation_duration_seconds",
+                "Time spent generating tests",
+                seconds(metrics.generation_ms),
+            ),
+            (
+                "write_duration_seconds",
+                "Time spent writing test files",
+                seconds(metrics.write_ms),
+            ),
+            (
+                "run_duration_seconds",
+                "Duration of the whole run",
+                seconds(metrics.total_ms),
+            ),
+        ];
+
+        let project = self
+            .project
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        let mut text = String::new();
+        for (name, help, value) in gauges {
+            text.push_str(&format!(
+                "# HELP autotest_{name} {help}.\n# TYPE autotest_{name} gauge\nautotest_{name}{{project=\"{project}\"}} {value}\n"
+            ));
+        }
+        text
+    }
+
+    /// Write [`GenerationReport::to_prometheus`] to `path`, creating parent
+    /// directories. The file is replaced in one rename, so a collector never
+    /// reads it half-written.
+    pub fn write_prometheus(&mut self, path: &Path) -> Result<()> {
+        self.summarize();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".tmp");
+        let partial = Path::new(&partial);
+        write_file(partial, &self.to_prometheus())?;
+        std::fs::rename(partial, path).map_err(|e| AutoTestError::FileWrite {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_textfile_has_a_gauge_per_metric() {
+        let mut report = GenerationReport::new(Path::new("/work/\"app\""));
+        report.metrics.add_analysis(
+            &MemoryStats {
+                total_functions: 12,
+                total_params: 20,
+                estimated_memory_mb: 0,
+                type_refs: 32,
+                distinct_types: 9,
+                interned_types: 40,
+            },
+            Duration::from_millis(1500),
+        );
+        report.metrics.total_ms = 2250;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics/autotest.prom");
+        report.write_prometheus(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with(
+            "# HELP autotest_functions Functions analyzed.\n# TYPE autotest_functions gauge\nautotest_functions{project=\"/work/\\\"app\\\"\"} 12\n"
+        ));
+        assert!(text
+            .contains("autotest_analysis_duration_seconds{project=\"/work/\\\"app\\\"\"} 1.500\n"));
+        assert!(
+            text.contains("autotest_run_duration_seconds{project=\"/work/\\\"app\\\"\"} 2.250\n")
+        );
+        assert_eq!(text.matches("# TYPE").count(), 15);
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+    }
+}
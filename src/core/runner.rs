@@ -0,0 +1,219 @@
+//! # Test Runner
+//!
+//! Runs generated integration test targets with `cargo test` (or
+//! `cargo nextest run` when it is installed) and collects the outcome of every
+//! test, so a generation run can be smoke-tested in one command.
+
+use crate::error::{AutoTestError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Outcome of a single test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// One test reported by the test harness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestOutcome {
+    /// Test target (file stem under `tests/`) the test belongs to.
+    pub target: String,
+    /// Test function name.
+    pub name: String,
+    pub status: TestStatus,
+}
+
+/// An integration test target of a crate, e.g. `tests/integration_tests.rs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestTarget {
+    /// Directory containing the crate's `Cargo.toml`.
+    pub crate_dir: PathBuf,
+    /// Target name, passed as `--test <name>`.
+    pub name: String,
+}
+
+/// Result of running one test target.
+#[derive(Debug, Clone)]
+pub struct TargetRun {
+    pub target: TestTarget,
+    pub outcomes: Vec<TestOutcome>,
+    /// Whether the harness exited successfully.
+    pub success: bool,
+    /// Combined harness output, shown when the target fails to build.
+    pub output: String,
+}
+
+impl TargetRun {
+    /// Whether the target failed before any test ran, e.g. a compile error.
+    pub fn build_failed(&self) -> bool {
+        !self.success && self.outcomes.is_empty()
+    }
+}
+
+impl TestTarget {
+    /// The test target a generated file compiles to: a `.rs` file directly in
+    /// the `tests/` directory of a crate. Modules like `tests/common/mod.rs`
+    /// are not targets.
+    pub fn for_file(path: &Path) -> Option<Self> {
+        if path.extension()? != "rs" {
+            return None;
+        }
+        let tests_dir = path.parent()?;
+        if tests_dir.file_name()? != "tests" {
+            return None;
+        }
+        let crate_dir = tests_dir.parent()?;
+        if !crate_dir.join("Cargo.toml").is_file() {
+            return None;
+        }
+        Some(Self {
+            crate_dir: crate_dir.to_path_buf(),
+            name: path.file_stem()?.to_string_lossy().to_string(),
+        })
+    }
+}
+
+/// Whether `cargo nextest` is installed.
+pub fn nextest_available() -> bool {
+    Command::new("cargo")
+        .args(["nextest", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Build and run one test target.
+///
+/// # Arguments
+///
+/// * `target` - The test target to run
+/// * `nextest` - Use `cargo nextest run` instead of `cargo test`
+/// * `target_dir` - Cargo target directory, e.g. to share build artifacts
+///
+/// # Returns
+///
+/// The tests that ran with their outcome, or an error if cargo can't be started
+pub fn run_target(
+    target: &TestTarget,
+    nextest: bool,
+    target_dir: Option<&Path>,
+) -> Result<TargetRun> {
+    let mut command = Command::new("cargo");
+    if nextest {
+        command.args(["nextest", "run", "--no-fail-fast", "--test", &target.name]);
+    } else {
+        command.args(["test", "--no-fail-fast", "--test", &target.name]);
+    }
+    command.current_dir(&target.crate_dir);
+    if let Some(dir) = target_dir {
+        command.env("CARGO_TARGET_DIR", dir);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| AutoTestError::Io { source: e })?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let outcomes = if nextest {
+        parse_nextest(&target.name, &format!("{}\n{}", stdout, stderr))
+    } else {
+        parse_libtest(&target.name, &stdout)
+    };
+
+    Ok(TargetRun {
+        target: target.clone(),
+        outcomes,
+        success: output.status.success(),
+        output: format!("{}{}", stdout, stderr),
+    })
+}
+
+/// Parse `test name ... ok` lines of the libtest harness.
+fn parse_libtest(target: &str, output: &str) -> Vec<TestOutcome> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, result) = line.strip_prefix("test ")?.split_once(" ... ")?;
+            let status = match result.split([',', ' ']).next()? {
+                "ok" => TestStatus::Passed,
+                "FAILED" => TestStatus::Failed,
+                "ignored" => TestStatus::Ignored,
+                _ => return None,
+            };
+            Some(TestOutcome {
+                target: target.to_string(),
+                name: name.to_string(),
+                status,
+            })
+        })
+        .collect()
+}
+
+/// Parse `PASS [   0.004s] crate::target test_name` lines of nextest; failures
+/// are repeated in the final summary and only counted once.
+fn parse_nextest(target: &str, output: &str) -> Vec<TestOutcome> {
+    let mut seen = std::collections::HashSet::new();
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let status = match line.split_whitespace().next()? {
+                "PASS" => TestStatus::Passed,
+                "FAIL" | "TIMEOUT" => TestStatus::Failed,
+                "SKIP" => TestStatus::Ignored,
+                _ => return None,
+            };
+            let name = line.rsplit(' ').next()?;
+            if !seen.insert(name.to_string()) {
+                return None;
+            }
+            Some(TestOutcome {
+                target: target.to_string(),
+                name: name.to_string(),
+                status,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_libtest_and_nextest_output() {
+        let libtest = "\nrunning 3 tests\ntest test_add ... ok\ntest test_send ... FAILED\ntest test_net ... ignored, network\n\ntest result: FAILED. 1 passed; 1 failed; 1 ignored\n";
+        let outcomes = parse_libtest("integration_tests", libtest);
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|o| (o.name.as_str(), o.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("test_add", TestStatus::Passed),
+                ("test_send", TestStatus::Failed),
+                ("test_net", TestStatus::Ignored),
+            ]
+        );
+
+        let nextest = "    Starting 2 tests across 1 binary\n        PASS [   0.004s] rp::integration_tests test_add\n        FAIL [   0.010s] rp::integration_tests test_send\n------------\n        FAIL [   0.010s] rp::integration_tests test_send\n     Summary [   0.011s] 2 tests run: 1 passed, 1 failed, 0 skipped\n";
+        let outcomes = parse_nextest("integration_tests", nextest);
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[1].name, "test_send");
+        assert_eq!(outcomes[1].status, TestStatus::Failed);
+    }
+
+    #[test]
+    fn test_target_for_generated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+        let target = TestTarget::for_file(&dir.path().join("tests/integration_tests.rs")).unwrap();
+        assert_eq!(target.name, "integration_tests");
+        assert_eq!(target.crate_dir, dir.path());
+        assert!(TestTarget::for_file(&dir.path().join("tests/common/mod.rs")).is_none());
+        assert!(TestTarget::for_file(&dir.path().join("tests/report.json")).is_none());
+    }
+}
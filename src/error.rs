@@ -37,12 +37,40 @@ pub enum AutoTestError {
     #[error("Unsupported type '{type_name}' - consider adding custom mapping")]
     UnsupportedType { type_name: String },
 
+    #[error("Strict mode: {count} function(s) use types that can't be synthesized: {types}")]
+    StrictUnsupported { count: usize, types: String },
+
+    #[error("{count} function(s) {problem} (--fail-on {policy})")]
+    FailOn {
+        count: usize,
+        problem: String,
+        policy: String,
+    },
+
+    #[error("{count} public function(s) in staged files have no tests; tests were written to {files}, review and stage them")]
+    MissingTests { count: usize, files: String },
+
+    #[error("Plugin '{path}': {message}")]
+    Plugin { path: PathBuf, message: String },
+
+    #[error("{count} post-generation hook(s) failed: {commands}")]
+    HookFailed { count: usize, commands: String },
+
+    #[error("Generated tests failed: {message}")]
+    VerificationFailed { message: String },
+
     #[error("Configuration file error: {source}")]
     Config {
         #[from]
         source: config::ConfigError,
     },
 
+    #[error("Failed to write {count} file(s): {failures}")]
+    FilesNotWritten { count: usize, failures: String },
+
+    #[error("Another instance of autotest is running in this project (lock held on '{lock}'); wait for it to finish, or pass --no-lock")]
+    AlreadyRunning { lock: PathBuf },
+
     #[error("I/O operation failed: {source}")]
     Io {
         #[from]
@@ -58,6 +86,21 @@ pub enum AutoTestError {
     #[error("Timeout exceeded: operation took too long")]
     Timeout,
 
+    #[error("Failed to parse Cargo manifest '{path}': {message}")]
+    Manifest { path: PathBuf, message: String },
+
+    #[error("Failed to read coverage report '{path}': {message}")]
+    Coverage { path: PathBuf, message: String },
+
+    #[error("Failed to read cargo-mutants results '{path}': {message}")]
+    Mutants { path: PathBuf, message: String },
+
+    #[error("Invalid analysis data: {message}")]
+    AnalysisData { message: String },
+
+    #[error("Macro expansion failed: {message}")]
+    ExpandFailed { message: String },
+
     #[error("Project root not found: {path}")]
     ProjectRootNotFound { path: PathBuf },
 
@@ -65,5 +108,372 @@ pub enum AutoTestError {
     InvalidConfig { message: String },
 }
 
+impl AutoTestError {
+    /// Stable diagnostic code for this error, e.g. `AT0102`.
+    ///
+    /// Codes are grouped by area: `AT00xx` source analysis, `AT01xx` generation,
+    /// `AT02xx` configuration, `AT03xx` filesystem, `AT04xx` external reports and
+    /// `AT05xx` verification of generated tests.
+    /// Run `autotest explain <code>` for extended guidance.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ParseFailed { .. } => "AT0001",
+            Self::SyntaxError { .. } => "AT0002",
+            Self::Manifest { .. } => "AT0003",
+            Self::ProjectRootNotFound { .. } => "AT0004",
+            Self::AnalysisData { .. } => "AT0005",
+            Self::ExpandFailed { .. } => "AT0006",
+            Self::UnsupportedType { .. } => "AT0102",
+            Self::StrictUnsupported { .. } => "AT0103",
+            Self::Timeout => "AT0104",
+            Self::FailOn { .. } => "AT0105",
+            Self::MissingTests { .. } => "AT0106",
+            Self::Plugin { .. } => "AT0107",
+            Self::HookFailed { .. } => "AT0108",
+            Self::InvalidConfig { .. } => "AT0201",
+            Self::Config { .. } => "AT0202",
+            Self::Yaml { .. } => "AT0203",
+            Self::FileRead { .. } => "AT0301",
+            Self::FileWrite { .. } => "AT0302",
+            Self::Io { .. } => "AT0303",
+            Self::AlreadyRunning { .. } => "AT0304",
+            Self::FilesNotWritten { .. } => "AT0305",
+            Self::Coverage { .. } => "AT0401",
+            Self::Mutants { .. } => "AT0402",
+            Self::VerificationFailed { .. } => "AT0501",
+        }
+    }
+
+    /// Whether the error comes from the configuration or command-line input
+    /// rather than from generation itself (exit status 2 instead of 1).
+    pub fn is_config_error(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidConfig { .. }
+                | Self::Config { .. }
+                | Self::Yaml { .. }
+                | Self::ProjectRootNotFound { .. }
+                | Self::Coverage { .. }
+                | Self::Mutants { .. }
+        )
+    }
+}
+
+/// Diagnostic codes with a one-line title and the extended explanation shown
+/// by `autotest explain`.
+pub const ERROR_CODES: &[(&str, &str, &str)] = &[
+    (
+        "AT0001",
+        "Rust source failed to parse",
+        "A source file could not be parsed by `syn`, so none of its functions were analyzed.
+
+Check that the file compiles with `cargo check`. Files using syntax newer than
+the bundled `syn` understands, or macro-heavy files that are not valid Rust on
+their own, can be excluded with `filesystem.skip_patterns`.",
+    ),
+    (
+        "AT0002",
+        "Syntax error in a source file",
+        "A source file contains a syntax error at the reported line.
+
+Fix the error (`cargo check` shows the same location) or exclude the file with
+`filesystem.skip_patterns`.",
+    ),
+    (
+        "AT0003",
+        "Cargo.toml could not be read",
+        "The crate's `Cargo.toml` is not valid TOML or has an unexpected shape.
+
+Manifest data is used for the crate name, features, workspace members and
+proc-macro/wasm detection. Run `cargo metadata` to see cargo's own diagnosis.",
+    ),
+    (
+        "AT0004",
+        "Project root not found",
+        "No `Cargo.toml`, `package.json` or `src/` directory was found in the given path
+or any of its parents.
+
+Pass the crate directory explicitly: `autotest generate path/to/crate`.",
+    ),
+    (
+        "AT0005",
+        "Analysis data could not be read",
+        "Serialized analysis results (`ProjectInfo` as JSON) are malformed, or were
+written by a newer release with a higher `schema_version` than this one
+supports.
+
+Upgrade autotest to the release that wrote the data, or analyze the project
+again with this one.",
+    ),
+    (
+        "AT0006",
+        "Macro expansion failed",
+        "`--expand` could not expand the crate: `cargo expand` is not installed and
+`cargo rustc -Zunpretty=expanded` needs a nightly toolchain, or the crate failed
+to compile.
+
+Install cargo-expand (`cargo install cargo-expand`) or a nightly toolchain
+(`rustup toolchain install nightly`), and check that `cargo check` passes.
+Without `--expand`, functions generated by macros are not analyzed.",
+    ),
+    (
+        "AT0102",
+        "Type can't be synthesized",
+        "A parameter or return type has no built-in value or assertion, so the test would
+fall back to `Default::default()` or a TODO comment.
+
+Add a value to `[types.mappings]` (parameters) or an assertion to
+`[generation.custom_assertions]` (return types). Generation prints a
+ready-to-paste snippet using constructors found in your `impl` blocks:
+
+    [types.mappings]
+    \"Client\" = 'Client::new(\"test\", 0)'
+
+Keys without generics match every instantiation and also apply through `&T`,
+`Option<T>`, `Vec<T>` and `Box<T>`.",
+    ),
+    (
+        "AT0103",
+        "Strict mode found unsupported types",
+        "`generation.strict = true` refuses to write tests containing `Default` guesses or
+TODO assertions, and at least one function uses such a type (see AT0102 for
+each one).
+
+Add the suggested mappings, skip the functions with
+`generation.skip_functions`, or turn strict mode off.",
+    ),
+    (
+        "AT0104",
+        "Operation timed out",
+        "An operation exceeded `generation.timeout_seconds`.
+
+Raise the timeout, or narrow the run with `filesystem.skip_patterns`,
+`--top N` or `--coverage-report`.",
+    ),
+    (
+        "AT0105",
+        "Generation problems rejected by --fail-on",
+        "Tests were written, but some functions failed to generate, use unsupported
+types, or produced warnings, and the `--fail-on` policy turns that into a
+failing exit status (1).
+
+`target/autotest-report.json` lists every function with its status and error
+code. Use `--fail-on none` to only fail on hard errors.",
+    ),
+    (
+        "AT0106",
+        "Staged functions have no tests",
+        "`autotest generate --staged` (run by the hook from `autotest hook install`) found
+public functions in staged files that no test calls, wrote tests for them, and
+stopped the commit so they can be reviewed.
+
+Review the new tests, `git add` them and commit again. To only warn, set
+`generation.staged_warn_only = true` or pass `--warn-only`.",
+    ),
+    (
+        "AT0107",
+        "Generator plugin failed",
+        "A WASM generator plugin from `plugins/` or `plugins.paths` could not be loaded:
+the file is missing, is not a valid module, does not export `memory`, `alloc` and
+`generate`, or autotest was built without the `plugins` feature.
+
+A plugin that loads but fails while generating one test only produces a warning,
+and the built-in generator writes that test instead.",
+    ),
+    (
+        "AT0108",
+        "Post-generation hook failed",
+        "A command from `generation.post_hooks` or `generation.post_run_hooks` exited with
+a non-zero status, could not be started, or ran longer than
+`generation.hook_timeout_secs` (default 60) and was killed.
+
+The tests were written anyway. Every hook run, with its stderr, is listed under
+`hooks` in `target/autotest-report.json`.",
+    ),
+    (
+        "AT0201",
+        "Invalid configuration",
+        "`auto_test.toml`/`auto_test.yaml` could not be loaded: a syntax error, a value of
+the wrong type, an unknown profile, or an `extends` problem.
+
+`autotest config validate` lists unknown keys and type mismatches with their
+locations, and `autotest config migrate` upgrades legacy flat configs.",
+    ),
+    (
+        "AT0202",
+        "Configuration source error",
+        "A layered configuration source (global config or `AUTO_TEST_*` environment
+variables) could not be read or merged.
+
+Check the environment variables and the file named in the message.",
+    ),
+    (
+        "AT0203",
+        "YAML error",
+        "A YAML document could not be parsed or written. The message includes the line
+and column; `autotest config validate` reports the same location.",
+    ),
+    (
+        "AT0301",
+        "File could not be read",
+        "A file could not be read: it may have been removed during the run, or the
+process lacks permission. The message names the file and the OS error.",
+    ),
+    (
+        "AT0302",
+        "File could not be written",
+        "A generated test file could not be written. Check that the output directory
+(`generation.output_dir`) is writable and not on a read-only filesystem.",
+    ),
+    (
+        "AT0303",
+        "I/O error",
+        "An operating system I/O error occurred; the message contains the OS error.",
+    ),
+    (
+        "AT0304",
+        "Another instance is running",
+        "`autotest generate` holds an advisory lock on `.auto_test/lock` in the project
+root while it writes, so two runs can't interleave their writes. The lock is
+released when the other run exits, even if it crashes.
+
+Wait for the other run to finish. If the lock is held by something that can't
+be stopped (or the filesystem doesn't support locks), pass `--no-lock`.",
+    ),
+    (
+        "AT0305",
+        "Some files could not be written",
+        "Generated files are written in parallel, and every file is attempted even when
+others fail; the message lists each file that failed with its OS error.
+
+Each file is written through a temporary file in its directory and renamed into
+place, so the failed files were left as they were. Check the permissions and
+free space of the output directories.",
+    ),
+    (
+        "AT0401",
+        "Coverage report could not be read",
+        "`--coverage-report` must point to an lcov file (`cargo llvm-cov --lcov`) or an
+llvm-cov JSON export (`cargo llvm-cov --json`).",
+    ),
+    (
+        "AT0402",
+        "cargo-mutants results could not be read",
+        "`--mutants-report` must point to `mutants.out/outcomes.json` or
+`mutants.out/missed.txt` as written by `cargo mutants`.",
+    ),
+    (
+        "AT0501",
+        "Generated tests failed",
+        "`autotest run` generated tests and at least one of them failed, or a test file
+did not compile. Each failing test is listed above the summary, and build
+errors are printed with the target's compiler output.
+
+A failing generated test usually means a placeholder value or a guessed
+assertion doesn't fit the function: add a `[types.mappings]` entry or a
+`[generation.custom_assertions]` entry, or skip the function. Failures can also
+be real bugs in the function under test.",
+    ),
+];
+
+/// Extended explanation for a diagnostic code (case-insensitive).
+///
+/// # Returns
+///
+/// The code's title and explanation, or `None` for unknown codes
+pub fn explain(code: &str) -> Option<(&'static str, &'static str)> {
+    ERROR_CODES
+        .iter()
+        .find(|(c, _, _)| c.eq_ignore_ascii_case(code.trim()))
+        .map(|(_, title, text)| (*title, *text))
+}
+
 /// Result type that uses AutoTestError as the error variant.
 pub type Result<T> = std::result::Result<T, AutoTestError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_error_code_is_explained() {
+        let errors = [
+            AutoTestError::ParseFailed {
+                path: PathBuf::from("a.rs"),
+                source: syn::Error::new(proc_macro2::Span::call_site(), "x"),
+            },
+            AutoTestError::SyntaxError {
+                path: PathBuf::from("a.rs"),
+                line: 1,
+                message: String::new(),
+            },
+            AutoTestError::Manifest {
+                path: PathBuf::new(),
+                message: String::new(),
+            },
+            AutoTestError::ProjectRootNotFound { path: PathBuf::new() },
+            AutoTestError::AnalysisData { message: String::new() },
+            AutoTestError::ExpandFailed { message: String::new() },
+            AutoTestError::UnsupportedType { type_name: String::new() },
+            AutoTestError::StrictUnsupported { count: 0, types: String::new() },
+            AutoTestError::Timeout,
+            AutoTestError::FailOn {
+                count: 0,
+                problem: String::new(),
+                policy: String::new(),
+            },
+            AutoTestError::MissingTests {
+                count: 0,
+                files: String::new(),
+            },
+            AutoTestError::Plugin {
+                path: PathBuf::new(),
+                message: String::new(),
+            },
+            AutoTestError::HookFailed {
+                count: 0,
+                commands: String::new(),
+            },
+            AutoTestError::InvalidConfig { message: String::new() },
+            AutoTestError::Config {
+                source: config::ConfigError::Message(String::new()),
+            },
+            AutoTestError::Yaml {
+                source: serde_yaml::from_str::<u8>("x").unwrap_err(),
+            },
+            AutoTestError::FileRead {
+                path: PathBuf::new(),
+                source: std::io::ErrorKind::NotFound.into(),
+            },
+            AutoTestError::FileWrite {
+                path: PathBuf::new(),
+                source: std::io::ErrorKind::NotFound.into(),
+            },
+            AutoTestError::Io { source: std::io::ErrorKind::NotFound.into() },
+            AutoTestError::AlreadyRunning { lock: PathBuf::new() },
+            AutoTestError::FilesNotWritten {
+                count: 0,
+                failures: String::new(),
+            },
+            AutoTestError::Coverage {
+                path: PathBuf::new(),
+                message: String::new(),
+            },
+            AutoTestError::Mutants {
+                path: PathBuf::new(),
+                message: String::new(),
+            },
+            AutoTestError::VerificationFailed { message: String::new() },
+        ];
+
+        let mut codes: Vec<&str> = errors.iter().map(AutoTestError::code).collect();
+        for code in &codes {
+            assert!(explain(code).is_some(), "{} has no explanation", code);
+        }
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert_eq!(codes.len(), ERROR_CODES.len());
+        assert_eq!(explain("at0102").unwrap().0, "Type can't be synthesized");
+    }
+}
@@ -0,0 +1,126 @@
+//! # C FFI
+//!
+//! `extern "C"` entry points (feature `ffi`) for IDE plugins and tools written in
+//! other languages that call the generator in-process. The declarations are in
+//! `include/autotest.h`; build the shared library with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`.
+//!
+//! Strings cross the boundary as NUL-terminated UTF-8. Strings returned by this
+//! module are owned by the caller and must be released with
+//! [`autotest_string_free`].
+
+use crate::config::Config;
+use crate::error::AutoTestError;
+use crate::AutoTest;
+use std::ffi::{c_char, CStr, CString};
+
+/// Generate tests for a project and return the generation report as JSON.
+///
+/// `config_json` uses the layout of `auto_test.toml` as a JSON object; when it
+/// is null, the project's own config file (or the defaults) is used. On failure
+/// the result is `{"error": {"code": "AT0201", "message": "..."}}` instead of a
+/// report, with `code` null for errors without a diagnostic code.
+///
+/// # Safety
+///
+/// `project_path` must point to a NUL-terminated string, and `config_json` must
+/// be null or point to one. Both only need to live for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn autotest_generate(
+    project_path: *const c_char,
+    config_json: *const c_char,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(|| {
+        if project_path.is_null() {
+            return Err("project_path is null".into());
+        }
+        // SAFETY: the caller guarantees NUL-terminated strings, see above
+        let project_path = unsafe { CStr::from_ptr(project_path) }.to_str()?;
+        let config_json = if config_json.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(config_json) }.to_str()?)
+        };
+        generate(project_path, config_json)
+    });
+
+    let json = match result {
+        Ok(Ok(report)) => report,
+        Ok(Err(error)) => error_json(error.as_ref()),
+        Err(_) => serde_json::json!({
+            "error": { "code": null, "message": "autotest panicked" }
+        })
+        .to_string(),
+    };
+    // serde_json escapes control characters, so the JSON has no interior NUL
+    CString::new(json).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a string returned by this library.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by [`autotest_generate`] that hasn't
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn autotest_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the pointer came from CString::into_raw, see above
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+fn generate(
+    project_path: &str,
+    config_json: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut builder = AutoTest::builder().project(project_path);
+    if let Some(json) = config_json {
+        builder = builder.config(Config::from_json(json)?);
+    }
+    let run = builder.run()?;
+    Ok(serde_json::to_string(&run.report)?)
+}
+
+fn error_json(error: &(dyn std::error::Error + 'static)) -> String {
+    let code = error
+        .downcast_ref::<AutoTestError>()
+        .map(AutoTestError::code);
+    serde_json::json!({
+        "error": { "code": code, "message": error.to_string() }
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::create_test_project;
+
+    fn call(project: &str, config: Option<&str>) -> serde_json::Value {
+        let project = CString::new(project).unwrap();
+        let config = config.map(|c| CString::new(c).unwrap());
+        unsafe {
+            let out = autotest_generate(
+                project.as_ptr(),
+                config.as_ref().map_or(std::ptr::null(), |c| c.as_ptr()),
+            );
+            let json = CStr::from_ptr(out).to_str().unwrap().to_string();
+            autotest_string_free(out);
+            serde_json::from_str(&json).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_generate_returns_report_or_error_json() {
+        let dir = create_test_project();
+        let project = dir.path().to_str().unwrap();
+
+        let report = call(project, Some(r#"{"generation": {"report_path": ""}}"#));
+        assert_eq!(report["summary"]["generated"], 1);
+        assert_eq!(report["files"][0], "tests/integration_tests.rs");
+
+        let error = call(project, Some(r#"{"generation": {"strict": "yes"}}"#));
+        assert_eq!(error["error"]["code"], "AT0201");
+    }
+}
@@ -21,10 +21,12 @@
 //!
 //! The library is organized into several modules:
 //!
+//! - `builder`: The [`AutoTest`] builder, the main library entry point
 //! - `core`: Core analysis and generation logic (see [`core`] module)
 //! - `utils`: Utility functions for file I/O and filesystem operations
 //! - `config`: Configuration management (see [`config`] module)
 //! - `error`: Error types and handling (see [`error`] module)
+//! - `playground`: String-in, string-out generation for the wasm32 web playground
 //!
 //! ## Features
 //!
@@ -47,12 +49,21 @@
 //!
 //! See the [README](https://github.com/yourusername/auto_test) for more information.
 
+#[cfg(feature = "fs")]
+pub mod builder;
+#[cfg(feature = "fs")]
 pub mod cli;
 pub mod config;
 pub mod core;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod playground;
 pub mod utils;
 
+#[cfg(feature = "fs")]
+pub use builder::{AutoTest, Strategy};
+
 /// Generate test files for a Rust project with default configuration.
 ///
 /// This is the main entry point for generating integration tests.
@@ -65,10 +76,9 @@ pub mod utils;
 /// generate_tests_for_project("./my_rust_project")?;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
+#[cfg(feature = "fs")]
 pub fn generate_tests_for_project(project_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let project_path = std::path::Path::new(project_path);
-    let config = config::Config::load(project_path)?;
-    generate_tests_for_project_with_config(project_path, &config)
+    AutoTest::builder().project(project_path).run().map(|_| ())
 }
 
 /// Generate test files for a Rust project with custom configuration.
@@ -94,60 +104,141 @@ pub fn generate_tests_for_project(project_path: &str) -> Result<(), Box<dyn std:
 /// generate_tests_for_project_with_config(project_path, &config)?;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
+#[cfg(feature = "fs")]
 pub fn generate_tests_for_project_with_config(
     project_path: &std::path::Path,
     config: &config::Config,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let test_files =
-        core::generator::rust_gen::RustGenerator::generate_with_config(project_path, config)?;
-
-    for test_file in &test_files {
-        eprintln!("Writing test file: {}", test_file.path);
-        utils::fs::FsUtils::write_test_file_atomic(test_file)?;
-    }
+    AutoTest::builder()
+        .project(project_path)
+        .config(config.clone())
+        .run()
+        .map(|_| ())
+}
 
-    // V Language Support
-    use std::fs;
-    use walkdir::WalkDir;
+/// Generate the test files for a Rust project without writing them.
+///
+/// The project's sources are read, but nothing is written: no test files, report
+/// or nextest profile. Pass the files to [`write_test_files`] with a
+/// [`utils::fs::VirtualFs`] to inspect them in memory, or [`utils::fs::DiskFs`]
+/// to write them.
+///
+/// # Arguments
+///
+/// * `project_path` - Path to the project root directory
+/// * `config` - Configuration for test generation behavior
+///
+/// # Returns
+///
+/// The test files with their target paths, or an error if generation fails
+///
+/// # Example
+/// ```no_run
+/// use auto_test::{config::Config, generate_test_files, write_test_files};
+/// use auto_test::utils::fs::VirtualFs;
+/// use std::path::Path;
+///
+/// let files = generate_test_files(Path::new("./my_project"), &Config::default())?;
+/// let mut fs = VirtualFs::new();
+/// write_test_files(&files, &mut fs)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "fs")]
+pub fn generate_test_files(
+    project_path: &std::path::Path,
+    config: &config::Config,
+) -> Result<Vec<core::models::TestFile>, Box<dyn std::error::Error>> {
+    AutoTest::builder()
+        .project(project_path)
+        .config(config.clone())
+        .dry_run(true)
+        .run()
+        .map(|run| run.files)
+}
 
-    for entry in WalkDir::new(project_path)
+/// Render the test files generated for a project as one deterministic text, for
+/// golden-file tests and for reviewing changes to the generator as diffs.
+///
+/// Generation runs sequentially and writes nothing. Files are sorted by their
+/// path relative to the project, and each follows a `// ==== <path> ====` header.
+/// The text holds no timestamps or absolute paths, so it only changes when the
+/// generated tests do.
+///
+/// # Arguments
+///
+/// * `project_path` - Path to the project root directory
+/// * `config` - Configuration for test generation behavior
+///
+/// # Returns
+///
+/// The rendered files, or an error if generation fails
+#[cfg(feature = "fs")]
+pub fn render_snapshot(
+    project_path: &std::path::Path,
+    config: &config::Config,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut config = config.clone();
+    config.parallel = false;
+    let mut files: Vec<(String, String)> = generate_test_files(project_path, &config)?
         .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("v") {
-            // Skip test files
-            if path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .map(|s| s.ends_with("_test.v"))
-                .unwrap_or(false)
-            {
-                continue;
-            }
-
-            let content = fs::read_to_string(path)?;
-            let functions = core::generator::v_gen::VParser::parse_function_signatures(&content);
-
-            if !functions.is_empty() {
-                let mut test_content = String::from("module main\n\n");
-                for func in functions {
-                    test_content.push_str(&core::generator::v_gen::VParser::generate_test(&func));
-                    test_content.push('\n');
-                }
-
-                let file_stem = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown");
-                let test_file_name = format!("{}_test.v", file_stem);
-                let test_file_path = path.parent().unwrap().join(&test_file_name);
+        .map(|file| {
+            let path = std::path::Path::new(&file.path)
+                .strip_prefix(project_path)
+                .map_or_else(|_| file.path.clone(), |p| p.to_string_lossy().replace('\\', "/"));
+            (path, file.content)
+        })
+        .collect();
+    files.sort();
 
-                eprintln!("Writing V test file: {:?}", test_file_path);
-                fs::write(test_file_path, test_content)?;
-            }
+    let mut snapshot = String::new();
+    for (path, content) in files {
+        snapshot.push_str(&format!("// ==== {} ====\n{}", path, content));
+        if !snapshot.ends_with('\n') {
+            snapshot.push('\n');
         }
     }
+    Ok(snapshot)
+}
 
+/// Write generated test files to a sink.
+///
+/// # Arguments
+///
+/// * `files` - Test files, e.g. from [`generate_test_files`]
+/// * `sink` - Where to write them
+///
+/// # Returns
+///
+/// Success, or the first error of the sink
+pub fn write_test_files(
+    files: &[core::models::TestFile],
+    sink: &mut dyn utils::fs::TestSink,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for file in files {
+        sink.write(file)?;
+    }
     Ok(())
 }
+
+/// Generate test files like [`generate_tests_for_project_with_config`] and
+/// return the per-function outcome of the run.
+///
+/// # Arguments
+///
+/// * `project_path` - Path to the project root directory
+/// * `config` - Configuration for test generation behavior
+///
+/// # Returns
+///
+/// The generation report, or an error if generation fails
+#[cfg(feature = "fs")]
+pub fn generate_tests_for_project_with_report(
+    project_path: &std::path::Path,
+    config: &config::Config,
+) -> Result<core::report::GenerationReport, Box<dyn std::error::Error>> {
+    AutoTest::builder()
+        .project(project_path)
+        .config(config.clone())
+        .run()
+        .map(|run| run.report)
+}
@@ -1,3 +1,13 @@
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    auto_test::cli::run()
+fn main() -> std::process::ExitCode {
+    match auto_test::cli::run() {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(error) => {
+            // Help, version and usage errors keep clap's own output and exit status
+            if let Some(clap_error) = error.downcast_ref::<clap::Error>() {
+                clap_error.exit();
+            }
+            auto_test::cli::report_error(error.as_ref());
+            std::process::ExitCode::from(auto_test::cli::exit_code(error.as_ref()))
+        }
+    }
 }
@@ -0,0 +1,128 @@
+//! # Web Playground
+//!
+//! String-in, string-out generation for the browser playground: analyze a Rust
+//! source string and render its tests, without touching the filesystem. Build it
+//! for the browser without the default features:
+//!
+//! ```text
+//! cargo rustc --release --lib --no-default-features --target wasm32-unknown-unknown --crate-type cdylib
+//! ```
+//!
+//! On wasm32 the module exports the same calling convention as generator
+//! plugins, so the page needs no bindings generator:
+//!
+//! - `autotest_alloc(len: i32) -> i32`: a buffer of `len` bytes for the request
+//! - `autotest_generate_source(ptr: i32, len: i32) -> i64`: handles the JSON
+//!   request at `ptr..ptr + len` and returns the JSON response, packed as
+//!   `(out_ptr << 32) | out_len`
+//! - `autotest_dealloc(ptr: i32, len: i32)`: releases a request or response
+//!
+//! The request is `{"source": "...", "config": {...}}`, where `config` is
+//! optional and uses the layout of `auto_test.toml`. The response is
+//! `{"tests": "..."}`, or `{"error": {"code": "AT0001", "message": "..."}}`.
+
+use crate::config::Config;
+use crate::core::generator::rust_gen::RustGenerator;
+use crate::error::{AutoTestError, Result};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Request {
+    source: String,
+    #[serde(default)]
+    config: Option<serde_json::Value>,
+}
+
+/// Generate the tests for a source string.
+///
+/// # Arguments
+///
+/// * `source` - Rust source of a single file
+/// * `config` - Configuration for generation behavior
+///
+/// # Returns
+///
+/// The contents of the test file, or a parse error
+pub fn generate_from_source(source: &str, config: &Config) -> Result<String> {
+    RustGenerator::generate_for_source(source, config)
+}
+
+/// Handle a JSON playground request, see the module docs for the format.
+pub fn respond(request: &str) -> String {
+    let response = serde_json::from_str::<Request>(request)
+        .map_err(|e| AutoTestError::InvalidConfig {
+            message: format!("invalid playground request: {}", e),
+        })
+        .and_then(|request| {
+            let config = match request.config {
+                Some(config) => Config::from_json(&config.to_string())?,
+                None => Config::default(),
+            };
+            generate_from_source(&request.source, &config)
+        });
+
+    match response {
+        Ok(tests) => serde_json::json!({ "tests": tests }),
+        Err(error) => serde_json::json!({
+            "error": { "code": error.code(), "message": error.to_string() }
+        }),
+    }
+    .to_string()
+}
+
+#[cfg(target_arch = "wasm32")]
+mod exports {
+    /// Allocate a buffer of `len` bytes for a request.
+    #[no_mangle]
+    pub extern "C" fn autotest_alloc(len: usize) -> *mut u8 {
+        let mut buffer = Vec::<u8>::with_capacity(len);
+        let ptr = buffer.as_mut_ptr();
+        std::mem::forget(buffer);
+        ptr
+    }
+
+    /// Release a buffer from [`autotest_alloc`] or a response.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must describe a buffer returned by this module that
+    /// hasn't been released yet.
+    #[no_mangle]
+    pub unsafe extern "C" fn autotest_dealloc(ptr: *mut u8, len: usize) {
+        // SAFETY: the buffer was allocated as a Vec<u8> with capacity `len`
+        drop(unsafe { Vec::from_raw_parts(ptr, 0, len) });
+    }
+
+    /// Handle the JSON request at `ptr..ptr + len`, see the module docs.
+    ///
+    /// # Safety
+    ///
+    /// `ptr..ptr + len` must be initialized memory, e.g. from [`autotest_alloc`].
+    #[no_mangle]
+    pub unsafe extern "C" fn autotest_generate_source(ptr: *const u8, len: usize) -> u64 {
+        // SAFETY: guaranteed by the caller, see above
+        let request = unsafe { std::slice::from_raw_parts(ptr, len) };
+        let response = super::respond(&String::from_utf8_lossy(request)).into_bytes();
+        let response = Box::into_raw(response.into_boxed_slice());
+        ((response as *mut u8 as u64) << 32) | response.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respond_with_tests_or_error() {
+        let response: serde_json::Value = serde_json::from_str(&respond(
+            r#"{"source": "pub fn add(a: i32, b: i32) -> i32 { a + b }", "config": {"generation": {"strategy": "property"}}}"#,
+        ))
+        .unwrap();
+        let tests = response["tests"].as_str().unwrap();
+        assert!(tests.contains("fn prop_add_deterministic("));
+
+        let response: serde_json::Value =
+            serde_json::from_str(&respond(r#"{"source": "pub fn broken("}"#)).unwrap();
+        assert_eq!(response["error"]["code"], "AT0001");
+    }
+}
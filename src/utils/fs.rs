@@ -6,20 +6,30 @@
 //! test generation, including atomic writes for data integrity and batch operations
 //! for performance.
 
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "fs")]
 use tempfile::NamedTempFile;
+#[cfg(feature = "fs")]
 use std::io::Write;
+#[cfg(feature = "fs")]
+use rayon::prelude::*;
 use crate::core::models::TestFile;
-use crate::error::{AutoTestError, Result};
+#[cfg(feature = "fs")]
+use crate::error::AutoTestError;
+use crate::error::Result;
 
 /// Filesystem utility functions for safe file operations.
 ///
 /// This struct provides methods for writing test files with various safety
 /// and performance optimizations, including atomic operations to prevent
 /// data corruption during concurrent writes or system interruptions.
+#[cfg(feature = "fs")]
 pub struct FsUtils;
 
+#[cfg(feature = "fs")]
 impl FsUtils {
     /// Write a single test file to disk.
     ///
@@ -90,6 +100,20 @@ impl FsUtils {
         Ok(())
     }
 
+    /// Write a test file atomically unless the file on disk already has its
+    /// content, leaving up-to-date files (and their modification times) alone.
+    ///
+    /// # Returns
+    ///
+    /// Whether the file was written.
+    pub fn write_test_file_if_changed(test: &TestFile) -> Result<bool> {
+        if fs::read(&test.path).is_ok_and(|existing| existing == test.content.as_bytes()) {
+            return Ok(false);
+        }
+        Self::write_test_file_atomic(test)?;
+        Ok(true)
+    }
+
     /// Write multiple test files to disk sequentially.
     ///
     /// This method writes each file individually without atomic operations.
@@ -109,11 +133,12 @@ impl FsUtils {
         Ok(())
     }
 
-    /// Write multiple test files atomically for optimal concurrent safety.
+    /// Write multiple test files atomically, in parallel.
     ///
-    /// Each file is written atomically using temporary files, ensuring that
-    /// the entire batch operation is either completely successful or can be
-    /// safely rolled back. This is recommended for production use.
+    /// Each file is written atomically using a temporary file, so a failed write
+    /// never leaves a partial file behind. Every file is attempted even when some
+    /// fail, which matters on network filesystems where thousands of sequential
+    /// writes dominate the run. This is recommended for production use.
     ///
     /// # Arguments
     ///
@@ -121,11 +146,226 @@ impl FsUtils {
     ///
     /// # Returns
     ///
-    /// Returns `Result<()>` indicating success or failure with detailed error information.
+    /// Returns `Result<()>`, with one [`AutoTestError::FilesNotWritten`] naming
+    /// every file that failed.
     pub fn write_many_atomic(files: &[TestFile]) -> Result<()> {
-        for file in files {
-            Self::write_test_file_atomic(file)?;
+        let results = files.par_iter().map(Self::write_test_file_atomic).collect();
+        Self::all_written(files, results).map(|_| ())
+    }
+
+    /// The outcomes of writing each of `files`, or one error naming every file
+    /// whose write failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The files written
+    /// * `results` - The outcome for each file, in the same order
+    pub fn all_written<T>(files: &[TestFile], results: Vec<Result<T>>) -> Result<Vec<T>> {
+        let mut written = Vec::with_capacity(results.len());
+        let mut failures = Vec::new();
+        for (file, result) in files.iter().zip(results) {
+            match result {
+                Ok(value) => written.push(value),
+                Err(e) => failures.push(format!("{}: {}", file.path, e)),
+            }
+        }
+        if failures.is_empty() {
+            Ok(written)
+        } else {
+            Err(AutoTestError::FilesNotWritten {
+                count: failures.len(),
+                failures: failures.join("; "),
+            })
+        }
+    }
+
+    /// Copy a project tree, leaving out build output and version control.
+    ///
+    /// Used to generate and run tests in a throwaway copy of a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Project root to copy
+    /// * `to` - Destination directory, created if missing
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<()>` indicating success or failure with detailed error information.
+    pub fn copy_project(from: &Path, to: &Path) -> Result<()> {
+        let entries = walkdir::WalkDir::new(from)
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0 || !matches!(e.file_name().to_str(), Some("target" | ".git"))
+            });
+
+        for entry in entries {
+            let entry = entry.map_err(|e| AutoTestError::Io { source: e.into() })?;
+            let relative = entry.path().strip_prefix(from).unwrap_or(entry.path());
+            let dest = to.join(relative);
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&dest).map_err(|e| AutoTestError::FileWrite {
+                    path: dest.clone(),
+                    source: e,
+                })?;
+            } else if entry.file_type().is_file() {
+                fs::copy(entry.path(), &dest).map_err(|e| AutoTestError::FileWrite {
+                    path: dest.clone(),
+                    source: e,
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Advisory lock on `.auto_test/lock` in a project root, held while a run
+/// writes to the project so concurrent runs don't interleave their writes.
+///
+/// The lock is released when the value is dropped or the process exits. The
+/// lock file itself is left in place.
+#[cfg(feature = "fs")]
+#[derive(Debug)]
+pub struct RunLock {
+    _file: fs::File,
+}
+
+#[cfg(feature = "fs")]
+impl RunLock {
+    /// Path of the lock file, relative to the project root.
+    pub const PATH: &'static str = ".auto_test/lock";
+
+    /// Take the project's lock without waiting.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_root` - Root of the project the run writes to
+    ///
+    /// # Returns
+    ///
+    /// The held lock, or [`AutoTestError::AlreadyRunning`] if another process
+    /// holds it
+    pub fn acquire(project_root: &Path) -> Result<Self> {
+        let path = project_root.join(Self::PATH);
+        let write_error = |source| AutoTestError::FileWrite {
+            path: path.clone(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(write_error)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Self { _file: file }),
+            Err(fs::TryLockError::WouldBlock) => Err(AutoTestError::AlreadyRunning { lock: path }),
+            Err(fs::TryLockError::Error(e)) => Err(write_error(e)),
         }
+    }
+}
+
+/// Destination for generated test files.
+///
+/// [`DiskFs`] writes them to disk, [`VirtualFs`] keeps them in memory so web
+/// services and tests can generate without temporary directories.
+pub trait TestSink {
+    /// Store a test file, replacing any previous contents at its path.
+    fn write(&mut self, file: &TestFile) -> Result<()>;
+}
+
+/// Sink writing test files to disk atomically, creating parent directories.
+#[cfg(feature = "fs")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DiskFs;
+
+#[cfg(feature = "fs")]
+impl TestSink for DiskFs {
+    fn write(&mut self, file: &TestFile) -> Result<()> {
+        FsUtils::write_test_file_atomic(file)
+    }
+}
+
+/// In-memory sink holding the contents of every written test file by path.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VirtualFs {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl VirtualFs {
+    /// An empty virtual filesystem.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Contents of the file written at `path`, if any.
+    pub fn read(&self, path: impl AsRef<Path>) -> Option<&str> {
+        self.files.get(path.as_ref()).map(String::as_str)
+    }
+
+    /// Paths of all written files, in sorted order.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Number of files written.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether no file was written.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The written files, sorted by path.
+    pub fn into_files(self) -> Vec<TestFile> {
+        self.files
+            .into_iter()
+            .map(|(path, content)| TestFile {
+                path: path.to_string_lossy().to_string(),
+                content,
+            })
+            .collect()
+    }
+}
+
+impl TestSink for VirtualFs {
+    fn write(&mut self, file: &TestFile) -> Result<()> {
+        self.files
+            .insert(PathBuf::from(&file.path), file.content.clone());
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_many_atomic_attempts_every_file_and_reports_all_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        // A file where a directory is expected makes writes beneath it fail
+        fs::write(dir.path().join("blocked"), "").unwrap();
+        let file = |path: String| TestFile {
+            content: format!("// {}", path),
+            path: dir.path().join(path).to_string_lossy().to_string(),
+        };
+        let mut files: Vec<TestFile> = (0..200).map(|i| file(format!("tests/t{}.rs", i))).collect();
+        files.insert(10, file("blocked/a.rs".to_string()));
+        files.push(file("blocked/b.rs".to_string()));
+
+        let err = FsUtils::write_many_atomic(&files).unwrap_err();
+        assert_eq!(err.code(), "AT0305");
+        let message = err.to_string();
+        assert!(message.starts_with("Failed to write 2 file(s): "), "{}", message);
+        assert!(message.contains("blocked/a.rs: ") && message.contains("blocked/b.rs: "));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("tests/t199.rs")).unwrap(),
+            "// tests/t199.rs"
+        );
+        assert_eq!(fs::read_dir(dir.path().join("tests")).unwrap().count(), 200);
+    }
+}
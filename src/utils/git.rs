@@ -0,0 +1,60 @@
+//! # Git Utilities
+//!
+//! Thin wrappers around the `git` command line for hook installation and
+//! staged-files mode.
+
+use crate::error::{AutoTestError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Run git in `dir` and return its trimmed stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| AutoTestError::Io { source: e })?;
+    if !output.status.success() {
+        return Err(AutoTestError::Io {
+            source: std::io::Error::other(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Root of the working tree containing `dir`.
+pub fn repo_root(dir: &Path) -> Result<PathBuf> {
+    git(dir, &["rev-parse", "--show-toplevel"]).map(PathBuf::from)
+}
+
+/// Directory git runs hooks from, honoring `core.hooksPath` and worktrees.
+pub fn hooks_dir(dir: &Path) -> Result<PathBuf> {
+    let path = PathBuf::from(git(dir, &["rev-parse", "--git-path", "hooks"])?);
+    Ok(if path.is_absolute() { path } else { dir.join(path) })
+}
+
+/// Added, copied, modified or renamed files in the index, as absolute paths.
+pub fn staged_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let root = repo_root(dir)?;
+    let names = git(
+        dir,
+        &["diff", "--cached", "--name-only", "--diff-filter=ACMR", "-z"],
+    )?;
+    Ok(names
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .map(|name| root.join(name))
+        .collect())
+}
+
+/// Contents of a file as staged in the index, which may differ from the working tree.
+pub fn staged_contents(file: &Path) -> Result<String> {
+    let dir = file.parent().unwrap_or(Path::new("."));
+    let root = repo_root(dir)?;
+    let relative = file.strip_prefix(&root).unwrap_or(file);
+    git(&root, &["show", &format!(":{}", relative.to_string_lossy())])
+}
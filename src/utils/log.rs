@@ -0,0 +1,29 @@
+//! # Status Output
+//!
+//! Progress notes and warnings printed to stderr while a run goes on. Quiet
+//! mode (`generate --quiet`) silences them, so stderr only carries genuine
+//! errors and wrappers can rely on what's printed to stdout.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Turn quiet mode on or off for the rest of the process.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether status output is silenced.
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `eprintln!` for status output: notes, warnings and progress, but not errors.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::utils::log::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use status;
@@ -1 +1,8 @@
 pub mod fs;
+pub mod git;
+pub mod log;
+pub(crate) mod parallel;
+pub mod patch;
+pub(crate) mod progress;
+#[cfg(all(test, feature = "fs"))]
+pub(crate) mod test_support;
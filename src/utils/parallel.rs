@@ -0,0 +1,27 @@
+//! # Parallelism
+//!
+//! Generation and writing run on a rayon pool sized by `performance.threads`
+//! (or `--jobs`). Modules and the functions within them are split over the
+//! pool down to single functions, and idle threads steal work from busy ones.
+
+use crate::error::{AutoTestError, Result};
+
+/// Run `op` on a pool of `threads` threads; `None` or 0 uses one per CPU.
+pub(crate) fn install<R: Send>(threads: Option<usize>, op: impl FnOnce() -> R + Send) -> Result<R> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
+        .build()
+        .map_err(|e| AutoTestError::Io { source: std::io::Error::other(e) })?;
+    Ok(pool.install(op))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_has_the_configured_threads() {
+        assert_eq!(install(Some(3), rayon::current_num_threads).unwrap(), 3);
+        assert_eq!(install(None, rayon::current_num_threads).unwrap(), rayon::current_num_threads());
+    }
+}
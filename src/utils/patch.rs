@@ -0,0 +1,112 @@
+//! # Patches
+//!
+//! Renders generated test files as a unified diff against the working tree, in
+//! the format `git apply` accepts, so generated tests can be reviewed (or
+//! attached to a pull request by a bot) before they land.
+
+use crate::core::models::TestFile;
+use crate::error::{AutoTestError, Result};
+use std::path::Path;
+
+/// Diff of one file, with `a/` and `b/` prefixes as written by `git diff`.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file relative to the directory the patch is applied in
+/// * `old` - Current contents, or `None` when the file is created
+/// * `new` - Contents after the patch
+///
+/// # Returns
+///
+/// The diff, or an empty string when the contents are unchanged
+pub fn file_diff(path: &str, old: Option<&str>, new: &str) -> String {
+    if old == Some(new) {
+        return String::new();
+    }
+
+    let mut patch = format!("diff --git a/{} b/{}\n", path, path);
+    match old {
+        Some(_) => patch.push_str(&format!("--- a/{}\n+++ b/{}\n", path, path)),
+        None => patch.push_str(&format!(
+            "new file mode 100644\n--- /dev/null\n+++ b/{}\n",
+            path
+        )),
+    }
+    let diff = similar::TextDiff::from_lines(old.unwrap_or_default(), new);
+    for hunk in diff.unified_diff().iter_hunks() {
+        patch.push_str(&hunk.to_string());
+    }
+    patch
+}
+
+/// Patch turning the current contents of `files` into the generated ones.
+///
+/// # Arguments
+///
+/// * `files` - Generated test files
+/// * `base` - Directory the patch is applied in, usually the repository root;
+///   paths in the patch are relative to it
+///
+/// # Returns
+///
+/// The combined patch, empty when every file is already up to date
+pub fn build_patch(files: &[TestFile], base: &Path) -> Result<String> {
+    let base = std::path::absolute(base).map_err(|e| AutoTestError::Io { source: e })?;
+    let mut patch = String::new();
+    for file in files {
+        let path = std::path::absolute(&file.path).map_err(|e| AutoTestError::Io { source: e })?;
+        let old = if path.exists() {
+            Some(
+                std::fs::read_to_string(&path).map_err(|e| AutoTestError::FileRead {
+                    path: path.clone(),
+                    source: e,
+                })?,
+            )
+        } else {
+            None
+        };
+        let relative = path.strip_prefix(&base).unwrap_or(&path);
+        // Patches always use forward slashes
+        let display = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        patch.push_str(&file_diff(&display, old.as_deref(), &file.content));
+    }
+    Ok(patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_creates_and_updates_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("tests")).unwrap();
+        std::fs::write(dir.path().join("tests/a.rs"), "fn a() {}\n").unwrap();
+        let files = vec![
+            TestFile {
+                path: dir.path().join("tests/a.rs").to_string_lossy().to_string(),
+                content: "fn a() {}\nfn b() {}\n".to_string(),
+            },
+            TestFile {
+                path: dir
+                    .path()
+                    .join("tests/new.rs")
+                    .to_string_lossy()
+                    .to_string(),
+                content: "fn c() {}\n".to_string(),
+            },
+        ];
+
+        let patch = build_patch(&files, dir.path()).unwrap();
+        assert_eq!(
+            patch,
+            "diff --git a/tests/a.rs b/tests/a.rs\n--- a/tests/a.rs\n+++ b/tests/a.rs\n@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n\
+             diff --git a/tests/new.rs b/tests/new.rs\nnew file mode 100644\n--- /dev/null\n+++ b/tests/new.rs\n@@ -0,0 +1 @@\n+fn c() {}\n"
+        );
+        assert_eq!(file_diff("x.rs", Some("same\n"), "same\n"), "");
+    }
+}
@@ -0,0 +1,26 @@
+//! # Progress Bars
+//!
+//! Progress bars shared by the phases of a run (generating tests, writing
+//! files), so they look the same. indicatif doesn't draw them when stderr
+//! isn't a terminal or in quiet mode, and colors follow `--no-color` and
+//! `NO_COLOR`.
+
+use crate::utils::log::is_quiet;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// A progress bar over `len` steps, showing the current message after the
+/// counts; hidden in quiet mode.
+pub(crate) fn progress_bar(len: u64) -> ProgressBar {
+    let progress = ProgressBar::new(len);
+    progress.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({eta}) - {msg}",
+        )
+        .unwrap()
+        .progress_chars("#>-"),
+    );
+    if is_quiet() {
+        progress.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    progress
+}
@@ -0,0 +1,24 @@
+//! # Test Support
+//!
+//! Sample projects shared by the unit tests of the CLI, the builder API and
+//! the C API.
+
+use tempfile::TempDir;
+
+/// A library crate in a temporary directory whose `src/lib.rs` holds a single
+/// `pub fn add(a: i32, b: i32) -> i32`.
+pub(crate) fn create_test_project() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"sample\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(
+        dir.path().join("src/lib.rs"),
+        "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )
+    .unwrap();
+    dir
+}
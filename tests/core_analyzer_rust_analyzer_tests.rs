@@ -1,6 +1,7 @@
+#![cfg(feature = "fs")]
+
 #[cfg(test)]
 mod tests {
-    use auto_test::*;
 
     #[test] fn test_analyze_rust_file_integration() {
         // Arrange
@@ -0,0 +1,117 @@
+#![cfg(feature = "fs")]
+
+//! Golden-file tests: the tests generated for each sample crate in `fixtures/`
+//! are compared with the committed `fixtures/<crate>.golden`, so generator
+//! changes show up as reviewable diffs. Run with `UPDATE_GOLDEN=1` to rewrite
+//! the golden files after an intended change. The generated tests must also
+//! pass `cargo check` in a copy of each crate.
+
+use auto_test::config::Config;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn fixture_crates() -> Vec<PathBuf> {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let mut crates: Vec<_> = fs::read_dir(&fixtures)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("Cargo.toml").exists())
+        .collect();
+    crates.sort();
+    assert!(!crates.is_empty(), "no fixture crates in {}", fixtures.display());
+    crates
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap().filter_map(|entry| entry.ok()) {
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_fixtures_match_golden_files() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let crates = fixture_crates();
+
+    let mut mismatches = Vec::new();
+    for project in &crates {
+        let name = project.file_name().unwrap().to_string_lossy();
+        let golden = fixtures.join(format!("{}.golden", name));
+        let snapshot = auto_test::render_snapshot(project, &Config::default())
+            .unwrap_or_else(|e| panic!("generating tests for fixture {} failed: {}", name, e));
+
+        if update {
+            fs::write(&golden, &snapshot).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&golden).unwrap_or_default().replace("\r\n", "\n");
+        if snapshot != expected {
+            let diff = similar::TextDiff::from_lines(&expected, &snapshot);
+            mismatches.push(format!(
+                "{}\n{}",
+                golden.display(),
+                diff.unified_diff().header("golden", "generated")
+            ));
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "generated tests differ from the golden files; rerun with UPDATE_GOLDEN=1 if the change is intended:\n\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_fixture_tests_compile() {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    // Shared between fixtures and runs, so dependencies build once
+    let target_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/golden-check");
+
+    let mut failures = Vec::new();
+    for project in &fixture_crates() {
+        let name = project.file_name().unwrap().to_string_lossy();
+        let dir = tempfile::tempdir().unwrap();
+        let copy = dir.path().join(name.as_ref());
+        copy_dir(project, &copy);
+
+        let config = Config {
+            parallel: false,
+            ..Config::default()
+        };
+        for file in auto_test::generate_test_files(&copy, &config).unwrap() {
+            let path = Path::new(&file.path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, &file.content).unwrap();
+        }
+
+        let output = Command::new(&cargo)
+            .args(["check", "--tests", "--quiet"])
+            .current_dir(&copy)
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .output()
+            .unwrap();
+        if !output.status.success() {
+            failures.push(format!(
+                "{}:\n{}",
+                name,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "generated tests don't compile:\n\n{}",
+        failures.join("\n")
+    );
+}